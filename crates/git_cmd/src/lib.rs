@@ -208,6 +208,22 @@ impl Repo {
         Ok(())
     }
 
+    /// Apply the changes of the given commits on top of the current branch.
+    /// If a commit can't be applied, e.g. because of a conflict, the cherry-pick is aborted
+    /// and the branch is left as it was.
+    #[instrument(skip(self))]
+    pub fn cherry_pick(&self, commits: &[String]) -> anyhow::Result<()> {
+        let mut args = vec!["cherry-pick", "--allow-empty"];
+        args.extend(commits.iter().map(String::as_str));
+        if let Err(e) = self.git(&args) {
+            if let Err(abort_err) = self.git(&["cherry-pick", "--abort"]) {
+                debug!("cannot abort cherry-pick: {abort_err:?}");
+            }
+            return Err(e.context("cannot cherry-pick commits"));
+        }
+        Ok(())
+    }
+
     /// Checkout to the latest commit.
     pub fn checkout_last_commit_at_paths(&self, paths: &[&Path]) -> anyhow::Result<()> {
         let previous_commit = self.last_commit_at_paths(paths)?;
@@ -294,11 +310,69 @@ impl Repo {
             .context("can't determine current commit hash")
     }
 
+    /// Whether `commit` exists in the local repository.
+    pub fn commit_exists(&self, commit: &str) -> bool {
+        self.git(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{commit}^{{commit}}"),
+        ])
+        .is_ok()
+    }
+
+    /// Whether the repository is a shallow clone, i.e. it doesn't contain the whole git history.
+    pub fn is_shallow(&self) -> anyhow::Result<bool> {
+        let output = self
+            .git(&["rev-parse", "--is-shallow-repository"])
+            .context("can't determine if the repository is shallow")?;
+        Ok(output == "true")
+    }
+
+    /// Content of the file at `path` in the given `commit`.
+    /// `path` is relative to the repository directory.
+    /// Return [`Option::None`] if the file doesn't exist in the commit.
+    /// Return an error if the commit doesn't exist.
+    pub fn file_at_commit(&self, commit: &str, path: &Utf8Path) -> anyhow::Result<Option<String>> {
+        anyhow::ensure!(
+            self.commit_exists(commit),
+            "commit {commit} doesn't exist in the local repository"
+        );
+        let object = format!("{commit}:./{path}");
+        if self.git(&["cat-file", "-e", &object]).is_err() {
+            return Ok(None);
+        }
+        let content = self
+            .git(&["show", &object])
+            .with_context(|| format!("can't read {path} at commit {commit}"))?;
+        Ok(Some(content))
+    }
+
     /// Create a git tag
     pub fn tag(&self, name: &str, message: &str) -> anyhow::Result<String> {
         self.git(&["tag", "-m", message, name])
     }
 
+    /// Create a signed git tag.
+    /// The key and the signature format (GPG, SSH or X.509) are read from the
+    /// `user.signingKey` and `gpg.format` git configs.
+    pub fn tag_signed(&self, name: &str, message: &str) -> anyhow::Result<String> {
+        self.git(&["tag", "--sign", "-m", message, name])
+            .with_context(|| format!("can't create signed git tag {name}"))
+    }
+
+    /// Delete a local git tag
+    pub fn delete_tag(&self, name: &str) -> anyhow::Result<()> {
+        self.git(&["tag", "--delete", name])
+            .with_context(|| format!("can't delete git tag {name}"))?;
+        Ok(())
+    }
+
+    pub fn delete_tag_in_remote(&self, name: &str) -> anyhow::Result<()> {
+        self.push(&format!(":refs/tags/{name}"))
+            .with_context(|| format!("can't delete git tag {name} in remote"))
+    }
+
     /// Get the commit hash of the given tag
     pub fn get_tag_commit(&self, tag: &str) -> Option<String> {
         self.git(&["rev-list", "-n", "1", tag]).ok()
@@ -477,6 +551,42 @@ mod tests {
         assert_eq!(repo.current_commit_message().unwrap(), commit_message);
     }
 
+    #[test]
+    fn commits_are_cherry_picked() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        let file = repository_dir.as_ref().join("file.txt");
+        repo.checkout_new_branch("feature").unwrap();
+        fs_err::write(&file, b"feature").unwrap();
+        repo.add_all_and_commit("add file").unwrap();
+        let commit = repo.current_commit_hash().unwrap();
+        repo.checkout_head().unwrap();
+
+        repo.cherry_pick(&[commit]).unwrap();
+        assert_eq!(repo.current_commit_message().unwrap(), "add file");
+        assert_eq!(fs_err::read_to_string(&file).unwrap(), "feature");
+    }
+
+    #[test]
+    fn conflicting_cherry_pick_is_aborted() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        let file = repository_dir.as_ref().join("file.txt");
+        repo.checkout_new_branch("feature").unwrap();
+        fs_err::write(&file, b"feature").unwrap();
+        repo.add_all_and_commit("feature change").unwrap();
+        let commit = repo.current_commit_hash().unwrap();
+        repo.checkout_head().unwrap();
+        fs_err::write(&file, b"main").unwrap();
+        repo.add_all_and_commit("main change").unwrap();
+
+        repo.cherry_pick(&[commit]).unwrap_err();
+        assert_eq!(repo.current_commit_message().unwrap(), "main change");
+        repo.is_clean().unwrap();
+    }
+
     #[test]
     fn clean_project_is_recognized() {
         test_logs::init();
@@ -485,6 +595,63 @@ mod tests {
         repo.is_clean().unwrap();
     }
 
+    #[test]
+    fn tag_is_deleted() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        repo.tag("v1.0.0", "v1.0.0").unwrap();
+        assert!(repo.tag_exists("v1.0.0").unwrap());
+        repo.delete_tag("v1.0.0").unwrap();
+        assert!(!repo.tag_exists("v1.0.0").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tag_is_signed_with_ssh_key() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        let key_dir = tempdir().unwrap();
+        let key = key_dir.path().join("id_ed25519");
+        let key = key.to_str().unwrap();
+        let status = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f", key])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        repo.git(&["config", "gpg.format", "ssh"]).unwrap();
+        repo.git(&["config", "user.signingkey", key]).unwrap();
+
+        repo.tag_signed("v1.0.0", "chore: release v1.0.0").unwrap();
+        let tag = repo.git(&["cat-file", "tag", "v1.0.0"]).unwrap();
+        assert!(tag.contains("-----BEGIN SSH SIGNATURE-----"), "{tag}");
+    }
+
+    #[test]
+    fn file_at_previous_commit_is_read() {
+        test_logs::init();
+        let repository_dir = tempdir().unwrap();
+        let repo = Repo::init(&repository_dir);
+        fs_err::write(repository_dir.as_ref().join("other.txt"), "other").unwrap();
+        repo.add_all_and_commit("add other file").unwrap();
+        let file = repository_dir.as_ref().join("file.txt");
+        fs_err::write(&file, "old").unwrap();
+        repo.add_all_and_commit("add file").unwrap();
+        fs_err::write(&file, "new").unwrap();
+        repo.add_all_and_commit("edit file").unwrap();
+
+        let path = Utf8Path::new("file.txt");
+        assert_eq!(
+            repo.file_at_commit("HEAD^", path).unwrap().as_deref(),
+            Some("old")
+        );
+        // The file doesn't exist in the commit.
+        assert_eq!(repo.file_at_commit("HEAD~2", path).unwrap(), None);
+        // The commit doesn't exist.
+        assert!(repo.file_at_commit("HEAD~10", path).is_err());
+    }
+
     #[test]
     fn dirty_project_is_recognized() {
         test_logs::init();
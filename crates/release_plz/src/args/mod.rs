@@ -7,6 +7,7 @@ mod release_pr;
 pub(crate) mod repo_command;
 mod set_version;
 mod update;
+mod yank;
 
 use std::path::Path;
 
@@ -23,7 +24,7 @@ use crate::config::Config;
 
 use self::{
     generate_completions::GenerateCompletions, release::Release, release_pr::ReleasePr,
-    update::Update,
+    update::Update, yank::Yank,
 };
 
 #[derive(clap::Parser, Debug)]
@@ -72,6 +73,16 @@ pub enum Command {
     /// Note that this command is meant to edit the versions of the packages
     /// of your workspace, not the version of your dependencies.
     SetVersion(SetVersion),
+    /// Roll back a release by yanking versions from the cargo registry.
+    /// Specify a version with the syntax `<package_name>@<version>`.
+    /// E.g. `release-plz yank rand@1.2.3`
+    ///
+    /// You can also yank multiple versions, separated by space,
+    /// or all the packages released with a git tag.
+    /// E.g. `release-plz yank --tag v1.2.3`
+    ///
+    /// Optionally, delete or mark the git tag and turn the git release into a draft or delete it.
+    Yank(Yank),
 }
 
 #[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
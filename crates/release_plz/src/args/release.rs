@@ -4,7 +4,9 @@ use clap::{
     builder::{NonEmptyStringValueParser, PathBufValueParser},
     ValueEnum,
 };
-use release_plz_core::{GitBackend, GitHub, GitLab, Gitea, ReleaseRequest};
+use release_plz_core::{
+    fs_utils::to_utf8_path, Bitbucket, GitBackend, GitHub, GitLab, Gitea, ReleaseRequest, RepoUrl,
+};
 use secrecy::SecretString;
 
 use crate::config::Config;
@@ -35,6 +37,22 @@ pub struct Release {
     /// Perform all checks without uploading.
     #[arg(long)]
     pub dry_run: bool,
+    /// Only resume the releases interrupted in a previous run, e.g. because
+    /// the git tag was pushed but the git release wasn't created.
+    /// Release-plz tracks the completed release steps in the release journal
+    /// (see `--release-journal`).
+    #[arg(long)]
+    pub resume: bool,
+    /// Path of the file where release-plz tracks the completed release steps.
+    /// Default: `release-plz/release-journal.json` in the cargo target directory.
+    /// In CI, the target directory is usually lost when a job fails:
+    /// cache or upload this file to resume interrupted releases in the next job.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    release_journal: Option<PathBuf>,
     /// Don't verify the contents by building them.
     /// When you pass this flag, `release-plz` adds the `--no-verify` flag to `cargo publish`.
     #[arg(long)]
@@ -77,6 +95,32 @@ pub enum ReleaseGitBackendKind {
     Gitea,
     #[value(name = "gitlab")]
     Gitlab,
+    #[value(name = "bitbucket")]
+    Bitbucket,
+}
+
+impl ReleaseGitBackendKind {
+    pub fn git_backend(
+        self,
+        repo_url: RepoUrl,
+        git_token: SecretString,
+    ) -> anyhow::Result<GitBackend> {
+        let backend = match self {
+            ReleaseGitBackendKind::Gitea => GitBackend::Gitea(Gitea::new(repo_url, git_token)?),
+            ReleaseGitBackendKind::Github => {
+                GitBackend::Github(GitHub::new(repo_url.owner, repo_url.name, git_token))
+            }
+            ReleaseGitBackendKind::Gitlab => GitBackend::Gitlab(GitLab::new(repo_url, git_token)?),
+            ReleaseGitBackendKind::Bitbucket => {
+                anyhow::ensure!(
+                    repo_url.is_on_bitbucket_cloud(),
+                    "Can't use the Bitbucket backend: the repository is not hosted in Bitbucket Cloud (bitbucket.org). Bitbucket Data Center isn't supported."
+                );
+                GitBackend::Bitbucket(Bitbucket::new(repo_url.owner, repo_url.name, git_token))
+            }
+        };
+        Ok(backend)
+    }
 }
 
 impl ConfigCommand for Release {
@@ -95,23 +139,23 @@ impl Release {
             let git_token = SecretString::from(git_token.clone());
             let repo_url = self.get_repo_url(config)?;
             let release = release_plz_core::GitRelease {
-                backend: match self.backend {
-                    ReleaseGitBackendKind::Gitea => {
-                        GitBackend::Gitea(Gitea::new(repo_url, git_token)?)
-                    }
-                    ReleaseGitBackendKind::Github => {
-                        GitBackend::Github(GitHub::new(repo_url.owner, repo_url.name, git_token))
-                    }
-                    ReleaseGitBackendKind::Gitlab => {
-                        GitBackend::Gitlab(GitLab::new(repo_url, git_token)?)
-                    }
-                },
+                backend: self.backend.git_backend(repo_url, git_token)?,
             };
             Some(release)
         } else {
             None
         };
-        let mut req = ReleaseRequest::new(metadata).with_dry_run(self.dry_run);
+        let release_branch = config
+            .workspace
+            .current_release_branch(&metadata.workspace_root)?;
+        let mut req = ReleaseRequest::new(metadata)
+            .with_dry_run(self.dry_run)
+            .with_resume(self.resume)
+            .with_maintenance_branch(release_branch.is_some());
+
+        if let Some(release_journal) = &self.release_journal {
+            req = req.with_release_journal_path(to_utf8_path(release_journal)?.to_path_buf());
+        }
 
         if let Some(registry) = self.registry {
             req = req.with_registry(registry);
@@ -128,12 +172,19 @@ impl Release {
         if let Some(release_always) = config.workspace.release_always {
             req = req.with_release_always(release_always);
         }
+        req = req.with_release_trigger(config.workspace.release_trigger()?);
+        if let Some(combined_git_release) = config.workspace.combined_git_release() {
+            req = req.with_combined_git_release(combined_git_release);
+        }
 
         req = req.with_publish_timeout(config.workspace.publish_timeout()?);
+        req = req.with_publish_concurrency(config.workspace.publish_concurrency()?);
+        req = req.with_publish_workspace(config.workspace.publish_workspace == Some(true));
 
         req = config.fill_release_config(self.allow_dirty, self.no_verify, req);
 
         req = req.with_branch_prefix(config.workspace.pr_branch_prefix.clone());
+        req = req.with_notifications(config.notifications());
 
         Ok(req)
     }
@@ -179,6 +230,26 @@ mod tests {
         assert!(actual_request.allow_dirty("aaa"));
     }
 
+    #[test]
+    fn release_journal_path_is_configurable() {
+        let config = Config::default();
+        let default_request = default_args()
+            .release_request(&config, fake_metadata())
+            .unwrap();
+        assert!(default_request
+            .release_journal_path()
+            .ends_with("release-plz/release-journal.json"));
+
+        let release_args = Release {
+            release_journal: Some(PathBuf::from("/tmp/release-journal.json")),
+            ..default_args()
+        };
+        let request = release_args
+            .release_request(&config, fake_metadata())
+            .unwrap();
+        assert_eq!(request.release_journal_path(), "/tmp/release-journal.json");
+    }
+
     #[test]
     fn package_config_is_overriden() {
         let config = r#"
@@ -210,6 +281,8 @@ mod tests {
             registry: None,
             token: None,
             dry_run: false,
+            resume: false,
+            release_journal: None,
             repo_url: None,
             git_token: None,
             backend: ReleaseGitBackendKind::Github,
@@ -9,8 +9,8 @@ use clap::{
 };
 use git_cliff_core::config::Config as GitCliffConfig;
 use release_plz_core::{
-    fs_utils::to_utf8_path, ChangelogRequest, GitBackend, GitHub, GitLab, Gitea, RepoUrl,
-    UpdateRequest,
+    fs_utils::to_utf8_path, Bitbucket, ChangelogRequest, GitBackend, GitHub, GitLab, Gitea,
+    RepoUrl, UpdateRequest,
 };
 use secrecy::SecretString;
 
@@ -115,6 +115,8 @@ pub enum GitBackendKind {
     Gitea,
     #[value(name = "gitlab")]
     Gitlab,
+    #[value(name = "bitbucket")]
+    Bitbucket,
 }
 
 impl RepoCommand for Update {
@@ -151,6 +153,13 @@ impl Update {
             }
             GitBackendKind::Gitea => GitBackend::Gitea(Gitea::new(repo, token)?),
             GitBackendKind::Gitlab => GitBackend::Gitlab(GitLab::new(repo, token)?),
+            GitBackendKind::Bitbucket => {
+                anyhow::ensure!(
+                    repo.is_on_bitbucket_cloud(),
+                    "Can't use the Bitbucket backend: the repository is not hosted in Bitbucket Cloud (bitbucket.org). Bitbucket Data Center isn't supported."
+                );
+                GitBackend::Bitbucket(Bitbucket::new(repo.owner, repo.name, token))
+            }
         }))
     }
 
@@ -212,6 +221,12 @@ impl Update {
         if let Some(registry) = &self.registry {
             update = update.with_registry(registry.clone());
         }
+        if let Some(release_branch) = config
+            .workspace
+            .current_release_branch(update.local_manifest_dir()?)?
+        {
+            update = update.with_registry_version(release_branch.version.clone());
+        }
         if let Some(release_commits) = &config.workspace.release_commits {
             update = update.with_release_commits(release_commits)?;
         }
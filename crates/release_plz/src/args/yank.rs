@@ -0,0 +1,235 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::semver::Version;
+use clap::{
+    builder::{NonEmptyStringValueParser, PathBufValueParser},
+    ValueEnum,
+};
+use release_plz_core::{
+    ReleaseRequest, YankGitReleaseAction, YankRequest, YankSpec, YankTagAction,
+};
+use secrecy::SecretString;
+
+use crate::config::Config;
+
+use super::{
+    config_command::ConfigCommand, manifest_command::ManifestCommand,
+    release::ReleaseGitBackendKind, repo_command::RepoCommand, OutputType,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Yank {
+    /// Version of the package you want to yank. Format: `<package_name>@<version>`.
+    #[arg(required_unless_present = "tag", conflicts_with = "tag")]
+    versions: Vec<String>,
+    /// Yank all the packages released with this git tag.
+    /// E.g. `release-plz yank --tag v1.2.3`
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    tag: Option<String>,
+    /// What to do with the git tag of the yanked version.
+    #[arg(long, value_enum, default_value_t = GitTagAction::Keep)]
+    git_tag_action: GitTagAction,
+    /// What to do with the GitHub/Gitea/GitLab release of the yanked version.
+    /// Requires `--git-token`.
+    #[arg(long, value_enum, default_value_t = GitReleaseAction::Keep)]
+    git_release_action: GitReleaseAction,
+    /// Path to the Cargo.toml of the project you want to yank.
+    /// If not provided, release-plz will use the Cargo.toml of the current directory.
+    /// Both Cargo workspaces and single packages are supported.
+    #[arg(long, value_parser = PathBufValueParser::new())]
+    manifest_path: Option<PathBuf>,
+    /// Registry where you want to yank the packages.
+    /// The registry name needs to be present in the Cargo config.
+    /// If unspecified, the `publish` field of the package manifest is used.
+    /// If the `publish` field is empty, crates.io is used.
+    #[arg(long)]
+    registry: Option<String>,
+    /// Token used to yank from the cargo registry.
+    /// Override the `CARGO_REGISTRY_TOKEN` environment variable, or the `CARGO_REGISTRIES_<NAME>_TOKEN`
+    /// environment variable, used for registry specified in the `registry` input variable.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    token: Option<String>,
+    /// Print what would be yanked without yanking it.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// GitHub/Gitea/Gitlab repository url where your project is hosted.
+    /// It is used to edit the git release.
+    /// It defaults to the url of the default remote.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    pub repo_url: Option<String>,
+    /// Git token used to edit the GitHub/Gitea/GitLab release.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new(), env, hide_env_values=true)]
+    pub git_token: Option<String>,
+    /// Kind of git backend
+    #[arg(long, value_enum, default_value_t = ReleaseGitBackendKind::Github)]
+    backend: ReleaseGitBackendKind,
+    /// Path to the release-plz config file.
+    /// Default: `./release-plz.toml`.
+    /// If no config file is found, the default configuration is used.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = PathBufValueParser::new()
+    )]
+    config: Option<PathBuf>,
+    /// Output format. If specified, prints the version, the tag and the registries of the
+    /// yanked packages.
+    #[arg(short, long, value_enum)]
+    pub output: Option<OutputType>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GitTagAction {
+    /// Leave the git tag as it is.
+    Keep,
+    /// Delete the git tag locally and in the remote.
+    Delete,
+    /// Push the `<tag>-yanked` git tag, pointing to the same commit of the git tag.
+    Mark,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GitReleaseAction {
+    /// Leave the git release as it is.
+    Keep,
+    /// Turn the git release into a draft. Not supported by GitLab.
+    Draft,
+    /// Delete the git release.
+    Delete,
+}
+
+impl From<GitTagAction> for YankTagAction {
+    fn from(value: GitTagAction) -> Self {
+        match value {
+            GitTagAction::Keep => Self::Keep,
+            GitTagAction::Delete => Self::Delete,
+            GitTagAction::Mark => Self::Mark,
+        }
+    }
+}
+
+impl From<GitReleaseAction> for YankGitReleaseAction {
+    fn from(value: GitReleaseAction) -> Self {
+        match value {
+            GitReleaseAction::Keep => Self::Keep,
+            GitReleaseAction::Draft => Self::Draft,
+            GitReleaseAction::Delete => Self::Delete,
+        }
+    }
+}
+
+impl ConfigCommand for Yank {
+    fn config_path(&self) -> Option<&Path> {
+        self.config.as_deref()
+    }
+}
+
+impl Yank {
+    fn yank_spec(&self) -> anyhow::Result<YankSpec> {
+        if let Some(tag) = &self.tag {
+            return Ok(YankSpec::Tag(tag.clone()));
+        }
+        let versions = self
+            .versions
+            .iter()
+            .map(|v| {
+                let (package, version) = v.split_once('@').ok_or_else(|| {
+                    anyhow::anyhow!("version {v} is invalid. Format need to be `<package_name>@<version>`. E.g. `release-plz yank serde@1.2.3`")
+                })?;
+                let version = Version::parse(version)?;
+                Ok((package.to_string(), version))
+            })
+            .collect::<anyhow::Result<BTreeMap<String, Version>>>()?;
+        Ok(YankSpec::Packages(versions))
+    }
+
+    pub fn yank_request(
+        self,
+        config: &Config,
+        metadata: cargo_metadata::Metadata,
+    ) -> anyhow::Result<YankRequest> {
+        anyhow::ensure!(
+            self.git_release_action == GitReleaseAction::Keep || self.git_token.is_some(),
+            "please provide the git token with the --git-token cli argument to edit the git release."
+        );
+        let spec = self.yank_spec()?;
+        let mut req = ReleaseRequest::new(metadata).with_dry_run(self.dry_run);
+        if let Some(git_token) = &self.git_token {
+            let git_token = SecretString::from(git_token.clone());
+            let repo_url = self.get_repo_url(config)?;
+            req = req.with_git_release(release_plz_core::GitRelease {
+                backend: self.backend.git_backend(repo_url, git_token)?,
+            });
+        }
+        if let Some(registry) = self.registry {
+            req = req.with_registry(registry);
+        }
+        if let Some(token) = self.token {
+            req = req.with_token(SecretString::from(token));
+        }
+        if let Some(repo_url) = self.repo_url {
+            req = req.with_repo_url(repo_url);
+        }
+        req = req.with_publish_timeout(config.workspace.publish_timeout()?);
+        // Needed to compute the git tags of the packages.
+        req = config.fill_release_config(false, false, req);
+
+        let yank_request = YankRequest::new(req, spec)
+            .with_tag_action(self.git_tag_action.into())
+            .with_git_release_action(self.git_release_action.into());
+        Ok(yank_request)
+    }
+}
+
+impl RepoCommand for Yank {
+    fn repo_url(&self) -> Option<&str> {
+        self.repo_url.as_deref()
+    }
+}
+
+impl ManifestCommand for Yank {
+    fn optional_manifest(&self) -> Option<&Path> {
+        self.manifest_path.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn package_versions_are_parsed() {
+        let yank = Yank::parse_from(["yank", "serde@1.2.3", "rand@0.1.0"]);
+        let expected: BTreeMap<String, Version> = [
+            ("serde".to_string(), Version::new(1, 2, 3)),
+            ("rand".to_string(), Version::new(0, 1, 0)),
+        ]
+        .into();
+        assert_eq!(yank.yank_spec().unwrap(), YankSpec::Packages(expected));
+    }
+
+    #[test]
+    fn tag_is_parsed() {
+        let yank = Yank::parse_from(["yank", "--tag", "v1.2.3"]);
+        assert_eq!(
+            yank.yank_spec().unwrap(),
+            YankSpec::Tag("v1.2.3".to_string())
+        );
+    }
+
+    #[test]
+    fn version_without_package_is_invalid() {
+        let yank = Yank::parse_from(["yank", "1.2.3"]);
+        assert!(yank.yank_spec().is_err());
+    }
+
+    #[test]
+    fn versions_and_tag_conflict() {
+        Yank::try_parse_from(["yank", "serde@1.2.3", "--tag", "v1.2.3"]).unwrap_err();
+    }
+}
@@ -1,12 +1,18 @@
-use cargo_metadata::camino::Utf8Path;
+use anyhow::Context as _;
+use cargo_metadata::{camino::Utf8Path, semver::VersionReq};
 use cargo_utils::to_utf8_pathbuf;
+use regex::Regex;
 use release_plz_core::{
     fs_utils::to_utf8_path, set_version::SetVersionRequest, GitReleaseConfig, ReleaseRequest,
     UpdateRequest,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    time::Duration,
+};
 use url::Url;
 
 use crate::changelog_config::ChangelogCfg;
@@ -27,9 +33,17 @@ pub struct Config {
     /// Not all settings of `workspace` can be overridden.
     #[serde(default)]
     package: Vec<PackageSpecificConfigWithName>,
+    /// # Notification
+    /// Webhooks called after a release.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notification: Vec<NotificationConfig>,
 }
 
 impl Config {
+    pub fn notifications(&self) -> Vec<release_plz_core::Notification> {
+        self.notification.iter().cloned().map(Into::into).collect()
+    }
+
     /// Package-specific configurations.
     /// Returns `<package name, package config>`.
     fn packages(&self) -> HashMap<&str, &PackageSpecificConfig> {
@@ -125,6 +139,22 @@ pub struct Workspace {
     /// - If `true`, update all the dependencies in the Cargo.lock file by running `cargo update`.
     /// - If `false` or [`Option::None`], only update the workspace packages by running `cargo update --workspace`.
     pub dependencies_update: Option<bool>,
+    /// # Git Release Combined
+    /// If `true`, create a single git release for all the packages released by `release-plz release`,
+    /// instead of one git release per package. The git tags of the packages are created anyway.
+    pub git_release_combined: Option<bool>,
+    /// # Git Release Combined Tag
+    /// Tera template of the git tag of the combined git release.
+    /// Default: `v{{ version }}`.
+    pub git_release_combined_tag: Option<String>,
+    /// # Git Release Combined Name
+    /// Tera template of the name of the combined git release.
+    /// Default: `{{ tag }}`.
+    pub git_release_combined_name: Option<String>,
+    /// # Git Release Combined Body
+    /// Tera template of the body of the combined git release.
+    /// By default, it contains the changelog of each package under a heading.
+    pub git_release_combined_body: Option<String>,
     /// # PR Name
     /// Tera template of the pull request's name created by release-plz.
     pub pr_name: Option<String>,
@@ -139,12 +169,56 @@ pub struct Workspace {
     /// Labels to add to the release PR.
     #[serde(default)]
     pub pr_labels: Vec<String>,
+    /// # PR Reviewers
+    /// Usernames of the users asked to review the release PR.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_reviewers: Vec<String>,
+    /// # PR Team Reviewers
+    /// Slugs of the teams asked to review the release PR.
+    /// Only supported by GitHub and Gitea.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_team_reviewers: Vec<String>,
+    /// # PR Assignees
+    /// Usernames of the users assigned to the release PR.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pr_assignees: Vec<String>,
+    /// # PR Milestone
+    /// Title of the open milestone of the release PR.
+    pub pr_milestone: Option<String>,
+    /// # PR Contributor Commits
+    /// What to do when the release PR contains commits that release-plz didn't author,
+    /// e.g. a manual edit of the changelog.
+    /// Default: `close`.
+    pub pr_contributor_commits: Option<ContributorCommits>,
+    /// # PR Changelog Edits
+    /// If `true`, apply the changelogs edited in the body of the release PR to the changelog files.
+    /// Default: `false`.
+    pub pr_changelog_edits: Option<bool>,
     /// # PR Branch Prefix
     /// Prefix for the PR Branch
     pub pr_branch_prefix: Option<String>,
+    /// # Release PR Mode
+    /// Whether to open a single release PR, or a release PR for each package or version group.
+    /// Default: `workspace`.
+    pub release_pr_mode: Option<ReleasePrMode>,
+    /// # PR Auto Merge
+    /// If set, enable auto-merge on the release PR with the given merge method,
+    /// so that the PR is merged once the CI passes.
+    pub pr_auto_merge: Option<MergeMethod>,
     /// # Publish Timeout
     /// Timeout for the publishing process
     pub publish_timeout: Option<String>,
+    /// # Publish Concurrency
+    /// Maximum number of packages to release at the same time.
+    /// Only packages that don't depend on each other are released concurrently.
+    /// Defaults to `1`.
+    pub publish_concurrency: Option<usize>,
+    /// # Publish Workspace
+    /// If `true`, publish all the packages with a single `cargo publish` invocation,
+    /// letting cargo publish them in the right order.
+    /// Requires cargo 1.90 or newer: with older versions, packages are published one by one.
+    /// Defaults to `false`.
+    pub publish_workspace: Option<bool>,
     /// # Repo URL
     /// GitHub/Gitea repository url where your project is hosted.
     /// It is used to generate the changelog release link.
@@ -163,15 +237,112 @@ pub struct Workspace {
     ///   `release-plz-`. So if you want to create a PR that should trigger a release
     ///   (e.g. when you fix the CI), use this branch name format (e.g. `release-plz-fix-ci`).
     pub release_always: Option<bool>,
+    /// # Release Trigger
+    /// How `release-plz release` decides whether to release when `release_always` is `false`.
+    /// Default: `release_pr`.
+    pub release_trigger: Option<ReleaseTrigger>,
+    /// # Release Commit Message
+    /// With `release_trigger = "version_bump"`, release also if the message of the
+    /// current commit matches this regex.
+    pub release_commit_message: Option<String>,
+    /// # Release Branches
+    /// Maintenance branches of older release lines, e.g. `1.x`.
+    /// On these branches, release-plz compares the packages with the latest registry
+    /// version matching the `version` requirement and doesn't mark git releases as latest.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub release_branches: Vec<ReleaseBranch>,
+}
+
+/// Maintenance branch of a release line.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReleaseBranch {
+    /// # Branch
+    /// Name of the git branch, e.g. `v1`.
+    pub branch: String,
+    /// # Version
+    /// Version requirement of the packages released from this branch, e.g. `^1`.
+    pub version: String,
 }
 
 impl Workspace {
+    /// Get the combined git release, if enabled.
+    pub fn combined_git_release(&self) -> Option<release_plz_core::CombinedGitRelease> {
+        (self.git_release_combined == Some(true)).then(|| {
+            release_plz_core::CombinedGitRelease::default()
+                .set_tag_template(self.git_release_combined_tag.clone())
+                .set_name_template(self.git_release_combined_name.clone())
+                .set_body_template(self.git_release_combined_body.clone())
+        })
+    }
+
     /// Get the publish timeout. Defaults to 30 minutes.
     pub fn publish_timeout(&self) -> anyhow::Result<Duration> {
         let publish_timeout = self.publish_timeout.as_deref().unwrap_or("30m");
         duration_str::parse(publish_timeout)
             .map_err(|e| anyhow::anyhow!("invalid publish_timeout {publish_timeout}: {e}"))
     }
+
+    pub fn release_trigger(&self) -> anyhow::Result<release_plz_core::ReleaseTrigger> {
+        let trigger = match self.release_trigger.unwrap_or_default() {
+            ReleaseTrigger::ReleasePr => {
+                anyhow::ensure!(
+                    self.release_commit_message.is_none(),
+                    "release_commit_message requires release_trigger = \"version_bump\""
+                );
+                release_plz_core::ReleaseTrigger::ReleasePr
+            }
+            ReleaseTrigger::VersionBump => {
+                let commit_message = self
+                    .release_commit_message
+                    .as_deref()
+                    .map(|pattern| {
+                        Regex::new(pattern).with_context(|| {
+                            format!("invalid release_commit_message regex `{pattern}`")
+                        })
+                    })
+                    .transpose()?;
+                release_plz_core::ReleaseTrigger::VersionBump { commit_message }
+            }
+        };
+        Ok(trigger)
+    }
+
+    /// Get the release branch of the git branch checked out in `project_dir`, if any.
+    pub fn current_release_branch(
+        &self,
+        project_dir: &Utf8Path,
+    ) -> anyhow::Result<Option<&ReleaseBranch>> {
+        if self.release_branches.is_empty() {
+            return Ok(None);
+        }
+        let repo = git_cmd::Repo::new(project_dir)?;
+        self.release_branch(repo.original_branch())
+    }
+
+    /// Get the release branch with the given name, if any.
+    fn release_branch(&self, branch: &str) -> anyhow::Result<Option<&ReleaseBranch>> {
+        let Some(release_branch) = self.release_branches.iter().find(|b| b.branch == branch) else {
+            return Ok(None);
+        };
+        VersionReq::parse(&release_branch.version).with_context(|| {
+            format!(
+                "invalid version requirement `{}` of release branch `{branch}`",
+                release_branch.version
+            )
+        })?;
+        Ok(Some(release_branch))
+    }
+
+    /// Get the publish concurrency. Defaults to 1.
+    pub fn publish_concurrency(&self) -> anyhow::Result<usize> {
+        let publish_concurrency = self.publish_concurrency.unwrap_or(1);
+        anyhow::ensure!(
+            publish_concurrency > 0,
+            "invalid publish_concurrency {publish_concurrency}: it must be greater than 0"
+        );
+        Ok(publish_concurrency)
+    }
 }
 
 /// Config at the `[[package]]` level.
@@ -213,15 +384,25 @@ impl From<PackageConfig> for release_plz_core::ReleaseConfig {
         let is_publish_enabled = value.publish != Some(false);
         let is_git_tag_enabled = value.git_tag_enable != Some(false);
         let git_tag_name = value.git_tag_name.clone();
+        let git_tag_message = value.git_tag_message.clone();
+        let is_git_tag_signed = value.git_tag_sign == Some(true);
         let release = value.release != Some(false);
+        let publish_policy = publish_policy(&value);
         let mut cfg = Self::default()
             .with_publish(release_plz_core::PublishConfig::enabled(is_publish_enabled))
             .with_git_release(git_release(&value))
             .with_git_tag(
                 release_plz_core::GitTagConfig::enabled(is_git_tag_enabled)
-                    .set_name_template(git_tag_name),
+                    .set_name_template(git_tag_name)
+                    .set_message_template(git_tag_message)
+                    .set_sign(is_git_tag_signed),
             )
-            .with_release(release);
+            .with_release(release)
+            .with_hooks(release_plz_core::ReleaseHooks {
+                pre_publish: value.pre_publish_hook.clone(),
+                post_publish: value.post_publish_hook.clone(),
+                post_release: value.post_release_hook.clone(),
+            });
 
         if let Some(changelog_update) = value.changelog_update {
             cfg = cfg.with_changelog_update(changelog_update);
@@ -241,10 +422,27 @@ impl From<PackageConfig> for release_plz_core::ReleaseConfig {
         if let Some(allow_dirty) = value.publish_allow_dirty {
             cfg = cfg.with_allow_dirty(allow_dirty);
         }
-        cfg
+        if let Some(semver_check) = value.publish_semver_check {
+            cfg = cfg.with_semver_check(semver_check);
+        }
+        if let Some(integrity_check) = value.publish_integrity_check {
+            cfg = cfg.with_integrity_check(integrity_check.into());
+        }
+        cfg.with_publish_policy(publish_policy)
     }
 }
 
+fn publish_policy(config: &PackageConfig) -> release_plz_core::PublishPolicy {
+    let mut policy = release_plz_core::PublishPolicy::default()
+        .with_forbidden_files(config.publish_forbidden_files.clone().unwrap_or_default())
+        .with_require_readme(config.publish_require_readme == Some(true))
+        .with_require_license(config.publish_require_license == Some(true));
+    if let Some(max_size) = config.publish_max_size {
+        policy = policy.with_max_size(max_size);
+    }
+    policy
+}
+
 fn git_release(config: &PackageConfig) -> GitReleaseConfig {
     let is_git_release_enabled = config.git_release_enable != Some(false);
     let git_release_type: release_plz_core::ReleaseType = config
@@ -258,7 +456,10 @@ fn git_release(config: &PackageConfig) -> GitReleaseConfig {
         .set_draft(is_git_release_draft)
         .set_release_type(git_release_type)
         .set_name_template(git_release_name)
-        .set_body_template(git_release_body);
+        .set_body_template(git_release_body)
+        .set_assets(config.git_release_assets.clone().unwrap_or_default())
+        .set_assets_checksum(config.git_release_assets_checksum == Some(true))
+        .set_update(config.git_release_update.unwrap_or_default().into());
 
     if config.git_release_latest == Some(false) {
         git_release = git_release.set_latest(false);
@@ -303,6 +504,17 @@ pub struct PackageConfig {
     /// # Git Release Name
     /// Tera template of the git release name created by release-plz.
     pub git_release_name: Option<String>,
+    /// # Git Release Assets
+    /// Glob patterns of the files to upload to the git release, relative to the workspace root.
+    /// E.g. `["target/dist/*.tar.gz"]`.
+    pub git_release_assets: Option<Vec<String>>,
+    /// # Git Release Assets Checksum
+    /// If `true`, upload a `<file>.sha256` file with the SHA256 checksum of each git release asset.
+    pub git_release_assets_checksum: Option<bool>,
+    /// # Git Release Update
+    /// What to do if the git release of the tag exists already.
+    /// Default: `overwrite`.
+    pub git_release_update: Option<GitReleaseUpdate>,
     /// # Git Tag Enable
     /// Publish the git tag for the new package version.
     /// Enabled by default.
@@ -310,6 +522,22 @@ pub struct PackageConfig {
     /// # Git Tag Name
     /// Tera template of the git tag name created by release-plz.
     pub git_tag_name: Option<String>,
+    /// # Git Tag Message
+    /// Tera template of the git tag message created by release-plz.
+    pub git_tag_message: Option<String>,
+    /// # Git Tag Sign
+    /// Sign the git tag with the GPG, SSH or X.509 key configured in git.
+    pub git_tag_sign: Option<bool>,
+    /// # Pre Publish Hook
+    /// Command run by the system shell before `cargo publish`.
+    /// If the command fails, the package isn't released.
+    pub pre_publish_hook: Option<String>,
+    /// # Post Publish Hook
+    /// Command run by the system shell after the registry index contains the published package.
+    pub post_publish_hook: Option<String>,
+    /// # Post Release Hook
+    /// Command run by the system shell after the git tag is pushed and the git release is created.
+    pub post_release_hook: Option<String>,
     /// # Publish
     /// If `false`, don't run `cargo publish`.
     pub publish: Option<bool>,
@@ -325,6 +553,32 @@ pub struct PackageConfig {
     /// # Publish All Features
     /// If `true`, add the `--all-features` flag to the `cargo publish` command.
     pub publish_all_features: Option<bool>,
+    /// # Publish Max Size
+    /// Maximum size in bytes of the `.crate` file created by `cargo package`.
+    /// If the package is bigger, release-plz doesn't run `cargo publish`.
+    /// If unspecified, the crates.io limit of 10 MiB is used for packages published to crates.io.
+    pub publish_max_size: Option<u64>,
+    /// # Publish Forbidden Files
+    /// Glob patterns of the files that must not be included in the package,
+    /// relative to the package directory. E.g. `["**/.env", "**/*.pem"]`.
+    /// If the package contains one of these files, release-plz doesn't run `cargo publish`.
+    pub publish_forbidden_files: Option<Vec<String>>,
+    /// # Publish Require Readme
+    /// If `true`, don't run `cargo publish` if the package doesn't contain a README file.
+    pub publish_require_readme: Option<bool>,
+    /// # Publish Require License
+    /// If `true`, don't run `cargo publish` if the package doesn't contain a license file.
+    pub publish_require_license: Option<bool>,
+    /// # Publish Integrity Check
+    /// After publishing the package, verify that the package in the registry
+    /// is the package built locally, before creating the git tag.
+    /// If unspecified, the published package isn't verified.
+    pub publish_integrity_check: Option<IntegrityCheck>,
+    /// # Publish Semver Check
+    /// If `true`, run cargo-semver-checks against the version published in crates.io
+    /// before running `cargo publish` and report the outcome in the json output.
+    /// Default: `false`.
+    pub publish_semver_check: Option<bool>,
     /// # Semver Check
     /// Controls when to run cargo-semver-checks.
     /// If unspecified, run cargo-semver-checks if the package is a library.
@@ -373,14 +627,38 @@ impl PackageConfig {
             git_release_latest: self.git_release_latest.or(default.git_release_latest),
             git_release_name: self.git_release_name.or(default.git_release_name),
             git_release_body: self.git_release_body.or(default.git_release_body),
+            git_release_assets: self.git_release_assets.or(default.git_release_assets),
+            git_release_assets_checksum: self
+                .git_release_assets_checksum
+                .or(default.git_release_assets_checksum),
+            git_release_update: self.git_release_update.or(default.git_release_update),
 
+            pre_publish_hook: self.pre_publish_hook.or(default.pre_publish_hook),
+            post_publish_hook: self.post_publish_hook.or(default.post_publish_hook),
+            post_release_hook: self.post_release_hook.or(default.post_release_hook),
             publish: self.publish.or(default.publish),
             publish_allow_dirty: self.publish_allow_dirty.or(default.publish_allow_dirty),
             publish_no_verify: self.publish_no_verify.or(default.publish_no_verify),
             publish_features: self.publish_features.or(default.publish_features),
             publish_all_features: self.publish_all_features.or(default.publish_all_features),
+            publish_max_size: self.publish_max_size.or(default.publish_max_size),
+            publish_forbidden_files: self
+                .publish_forbidden_files
+                .or(default.publish_forbidden_files),
+            publish_require_readme: self
+                .publish_require_readme
+                .or(default.publish_require_readme),
+            publish_require_license: self
+                .publish_require_license
+                .or(default.publish_require_license),
+            publish_integrity_check: self
+                .publish_integrity_check
+                .or(default.publish_integrity_check),
+            publish_semver_check: self.publish_semver_check.or(default.publish_semver_check),
             git_tag_enable: self.git_tag_enable.or(default.git_tag_enable),
             git_tag_name: self.git_tag_name.or(default.git_tag_name),
+            git_tag_message: self.git_tag_message.or(default.git_tag_message),
+            git_tag_sign: self.git_tag_sign.or(default.git_tag_sign),
             release: self.release.or(default.release),
         }
     }
@@ -392,6 +670,102 @@ impl PackageConfig {
     }
 }
 
+/// Webhook called after a release.
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// # Url
+    /// Url of the webhook. It's a tera template,
+    /// so you can read secrets from environment variables with `get_env`.
+    pub url: String,
+    /// # Headers
+    /// HTTP headers sent to the webhook. Values are tera templates.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// # Template
+    /// Tera template of the payload. Overrides the template of the `preset`.
+    pub template: Option<String>,
+    /// # Preset
+    /// Payload format of a chat service.
+    pub preset: Option<NotificationPreset>,
+    /// # Scope
+    /// Whether to call the webhook once per run or once per released package.
+    /// Default: `release`.
+    pub scope: Option<NotificationScope>,
+}
+
+impl From<NotificationConfig> for release_plz_core::Notification {
+    fn from(value: NotificationConfig) -> Self {
+        Self {
+            url: value.url,
+            headers: value.headers,
+            template: value.template,
+            preset: value.preset.map(Into::into),
+            scope: value.scope.unwrap_or_default().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationPreset {
+    /// # Slack
+    /// Slack incoming webhook.
+    Slack,
+    /// # Discord
+    /// Discord webhook.
+    Discord,
+    /// # Matrix
+    /// Matrix client-server API. The message is sent with `PUT`.
+    Matrix,
+}
+
+impl From<NotificationPreset> for release_plz_core::NotificationPreset {
+    fn from(value: NotificationPreset) -> Self {
+        match value {
+            NotificationPreset::Slack => Self::Slack,
+            NotificationPreset::Discord => Self::Discord,
+            NotificationPreset::Matrix => Self::Matrix,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationScope {
+    /// # Release
+    /// Call the webhook once per run, with all the released packages.
+    #[default]
+    Release,
+    /// # Package
+    /// Call the webhook once per released package.
+    Package,
+}
+
+impl From<NotificationScope> for release_plz_core::NotificationScope {
+    fn from(value: NotificationScope) -> Self {
+        match value {
+            NotificationScope::Release => Self::Release,
+            NotificationScope::Package => Self::Package,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseTrigger {
+    /// # Release PR
+    /// Release if the current commit comes from a release PR.
+    /// The release PR is found with the API of the git forge.
+    #[default]
+    ReleasePr,
+    /// # Version Bump
+    /// Release if the current commit changes the version of a package
+    /// or if its message matches `release_commit_message`.
+    /// Only git is used.
+    VersionBump,
+}
+
 /// Whether to run cargo-semver-checks or not.
 /// Note: you can only run cargo-semver-checks on a library.
 #[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy)]
@@ -432,6 +806,128 @@ impl From<ReleaseType> for release_plz_core::ReleaseType {
     }
 }
 
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitReleaseUpdate {
+    /// # Overwrite
+    /// Update the name, body, draft, pre-release and latest fields of the existing release.
+    #[default]
+    Overwrite,
+    /// # Skip
+    /// Leave the existing release as it is.
+    Skip,
+    /// # Fail
+    /// Fail the release.
+    Fail,
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleasePrMode {
+    /// # Workspace
+    /// Open a single PR that releases all the packages.
+    #[default]
+    Workspace,
+    /// # Per Package
+    /// Open a PR for each package.
+    PerPackage,
+    /// # Per Group
+    /// Open a PR for each version group.
+    /// Packages without a version group are released in their own PR.
+    PerGroup,
+}
+
+impl From<ReleasePrMode> for release_plz_core::ReleasePrMode {
+    fn from(value: ReleasePrMode) -> Self {
+        match value {
+            ReleasePrMode::Workspace => Self::Workspace,
+            ReleasePrMode::PerPackage => Self::PerPackage,
+            ReleasePrMode::PerGroup => Self::PerGroup,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContributorCommits {
+    /// # Close
+    /// Close the release PR and open a new one without the commits.
+    #[default]
+    Close,
+    /// # Rebase
+    /// Regenerate the release PR and apply the commits on top of it.
+    /// If the commits can't be applied, behave like `keep`.
+    Rebase,
+    /// # Keep
+    /// Don't update the release PR and explain why in a comment.
+    Keep,
+}
+
+impl From<ContributorCommits> for release_plz_core::ContributorCommits {
+    fn from(value: ContributorCommits) -> Self {
+        match value {
+            ContributorCommits::Close => Self::Close,
+            ContributorCommits::Rebase => Self::Rebase,
+            ContributorCommits::Keep => Self::Keep,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMethod {
+    /// # Merge
+    /// Add the commits of the PR to the base branch with a merge commit.
+    Merge,
+    /// # Squash
+    /// Combine the commits of the PR into a single commit.
+    Squash,
+    /// # Rebase
+    /// Add the commits of the PR to the base branch individually.
+    Rebase,
+}
+
+impl From<MergeMethod> for release_plz_core::MergeMethod {
+    fn from(value: MergeMethod) -> Self {
+        match value {
+            MergeMethod::Merge => Self::Merge,
+            MergeMethod::Squash => Self::Squash,
+            MergeMethod::Rebase => Self::Rebase,
+        }
+    }
+}
+
+impl From<GitReleaseUpdate> for release_plz_core::GitReleaseUpdate {
+    fn from(value: GitReleaseUpdate) -> Self {
+        match value {
+            GitReleaseUpdate::Overwrite => Self::Overwrite,
+            GitReleaseUpdate::Skip => Self::Skip,
+            GitReleaseUpdate::Fail => Self::Fail,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityCheck {
+    /// # Checksum
+    /// Compare the checksum in the registry index with the checksum
+    /// of the `.crate` file created by `cargo publish`.
+    Checksum,
+    /// # Content
+    /// Download the published package and compare its files with the local package.
+    Content,
+}
+
+impl From<IntegrityCheck> for release_plz_core::IntegrityCheck {
+    fn from(value: IntegrityCheck) -> Self {
+        match value {
+            IntegrityCheck::Checksum => Self::Checksum,
+            IntegrityCheck::Content => Self::Content,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,12 +975,30 @@ mod tests {
                 pr_body: None,
                 pr_draft: false,
                 pr_labels: vec![],
+                pr_reviewers: vec![],
+                pr_team_reviewers: vec![],
+                pr_assignees: vec![],
+                pr_milestone: None,
+                pr_contributor_commits: None,
+                pr_changelog_edits: None,
                 pr_branch_prefix: Some("f-".to_string()),
+                release_pr_mode: None,
+                pr_auto_merge: None,
                 publish_timeout: Some("10m".to_string()),
+                publish_concurrency: None,
+                publish_workspace: None,
                 release_commits: Some("^feat:".to_string()),
                 release_always: None,
+                release_trigger: None,
+                release_commit_message: None,
+                release_branches: vec![],
+                git_release_combined: None,
+                git_release_combined_tag: None,
+                git_release_combined_name: None,
+                git_release_combined_body: None,
             },
             package: [].into(),
+            notification: vec![],
         }
     }
 
@@ -593,7 +1107,15 @@ mod tests {
                 pr_body: None,
                 pr_draft: false,
                 pr_labels: vec!["label1".to_string()],
+                pr_reviewers: vec![],
+                pr_team_reviewers: vec![],
+                pr_assignees: vec![],
+                pr_milestone: None,
+                pr_contributor_commits: None,
+                pr_changelog_edits: None,
                 pr_branch_prefix: Some("f-".to_string()),
+                release_pr_mode: None,
+                pr_auto_merge: None,
                 packages_defaults: PackageConfig {
                     semver_check: None,
                     changelog_update: true.into(),
@@ -605,8 +1127,17 @@ mod tests {
                     ..Default::default()
                 },
                 publish_timeout: Some("10m".to_string()),
+                publish_concurrency: None,
+                publish_workspace: None,
                 release_commits: Some("^feat:".to_string()),
                 release_always: None,
+                release_trigger: None,
+                release_commit_message: None,
+                release_branches: vec![],
+                git_release_combined: None,
+                git_release_combined_tag: None,
+                git_release_combined_name: None,
+                git_release_combined_body: None,
             },
             package: [PackageSpecificConfigWithName {
                 name: "crate1".to_string(),
@@ -625,6 +1156,7 @@ mod tests {
                 },
             }]
             .into(),
+            notification: vec![],
         };
 
         expect_test::expect![[r#"
@@ -668,7 +1200,7 @@ mod tests {
               |
             1 | [unknown]
               |  ^^^^^^^
-            unknown field `unknown`, expected one of `workspace`, `changelog`, `package`
+            unknown field `unknown`, expected one of `workspace`, `changelog`, `package`, `notification`
         "#]]
         .assert_eq(&error);
     }
@@ -726,4 +1258,143 @@ unknown = false"#;
         "#]]
         .assert_eq(&error);
     }
+
+    #[test]
+    fn notification_section_is_deserialized() {
+        let config = r#"
+[[notification]]
+url = "{{ get_env(name='SLACK_WEBHOOK') }}"
+preset = "slack"
+
+[[notification]]
+url = "https://example.com/hook"
+headers = { Authorization = "Bearer token" }
+template = "{{ releases | json_encode() }}"
+scope = "package"
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(
+            config.notifications(),
+            vec![
+                release_plz_core::Notification {
+                    url: "{{ get_env(name='SLACK_WEBHOOK') }}".to_string(),
+                    preset: Some(release_plz_core::NotificationPreset::Slack),
+                    ..Default::default()
+                },
+                release_plz_core::Notification {
+                    url: "https://example.com/hook".to_string(),
+                    headers: [("Authorization".to_string(), "Bearer token".to_string())].into(),
+                    template: Some("{{ releases | json_encode() }}".to_string()),
+                    preset: None,
+                    scope: release_plz_core::NotificationScope::Package,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn release_trigger_is_parsed() {
+        let config = r#"
+[workspace]
+release_always = false
+release_trigger = "version_bump"
+release_commit_message = "^chore: release"
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        let release_plz_core::ReleaseTrigger::VersionBump { commit_message } =
+            config.workspace.release_trigger().unwrap()
+        else {
+            panic!("unexpected release trigger");
+        };
+        assert!(commit_message.unwrap().is_match("chore: release v1.0.0"));
+    }
+
+    #[test]
+    fn release_commit_message_requires_version_bump_trigger() {
+        let config = r#"
+[workspace]
+release_commit_message = "^chore: release"
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        assert!(config.workspace.release_trigger().is_err());
+    }
+
+    #[test]
+    fn release_semver_check_is_opt_in() {
+        let config = r#"
+[workspace]
+semver_check = true
+
+[[package]]
+name = "pkg1"
+publish_semver_check = true
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        let default_config: release_plz_core::ReleaseConfig =
+            config.workspace.packages_defaults.clone().into();
+        assert_eq!(default_config, release_plz_core::ReleaseConfig::default());
+        let package_config: release_plz_core::ReleaseConfig = config.packages()["pkg1"]
+            .common
+            .clone()
+            .merge(config.workspace.packages_defaults)
+            .into();
+        assert_eq!(
+            package_config,
+            release_plz_core::ReleaseConfig::default().with_semver_check(true)
+        );
+    }
+
+    #[test]
+    fn publish_checks_are_parsed() {
+        let config = r#"
+[workspace]
+publish_max_size = 10485760
+publish_forbidden_files = ["**/.env"]
+publish_require_license = true
+publish_integrity_check = "checksum"
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        let package_config = config.workspace.packages_defaults;
+        assert_eq!(
+            package_config.publish_integrity_check,
+            Some(IntegrityCheck::Checksum)
+        );
+        let release_config: release_plz_core::ReleaseConfig = package_config.into();
+        assert_eq!(
+            release_config.publish_policy(),
+            &release_plz_core::PublishPolicy::default()
+                .with_max_size(10_485_760)
+                .with_forbidden_files(vec!["**/.env".to_string()])
+                .with_require_license(true)
+        );
+    }
+
+    #[test]
+    fn release_branch_is_found() {
+        let config = r#"
+[workspace]
+release_branches = [{ branch = "v1", version = "^1" }]
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        let release_branch = config.workspace.release_branch("v1").unwrap().unwrap();
+        assert_eq!(release_branch.version, "^1");
+        assert!(config.workspace.release_branch("main").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_release_branch_version_errors() {
+        let config = r#"
+[workspace]
+release_branches = [{ branch = "v1", version = "one" }]
+"#;
+
+        let config: Config = toml::from_str(config).unwrap();
+        assert!(config.workspace.release_branch("v1").is_err());
+    }
 }
@@ -45,14 +45,10 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
             let config = cmd_args.update.config()?;
             let update_request = cmd_args.update.update_request(&config, cargo_metadata)?;
             let request = get_release_pr_req(&config, update_request)?;
-            let release_pr = release_plz_core::release_pr(&request).await?;
+            let release_prs = release_plz_core::release_pr(&request).await?;
             if let Some(output_type) = cmd_args.output {
-                let prs = match release_pr {
-                    Some(pr) => vec![pr],
-                    None => vec![],
-                };
                 let prs_json = serde_json::json!({
-                    "prs": prs
+                    "prs": release_prs
                 });
                 print_output(output_type, prs_json);
             }
@@ -67,6 +63,8 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
                 .unwrap_or_default();
             if let Some(output_type) = cmd_args_output {
                 print_output(output_type, output);
+            } else if let Some(plan) = output.plan() {
+                print!("{plan}");
             }
         }
         Command::GenerateCompletions(cmd_args) => cmd_args.print(),
@@ -78,6 +76,16 @@ async fn run(args: CliArgs) -> anyhow::Result<()> {
             let request = cmd_args.set_version_request(&config)?;
             release_plz_core::set_version::set_version(&request)?;
         }
+        Command::Yank(cmd_args) => {
+            let cargo_metadata = cmd_args.cargo_metadata()?;
+            let config = cmd_args.config()?;
+            let cmd_args_output = cmd_args.output;
+            let request = cmd_args.yank_request(&config, cargo_metadata)?;
+            let output = release_plz_core::yank(&request).await?.unwrap_or_default();
+            if let Some(output_type) = cmd_args_output {
+                print_output(output_type, output);
+            }
+        }
     }
     Ok(())
 }
@@ -91,9 +99,24 @@ fn get_release_pr_req(
     let pr_body = config.workspace.pr_body.clone();
     let pr_labels = config.workspace.pr_labels.clone();
     let pr_draft = config.workspace.pr_draft;
+    let pr_mode = config.workspace.release_pr_mode.unwrap_or_default();
     let request = ReleasePrRequest::new(update_request)
+        .with_mode(pr_mode.into())
+        .with_auto_merge(config.workspace.pr_auto_merge.map(Into::into))
+        .with_changelog_edits(config.workspace.pr_changelog_edits == Some(true))
+        .with_contributor_commits(
+            config
+                .workspace
+                .pr_contributor_commits
+                .unwrap_or_default()
+                .into(),
+        )
         .mark_as_draft(pr_draft)
         .with_labels(pr_labels)
+        .with_reviewers(config.workspace.pr_reviewers.clone())
+        .with_team_reviewers(config.workspace.pr_team_reviewers.clone())
+        .with_assignees(config.workspace.pr_assignees.clone())
+        .with_milestone(config.workspace.pr_milestone.clone())
         .with_branch_prefix(pr_branch_prefix)
         .with_pr_name_template(pr_name)
         .with_pr_body_template(pr_body);
@@ -1,6 +1,24 @@
 use release_plz_core::fs_utils::Utf8TempDir;
 
-use crate::helpers::test_context::TestContext;
+use crate::helpers::{test_context::TestContext, TEST_REGISTRY};
+
+/// Parse the json output of `release-plz release`.
+/// Fields that change at every run (`commit`, `git_release_url`) are checked and removed.
+fn parse_releases(context: &TestContext, stdout: &[u8]) -> serde_json::Value {
+    let mut output: serde_json::Value = serde_json::from_slice(stdout).unwrap();
+    for release in output["releases"].as_array_mut().unwrap() {
+        let release = release.as_object_mut().unwrap();
+        let tag = release["tag"].as_str().unwrap().to_string();
+        let commit = release.remove("commit").unwrap();
+        assert_eq!(
+            commit.as_str(),
+            context.repo.get_tag_commit(&tag).as_deref()
+        );
+        let git_release_url = release.remove("git_release_url").unwrap();
+        assert!(git_release_url.as_str().unwrap().ends_with(&tag));
+    }
+    output
+}
 
 #[tokio::test]
 #[cfg_attr(not(feature = "docker-tests"), ignore)]
@@ -27,7 +45,7 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
     let crate_name = &context.gitea.repo;
 
     let outcome = context.run_release().success();
-    let expected_stdout = serde_json::json!({
+    let expected_releases = serde_json::json!({
         "releases": [
             {
                 "package_name": crate_name,
@@ -43,11 +61,22 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
                         "number":1434
                     }
                 ],
+                "registries": [TEST_REGISTRY],
+                "pre_release": false,
+                "semver_check": "skipped",
             }
         ]
-    })
-    .to_string();
-    outcome.stdout(format!("{expected_stdout}\n"));
+    });
+    let mut output = parse_releases(&context, &outcome.get_output().stdout);
+    let release_changelog = output["releases"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("changelog")
+        .unwrap();
+    let release_changelog = release_changelog.as_str().unwrap();
+    assert!(release_changelog.contains("### Other"));
+    assert!(release_changelog.contains("- add clippy lints"));
+    assert_eq!(output, expected_releases);
 }
 
 #[tokio::test]
@@ -69,18 +98,22 @@ async fn release_plz_releases_a_new_project_with_custom_tag_name() {
     assert!(!is_tag_created());
 
     let outcome = context.run_release().success();
-    let expected_stdout = serde_json::json!({
+    let expected_releases = serde_json::json!({
         "releases": [
             {
                 "package_name": crate_name,
                 "prs": [],
                 "tag": expected_tag,
                 "version": "0.1.0",
+                "changelog": "",
+                "registries": [TEST_REGISTRY],
+                "pre_release": false,
+                "semver_check": "skipped",
             }
         ]
-    })
-    .to_string();
-    outcome.stdout(format!("{expected_stdout}\n"));
+    });
+    let output = parse_releases(&context, &outcome.get_output().stdout);
+    assert_eq!(output, expected_releases);
 
     assert!(is_tag_created());
 }
@@ -185,18 +218,22 @@ async fn release_plz_does_not_releases_twice() {
 
     // Running `release` the first time, releases the project
     let outcome = context.run_release().success();
-    let expected_stdout = serde_json::json!({
+    let expected_releases = serde_json::json!({
         "releases": [
             {
                 "package_name": crate_name,
                 "prs": [],
                 "tag": "v0.1.0",
                 "version": "0.1.0",
+                "changelog": "",
+                "registries": [TEST_REGISTRY],
+                "pre_release": false,
+                "semver_check": "skipped",
             }
         ]
-    })
-    .to_string();
-    outcome.stdout(format!("{expected_stdout}\n"));
+    });
+    let output = parse_releases(&context, &outcome.get_output().stdout);
+    assert_eq!(output, expected_releases);
 
     // Running `release` the second time, releases nothing.
     let outcome = context.run_release().success();
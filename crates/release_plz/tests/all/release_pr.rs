@@ -29,10 +29,12 @@ async fn release_plz_opens_pr_with_default_config() {
 
 ## [0.1.0](https://localhost/{username}/{package}/releases/tag/v0.1.0) - {today}
 
+<!-- release-plz-changelog-start: {package} -->
 ### Other
 
 - cargo init
 - Initial commit
+<!-- release-plz-changelog-end: {package} -->
 </blockquote>
 
 
@@ -103,6 +103,30 @@ async fn is_in_cache_sparse(
     Ok(is_in_cache(crate_data.as_ref(), version))
 }
 
+/// SHA256 checksum of the `.crate` file of the package version, as written in the registry index.
+/// Return [`Option::None`] if the registry doesn't contain the package version.
+pub async fn published_checksum(
+    index: &mut CargoIndex,
+    package: &Package,
+    token: &Option<SecretString>,
+) -> anyhow::Result<Option<[u8; 32]>> {
+    let crate_data = match index {
+        CargoIndex::Git(index) => index.crate_(&package.name),
+        CargoIndex::Sparse(index) => fetch_sparse_metadata(index, &package.name, token)
+            .await
+            .context("failed fetching sparse metadata")?,
+    };
+    let version = package.version.to_string();
+    let checksum = crate_data.and_then(|crate_data| {
+        crate_data
+            .versions()
+            .iter()
+            .find(|v| v.version() == version)
+            .map(|v| *v.checksum())
+    });
+    Ok(checksum)
+}
+
 fn is_in_cache(crate_data: Option<&Crate>, version: &str) -> bool {
     if let Some(crate_data) = crate_data {
         if is_version_present(version, crate_data) {
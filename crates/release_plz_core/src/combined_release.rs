@@ -0,0 +1,202 @@
+use serde::Serialize;
+
+use crate::tera::{
+    tera_var, try_render_template, CHANGELOG_VAR, PACKAGE_VAR, RELEASES_VAR, VERSION_VAR,
+};
+
+const TAG_VAR: &str = "tag";
+
+/// Single GitHub/Gitea/GitLab release containing all the packages released in the same run.
+/// The git tags of the packages are created anyway.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CombinedGitRelease {
+    /// Tera template of the git tag of the release.
+    tag_template: Option<String>,
+    /// Tera template of the release name.
+    name_template: Option<String>,
+    /// Tera template of the release body.
+    body_template: Option<String>,
+}
+
+/// Package included in the combined release.
+#[derive(Serialize)]
+pub(crate) struct CombinedPackage<'a> {
+    pub package: &'a str,
+    pub version: String,
+    pub tag: &'a str,
+    pub changelog: &'a str,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RenderedRelease {
+    pub tag: String,
+    pub name: String,
+    pub body: String,
+    /// Message of the git tag.
+    pub message: String,
+}
+
+impl CombinedGitRelease {
+    pub fn set_tag_template(mut self, tag_template: Option<String>) -> Self {
+        self.tag_template = tag_template;
+        self
+    }
+
+    pub fn set_name_template(mut self, name_template: Option<String>) -> Self {
+        self.name_template = name_template;
+        self
+    }
+
+    pub fn set_body_template(mut self, body_template: Option<String>) -> Self {
+        self.body_template = body_template;
+        self
+    }
+
+    /// Render the tag, name and body of the release, and the message of its tag.
+    /// The `version` variable contains the version of the first package.
+    /// The tag message is rendered from the `git_tag_message` template, if any.
+    pub(crate) fn render(
+        &self,
+        packages: &[CombinedPackage],
+        tag_message_template: Option<&str>,
+    ) -> anyhow::Result<RenderedRelease> {
+        let mut context = tera::Context::new();
+        let version = packages
+            .first()
+            .map(|p| p.version.as_str())
+            .unwrap_or_default();
+        context.insert(VERSION_VAR, version);
+        context.insert(RELEASES_VAR, packages);
+
+        let default_tag_template = format!("v{}", tera_var(VERSION_VAR));
+        let tag_template = self
+            .tag_template
+            .as_deref()
+            .unwrap_or(&default_tag_template);
+        let tag = try_render_template(tag_template, &context, "combined_release_tag")?;
+        context.insert(TAG_VAR, &tag);
+
+        let default_name_template = tera_var(TAG_VAR);
+        let name_template = self
+            .name_template
+            .as_deref()
+            .unwrap_or(&default_name_template);
+        let name = try_render_template(name_template, &context, "combined_release_name")?;
+
+        let default_body_template = format!(
+            "{{% for release in {RELEASES_VAR} %}}## `{{{{ release.package }}}}` - {{{{ release.version }}}}\n\n{{{{ release.changelog }}}}\n\n{{% endfor %}}"
+        );
+        let body_template = self
+            .body_template
+            .as_deref()
+            .unwrap_or(&default_body_template);
+        let body = try_render_template(body_template, &context, "combined_release_body")?
+            .trim_end()
+            .to_string();
+
+        // The `package` and `changelog` variables let the tag message template
+        // of the packages render the combined tag too.
+        let package = packages.first().map(|p| p.package).unwrap_or_default();
+        context.insert(PACKAGE_VAR, package);
+        context.insert(CHANGELOG_VAR, &body);
+        let message = match tag_message_template {
+            Some(template) => {
+                try_render_template(template, &context, "combined_release_tag_message")?
+            }
+            None => name.clone(),
+        };
+
+        Ok(RenderedRelease {
+            tag,
+            name,
+            body,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages() -> Vec<CombinedPackage<'static>> {
+        vec![
+            CombinedPackage {
+                package: "a",
+                version: "1.2.0".to_string(),
+                tag: "a-v1.2.0",
+                changelog: "### Added\n\n- new feature",
+            },
+            CombinedPackage {
+                package: "b",
+                version: "1.2.0".to_string(),
+                tag: "b-v1.2.0",
+                changelog: "### Fixed\n\n- fix bug",
+            },
+        ]
+    }
+
+    #[test]
+    fn default_combined_release_is_rendered() {
+        let release = CombinedGitRelease::default()
+            .render(&packages(), None)
+            .unwrap();
+        assert_eq!(release.tag, "v1.2.0");
+        assert_eq!(release.name, "v1.2.0");
+        assert_eq!(release.message, "v1.2.0");
+        expect_test::expect![[r#"
+            ## `a` - 1.2.0
+
+            ### Added
+
+            - new feature
+
+            ## `b` - 1.2.0
+
+            ### Fixed
+
+            - fix bug"#]]
+        .assert_eq(&release.body);
+    }
+
+    #[test]
+    fn custom_combined_release_is_rendered() {
+        let release = CombinedGitRelease::default()
+            .set_tag_template(Some("release-{{ version }}".to_string()))
+            .set_name_template(Some("Release {{ tag }}".to_string()))
+            .set_body_template(Some(
+                "{% for release in releases %}- {{ release.tag }}\n{% endfor %}".to_string(),
+            ))
+            .render(&packages(), None)
+            .unwrap();
+        assert_eq!(
+            release,
+            RenderedRelease {
+                tag: "release-1.2.0".to_string(),
+                name: "Release release-1.2.0".to_string(),
+                body: "- a-v1.2.0\n- b-v1.2.0".to_string(),
+                message: "Release release-1.2.0".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn combined_tag_message_is_rendered() {
+        let release = CombinedGitRelease::default()
+            .render(
+                &packages(),
+                Some("chore: Release package {{ package }} version {{ version }} ({{ tag }})"),
+            )
+            .unwrap();
+        assert_eq!(
+            release.message,
+            "chore: Release package a version 1.2.0 (v1.2.0)"
+        );
+    }
+
+    #[test]
+    fn invalid_combined_tag_message_errors() {
+        let release = CombinedGitRelease::default().render(&packages(), Some("{{ tag"));
+        assert!(release.is_err());
+    }
+}
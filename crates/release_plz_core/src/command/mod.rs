@@ -2,7 +2,9 @@ mod release;
 mod release_pr;
 pub mod set_version;
 mod update;
+mod yank;
 
 pub use release::*;
 pub use release_pr::*;
 pub use update::*;
+pub use yank::*;
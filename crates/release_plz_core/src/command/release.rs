@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 
 use anyhow::Context;
 use cargo::util::VersionExt;
@@ -8,18 +11,35 @@ use cargo_metadata::{
     Metadata, Package,
 };
 use crates_index::{GitIndex, SparseIndex};
+use futures::StreamExt as _;
 use git_cmd::Repo;
+use itertools::Itertools as _;
 use secrecy::{ExposeSecret, SecretString};
 use serde::Serialize;
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 use url::Url;
 
 use crate::{
     cargo::{is_published, run_cargo, wait_until_published, CargoIndex, CargoRegistry, CmdOutput},
     changelog_parser,
-    git::backend::GitClient,
+    combined_release::{CombinedGitRelease, CombinedPackage},
+    git::backend::{GitClient, RemoteRelease},
+    next_ver::should_check_semver,
+    notification::{send_notifications, Notification, NotifiedPackage},
     pr_parser::{prs_from_text, Pr},
-    release_order::release_order,
+    publish_integrity::{verify_published_package, IntegrityCheck},
+    publish_policy::{crate_sizes, PublishPolicy},
+    publish_retry::{is_publish_successful, publish_with_retry, PublishRetry},
+    release_assets::{find_release_assets, ReleaseAsset},
+    release_hooks::{run_hook, HookKind, ReleaseHooks},
+    release_journal::{ReleaseJournal, ReleaseStep},
+    release_order::release_levels,
+    release_plan::{
+        CombinedGitReleasePlan, GitReleasePlan, PackageReleasePlan, RegistryPlan, ReleasePlan,
+        TagPlan,
+    },
+    release_trigger::{is_release_commit, ReleaseTrigger},
+    semver_check::{self, SemverCheck, SemverCheckOutcome},
     GitBackend, PackagePath, Project, ReleaseMetadata, ReleaseMetadataBuilder, Remote,
     CHANGELOG_FILENAME, DEFAULT_BRANCH_PREFIX,
 };
@@ -37,6 +57,11 @@ pub struct ReleaseRequest {
     token: Option<SecretString>,
     /// Perform all checks without uploading.
     dry_run: bool,
+    /// If true, only resume the releases that were interrupted in a previous run.
+    resume: bool,
+    /// Where the release journal is saved.
+    /// If [`Option::None`], it's saved in the target directory.
+    release_journal_path: Option<Utf8PathBuf>,
     /// If true, release on every commit.
     /// If false, release only on Release PR merge.
     release_always: bool,
@@ -50,8 +75,24 @@ pub struct ReleaseRequest {
     packages_config: PackagesConfig,
     /// publish timeout
     publish_timeout: Duration,
+    /// Maximum number of packages released at the same time.
+    /// Only packages that don't depend on each other are released concurrently.
+    publish_concurrency: usize,
+    /// If true, publish all the packages with a single `cargo publish` invocation,
+    /// if the cargo version supports it.
+    publish_workspace: bool,
     /// PR Branch Prefix
     branch_prefix: String,
+    /// Webhooks called after the release.
+    notifications: Vec<Notification>,
+    /// How to decide whether to release if `release_always` is false.
+    release_trigger: ReleaseTrigger,
+    /// If set, create a single git release for all the released packages,
+    /// instead of one git release per package.
+    combined_git_release: Option<CombinedGitRelease>,
+    /// If true, the packages are released from a maintenance branch (e.g. `1.x`),
+    /// so the git releases are never marked as the latest release.
+    maintenance_branch: bool,
 }
 
 impl ReleaseRequest {
@@ -62,12 +103,20 @@ impl ReleaseRequest {
             registry: None,
             token: None,
             dry_run: false,
+            resume: false,
+            release_journal_path: None,
             git_release: None,
             repo_url: None,
             packages_config: PackagesConfig::default(),
             publish_timeout: minutes_30,
+            publish_concurrency: 1,
+            publish_workspace: false,
             release_always: true,
             branch_prefix: DEFAULT_BRANCH_PREFIX.to_string(),
+            notifications: vec![],
+            release_trigger: ReleaseTrigger::default(),
+            combined_git_release: None,
+            maintenance_branch: false,
         }
     }
 
@@ -76,6 +125,44 @@ impl ReleaseRequest {
         cargo_utils::workspace_manifest(&self.metadata)
     }
 
+    pub(crate) fn project(&self) -> anyhow::Result<Project> {
+        let overrides = self.packages_config.overrides.keys().cloned().collect();
+        Project::new(
+            &self.local_manifest(),
+            None,
+            &overrides,
+            &self.metadata,
+            self,
+        )
+    }
+
+    pub(crate) fn workspace_root(&self) -> &Utf8Path {
+        &self.metadata.workspace_root
+    }
+
+    pub(crate) fn registry(&self) -> Option<&str> {
+        self.registry.as_deref()
+    }
+
+    pub(crate) fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub(crate) fn publish_timeout(&self) -> Duration {
+        self.publish_timeout
+    }
+
+    /// Where the release journal is saved.
+    /// By default, it's in the target directory, so that it's not committed.
+    pub fn release_journal_path(&self) -> Utf8PathBuf {
+        self.release_journal_path.clone().unwrap_or_else(|| {
+            self.metadata
+                .target_directory
+                .join("release-plz")
+                .join("release-journal.json")
+        })
+    }
+
     pub fn with_registry(mut self, registry: impl Into<String>) -> Self {
         self.registry = Some(registry.into());
         self
@@ -91,6 +178,16 @@ impl ReleaseRequest {
         self
     }
 
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    pub fn with_release_journal_path(mut self, release_journal_path: Utf8PathBuf) -> Self {
+        self.release_journal_path = Some(release_journal_path);
+        self
+    }
+
     pub fn with_git_release(mut self, git_release: GitRelease) -> Self {
         self.git_release = Some(git_release);
         self
@@ -111,11 +208,48 @@ impl ReleaseRequest {
         self
     }
 
+    /// Set the maximum number of packages released at the same time.
+    /// Values lower than `1` are treated as `1`.
+    pub fn with_publish_concurrency(mut self, publish_concurrency: usize) -> Self {
+        self.publish_concurrency = publish_concurrency.max(1);
+        self
+    }
+
+    pub fn with_publish_workspace(mut self, publish_workspace: bool) -> Self {
+        self.publish_workspace = publish_workspace;
+        self
+    }
+
     pub fn with_release_always(mut self, release_always: bool) -> Self {
         self.release_always = release_always;
         self
     }
 
+    pub fn with_release_trigger(mut self, release_trigger: ReleaseTrigger) -> Self {
+        self.release_trigger = release_trigger;
+        self
+    }
+
+    pub fn with_combined_git_release(mut self, combined_git_release: CombinedGitRelease) -> Self {
+        self.combined_git_release = Some(combined_git_release);
+        self
+    }
+
+    pub fn with_maintenance_branch(mut self, maintenance_branch: bool) -> Self {
+        self.maintenance_branch = maintenance_branch;
+        self
+    }
+
+    /// Whether the git release should be marked as the latest release.
+    /// Releases of maintenance branches are never the latest release.
+    fn is_latest_release(&self, release_config: &GitReleaseConfig) -> Option<bool> {
+        if self.maintenance_branch {
+            Some(false)
+        } else {
+            release_config.latest
+        }
+    }
+
     pub fn with_branch_prefix(mut self, pr_branch_prefix: Option<String>) -> Self {
         if let Some(branch_prefix) = pr_branch_prefix {
             self.branch_prefix = branch_prefix;
@@ -123,6 +257,11 @@ impl ReleaseRequest {
         self
     }
 
+    pub fn with_notifications(mut self, notifications: Vec<Notification>) -> Self {
+        self.notifications = notifications;
+        self
+    }
+
     /// Set release config for a specific package.
     pub fn with_package_config(
         mut self,
@@ -151,7 +290,23 @@ impl ReleaseRequest {
         config.publish.enabled
     }
 
+    /// Whether to create the git release of the package.
+    /// If the combined git release is enabled, the package doesn't have its own git release.
     fn is_git_release_enabled(&self, package: &str) -> bool {
+        self.is_in_git_release(package) && self.combined_git_release.is_none()
+    }
+
+    /// Whether the package is included in a git release,
+    /// either its own or the combined one.
+    /// Bitbucket doesn't have git releases, so only the git tag is created.
+    fn is_in_git_release(&self, package: &str) -> bool {
+        if self
+            .git_release
+            .as_ref()
+            .is_some_and(|r| matches!(r.backend, GitBackend::Bitbucket(_)))
+        {
+            return false;
+        }
         let config = self.get_package_config(package);
         config.git_release.enabled
     }
@@ -165,6 +320,27 @@ impl ReleaseRequest {
         self.packages_config.get(package)
     }
 
+    /// Config of the combined git release.
+    /// The combined git release doesn't belong to a package,
+    /// so it uses the `[workspace]` config, ignoring the package overrides.
+    fn combined_release_config(&self) -> &ReleaseConfig {
+        &self.packages_config.default
+    }
+
+    /// Template of the message of the combined git tag.
+    fn combined_tag_message_template(&self) -> Option<&str> {
+        self.combined_release_config()
+            .git_tag
+            .message_template
+            .as_deref()
+    }
+
+    /// Whether the package must stay in the release journal until
+    /// the combined git release is created.
+    fn is_in_combined_git_release(&self, package: &str) -> bool {
+        self.combined_git_release.is_some() && self.is_in_git_release(package)
+    }
+
     pub fn allow_dirty(&self, package: &str) -> bool {
         let config = self.get_package_config(package);
         config.allow_dirty
@@ -186,7 +362,10 @@ impl ReleaseRequest {
     }
 
     /// Find the token to use for the given `registry` ([`Option::None`] means crates.io).
-    fn find_registry_token(&self, registry: Option<&str>) -> anyhow::Result<Option<SecretString>> {
+    pub(crate) fn find_registry_token(
+        &self,
+        registry: Option<&str>,
+    ) -> anyhow::Result<Option<SecretString>> {
         let is_registry_same_as_request = self.registry.as_deref() == registry;
         let token = is_registry_same_as_request
             .then(|| self.token.clone())
@@ -257,6 +436,16 @@ pub struct ReleaseConfig {
     /// Whether this package has a changelog that release-plz updates or not.
     /// Default: `true`.
     changelog_update: bool,
+    /// Commands to run around the release steps.
+    hooks: ReleaseHooks,
+    /// Run cargo-semver-checks against crates.io before publishing the package.
+    /// Default: `false`.
+    semver_check: bool,
+    /// Rules that the packaged files must respect before running `cargo publish`.
+    publish_policy: PublishPolicy,
+    /// If set, verify that the published package is the package built locally,
+    /// before creating the git tag.
+    integrity_check: Option<IntegrityCheck>,
 }
 
 impl ReleaseConfig {
@@ -310,6 +499,26 @@ impl ReleaseConfig {
         self
     }
 
+    pub fn with_hooks(mut self, hooks: ReleaseHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    pub fn with_semver_check(mut self, semver_check: bool) -> Self {
+        self.semver_check = semver_check;
+        self
+    }
+
+    pub fn with_publish_policy(mut self, publish_policy: PublishPolicy) -> Self {
+        self.publish_policy = publish_policy;
+        self
+    }
+
+    pub fn with_integrity_check(mut self, integrity_check: IntegrityCheck) -> Self {
+        self.integrity_check = Some(integrity_check);
+        self
+    }
+
     pub fn publish(&self) -> &PublishConfig {
         &self.publish
     }
@@ -317,6 +526,10 @@ impl ReleaseConfig {
     pub fn git_release(&self) -> &GitReleaseConfig {
         &self.git_release
     }
+
+    pub fn publish_policy(&self) -> &PublishPolicy {
+        &self.publish_policy
+    }
 }
 
 impl Default for ReleaseConfig {
@@ -332,6 +545,10 @@ impl Default for ReleaseConfig {
             release: true,
             changelog_path: None,
             changelog_update: true,
+            hooks: ReleaseHooks::default(),
+            semver_check: false,
+            publish_policy: PublishPolicy::default(),
+            integrity_check: None,
         }
     }
 }
@@ -365,6 +582,18 @@ pub enum ReleaseType {
     Auto,
 }
 
+/// What to do when the git release of the tag exists already.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GitReleaseUpdate {
+    /// Update name, body, draft, pre-release and latest fields of the existing release.
+    #[default]
+    Overwrite,
+    /// Leave the existing release as it is.
+    Skip,
+    /// Return an error.
+    Fail,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitReleaseConfig {
     enabled: bool,
@@ -373,6 +602,11 @@ pub struct GitReleaseConfig {
     release_type: ReleaseType,
     name_template: Option<String>,
     body_template: Option<String>,
+    /// Glob patterns of the files to upload to the release, relative to the workspace root.
+    assets: Vec<String>,
+    /// If true, upload a `<file>.sha256` checksum for each asset.
+    assets_checksum: bool,
+    update: GitReleaseUpdate,
 }
 
 impl Default for GitReleaseConfig {
@@ -390,6 +624,9 @@ impl GitReleaseConfig {
             release_type: ReleaseType::default(),
             name_template: None,
             body_template: None,
+            assets: vec![],
+            assets_checksum: false,
+            update: GitReleaseUpdate::default(),
         }
     }
 
@@ -422,6 +659,21 @@ impl GitReleaseConfig {
         self
     }
 
+    pub fn set_assets(mut self, assets: Vec<String>) -> Self {
+        self.assets = assets;
+        self
+    }
+
+    pub fn set_assets_checksum(mut self, assets_checksum: bool) -> Self {
+        self.assets_checksum = assets_checksum;
+        self
+    }
+
+    pub fn set_update(mut self, update: GitReleaseUpdate) -> Self {
+        self.update = update;
+        self
+    }
+
     pub fn is_pre_release(&self, version: &Version) -> bool {
         match self.release_type {
             ReleaseType::Pre => true,
@@ -435,6 +687,10 @@ impl GitReleaseConfig {
 pub struct GitTagConfig {
     enabled: bool,
     name_template: Option<String>,
+    /// Tera template of the git tag message.
+    message_template: Option<String>,
+    /// If true, sign the git tag with the key configured in git.
+    sign: bool,
 }
 
 impl Default for GitTagConfig {
@@ -448,6 +704,8 @@ impl GitTagConfig {
         Self {
             enabled,
             name_template: None,
+            message_template: None,
+            sign: false,
         }
     }
 
@@ -456,6 +714,16 @@ impl GitTagConfig {
         self
     }
 
+    pub fn set_message_template(mut self, message_template: Option<String>) -> Self {
+        self.message_template = message_template;
+        self
+    }
+
+    pub fn set_sign(mut self, sign: bool) -> Self {
+        self.sign = sign;
+        self
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
@@ -470,6 +738,9 @@ pub struct GitRelease {
 #[derive(Serialize, Default)]
 pub struct Release {
     releases: Vec<PackageRelease>,
+    /// What the release would do. Only computed in dry run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan: Option<ReleasePlan>,
 }
 
 #[derive(Serialize)]
@@ -482,22 +753,50 @@ pub struct PackageRelease {
     /// the tag by themselves.
     tag: String,
     version: Version,
+    /// Changelog of the released version.
+    changelog: String,
+    /// Registries where the package was published.
+    /// `crates.io` is used for the default registry.
+    registries: Vec<String>,
+    /// Url of the GitHub/Gitea/GitLab release.
+    git_release_url: Option<String>,
+    /// Commit released.
+    commit: String,
+    /// Whether the version is a pre-release, according to the `git_release_type` config.
+    pre_release: bool,
+    /// Outcome of cargo-semver-checks.
+    semver_check: SemverCheckOutcome,
+    /// Steps completed while resuming a release interrupted in a previous run.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    resumed_steps: Vec<ReleaseStep>,
+}
+
+impl Release {
+    pub fn plan(&self) -> Option<&ReleasePlan> {
+        self.plan.as_ref()
+    }
+
+    fn notified_packages(&self) -> Vec<NotifiedPackage<'_>> {
+        self.releases
+            .iter()
+            .map(|r| NotifiedPackage {
+                package: &r.package_name,
+                version: r.version.to_string(),
+                tag: &r.tag,
+                changelog: &r.changelog,
+                prs: &r.prs,
+            })
+            .collect()
+    }
 }
 
 /// Release the project as it is.
 #[instrument(skip(input))]
 pub async fn release(input: &ReleaseRequest) -> anyhow::Result<Option<Release>> {
-    let overrides = input.packages_config.overrides.keys().cloned().collect();
-    let project = Project::new(
-        &input.local_manifest(),
-        None,
-        &overrides,
-        &input.metadata,
-        input,
-    )?;
+    let project = input.project()?;
     let repo = Repo::new(&input.metadata.workspace_root)?;
     let git_client = get_git_client(input)?;
-    let should_release = should_release(input, &repo, &git_client).await?;
+    let should_release = should_release(input, &project, &repo, &git_client).await?;
     if should_release == ShouldRelease::No {
         return Ok(None);
     }
@@ -522,6 +821,12 @@ pub async fn release(input: &ReleaseRequest) -> anyhow::Result<Option<Release>>
         }
     }
 
+    if let Ok(Some(release)) = &release {
+        if !input.dry_run {
+            send_notifications(&input.notifications, &release.notified_packages()).await;
+        }
+    }
+
     release
 }
 
@@ -532,41 +837,640 @@ async fn release_packages(
     git_client: &GitClient,
 ) -> anyhow::Result<Option<Release>> {
     let packages = project.publishable_packages();
-    let release_order = release_order(&packages).context("cannot determine release order")?;
+    let release_levels = release_levels(&packages).context("cannot determine release order")?;
+    let journal = if input.dry_run {
+        ReleaseJournal::default()
+    } else {
+        ReleaseJournal::load(input.release_journal_path())
+            .context("failed to load release journal")?
+    };
+    let plan = if input.dry_run {
+        let plan = release_plan(input, project, repo, git_client, &packages)
+            .await
+            .context("failed to compute the release plan")?;
+        Some(plan)
+    } else {
+        None
+    };
+    if let Some(combined) = &input.combined_git_release {
+        check_combined_git_release(input, project, combined, &packages)
+            .context("invalid combined git release")?;
+    }
+    let published_with_workspace = publish_workspace(input, project, repo, &packages, &journal)
+        .await
+        .context("failed to publish the workspace")?;
     let mut package_releases: Vec<PackageRelease> = vec![];
-    for package in release_order {
-        if let Some(pkg_release) =
-            release_package_if_needed(input, project, package, repo, git_client).await?
-        {
-            package_releases.push(pkg_release);
+    for level in release_levels {
+        // Packages of the same level don't depend on each other, so we can release them concurrently.
+        // `buffered` returns the results in the same order of the packages,
+        // so the release output is deterministic.
+        let level_results: Vec<anyhow::Result<Option<PackageRelease>>> =
+            futures::stream::iter(level)
+                .map(|package| {
+                    let is_published_with_workspace =
+                        published_with_workspace.contains(&package.name);
+                    release_package_if_needed(
+                        input,
+                        project,
+                        package,
+                        repo,
+                        git_client,
+                        &journal,
+                        is_published_with_workspace,
+                    )
+                })
+                .buffered(input.publish_concurrency)
+                .collect()
+                .await;
+
+        // Wait for all the packages of the level before returning an error,
+        // so that we don't abandon packages in the middle of their release.
+        let mut first_error = None;
+        for result in level_results {
+            match result {
+                Ok(Some(pkg_release)) => package_releases.push(pkg_release),
+                Ok(None) => {}
+                Err(e) if first_error.is_none() => first_error = Some(e),
+                Err(e) => error!("{e:?}"),
+            }
+        }
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+    }
+    if let Some(combined) = &input.combined_git_release {
+        if !input.dry_run {
+            release_combined(input, repo, git_client, combined, &mut package_releases)
+                .await
+                .context("failed to create the combined git release")?;
+            for package in &packages {
+                journal.complete(package)?;
+            }
         }
     }
-    let release = (!package_releases.is_empty()).then_some(Release {
+    let release = (!package_releases.is_empty() || plan.is_some()).then_some(Release {
         releases: package_releases,
+        plan,
     });
     Ok(release)
 }
 
+/// Render the combined git release before publishing, so that an invalid template
+/// doesn't stop the release after the packages are published.
+fn check_combined_git_release(
+    input: &ReleaseRequest,
+    project: &Project,
+    combined: &CombinedGitRelease,
+    packages: &[&Package],
+) -> anyhow::Result<()> {
+    let tags: Vec<String> = packages
+        .iter()
+        .map(|p| project.git_tag(&p.name, &p.version.to_string()))
+        .collect();
+    let packages: Vec<CombinedPackage> = packages
+        .iter()
+        .zip(&tags)
+        .map(|(p, tag)| CombinedPackage {
+            package: &p.name,
+            version: p.version.to_string(),
+            tag,
+            changelog: "",
+        })
+        .collect();
+    combined.render(&packages, input.combined_tag_message_template())?;
+    Ok(())
+}
+
+/// Create the git tag and the git release containing all the `package_releases`.
+async fn release_combined(
+    input: &ReleaseRequest,
+    repo: &Repo,
+    git_client: &GitClient,
+    combined: &CombinedGitRelease,
+    package_releases: &mut [PackageRelease],
+) -> anyhow::Result<()> {
+    let packages: Vec<CombinedPackage> = package_releases
+        .iter()
+        .filter(|r| input.is_in_git_release(&r.package_name))
+        .map(|r| CombinedPackage {
+            package: &r.package_name,
+            version: r.version.to_string(),
+            tag: &r.tag,
+            changelog: &r.changelog,
+        })
+        .collect();
+    let Some(first_package) = packages.first() else {
+        return Ok(());
+    };
+    let rendered = combined.render(&packages, input.combined_tag_message_template())?;
+    let version = Version::parse(&first_package.version)?;
+
+    // The combined tag might be the tag of a package.
+    let release_config = input.combined_release_config();
+    if !repo.tag_exists(&rendered.tag)? {
+        if release_config.git_tag.sign {
+            repo.tag_signed(&rendered.tag, &rendered.message)?;
+        } else {
+            repo.tag(&rendered.tag, &rendered.message)?;
+        }
+        repo.push(&rendered.tag)?;
+    }
+
+    let release_config = &release_config.git_release;
+    let git_release_info = GitReleaseInfo {
+        git_tag: rendered.tag.clone(),
+        release_name: rendered.name,
+        release_body: rendered.body,
+        draft: release_config.draft,
+        latest: input.is_latest_release(release_config),
+        pre_release: release_config.is_pre_release(&version),
+    };
+    let git_release = git_client
+        .create_or_update_release(&git_release_info, release_config.update)
+        .await?;
+    info!("created combined git release of tag {}", rendered.tag);
+
+    let mut uploaded_assets = BTreeSet::new();
+    for package_release in package_releases.iter_mut() {
+        if !input.is_in_git_release(&package_release.package_name) {
+            continue;
+        }
+        let assets: Vec<ReleaseAsset> =
+            package_git_release_assets(input, &package_release.package_name)?
+                .into_iter()
+                .filter(|asset| uploaded_assets.insert(asset.name.clone()))
+                .collect();
+        if !assets.is_empty() {
+            git_client
+                .upload_release_assets(
+                    &git_release,
+                    &package_release.package_name,
+                    &package_release.version.to_string(),
+                    &assets,
+                )
+                .await?;
+        }
+        package_release.git_release_url = git_release.html_url().map(ToOwned::to_owned);
+    }
+    Ok(())
+}
+
+/// Compute what the release of the `packages` would do, without changing anything.
+async fn release_plan(
+    input: &ReleaseRequest,
+    project: &Project,
+    repo: &Repo,
+    git_client: &GitClient,
+    packages: &[&Package],
+) -> anyhow::Result<ReleasePlan> {
+    let mut plan = ReleasePlan::default();
+    if input.resume {
+        // Only interrupted releases are resumed, and dry runs don't interrupt releases.
+        return Ok(plan);
+    }
+    for package in packages {
+        let package_plan = package_release_plan(input, project, repo, git_client, package).await?;
+        plan.packages.push(package_plan);
+    }
+    if let Some(combined) = &input.combined_git_release {
+        plan.combined_git_release = Some(combined_git_release_plan(input, repo, combined, &plan)?);
+    }
+    Ok(plan)
+}
+
+fn combined_git_release_plan(
+    input: &ReleaseRequest,
+    repo: &Repo,
+    combined: &CombinedGitRelease,
+    plan: &ReleasePlan,
+) -> anyhow::Result<CombinedGitReleasePlan> {
+    let released: Vec<&PackageReleasePlan> = plan
+        .packages
+        .iter()
+        .filter(|p| !p.tag.exists && input.is_in_git_release(&p.package_name))
+        .collect();
+    let changelogs: Vec<String> = released
+        .iter()
+        .map(|p| {
+            let package = input
+                .metadata
+                .packages
+                .iter()
+                .find(|pkg| pkg.name == p.package_name);
+            package
+                .map(|package| last_changelog_entry(input, package))
+                .unwrap_or_default()
+        })
+        .collect();
+    let packages: Vec<CombinedPackage> = released
+        .iter()
+        .zip(&changelogs)
+        .map(|(p, changelog)| CombinedPackage {
+            package: &p.package_name,
+            version: p.version.to_string(),
+            tag: &p.tag.name,
+            changelog,
+        })
+        .collect();
+    let rendered = combined.render(&packages, input.combined_tag_message_template())?;
+    let tag_exists = repo.tag_exists(&rendered.tag)?;
+    Ok(CombinedGitReleasePlan {
+        tag: TagPlan {
+            create: !tag_exists,
+            exists: tag_exists,
+            name: rendered.tag,
+        },
+        name: rendered.name,
+        body: rendered.body,
+        packages: released.iter().map(|p| p.package_name.clone()).collect(),
+    })
+}
+
+async fn package_release_plan(
+    input: &ReleaseRequest,
+    project: &Project,
+    repo: &Repo,
+    git_client: &GitClient,
+    package: &Package,
+) -> anyhow::Result<PackageReleasePlan> {
+    let git_tag = project.git_tag(&package.name, &package.version.to_string());
+    let release_name = project.release_name(&package.name, &package.version.to_string());
+    // If the tag exists, the package is considered released.
+    let tag_exists = repo.tag_exists(&git_tag)?;
+
+    let registry_indexes = registry_indexes(package, input.registry.clone())
+        .context("can't determine registry indexes")?;
+    let mut registries = vec![];
+    for mut registry in registry_indexes {
+        let token = input.find_registry_token(registry.name.as_deref())?;
+        let already_published =
+            is_published(&mut registry.index, package, input.publish_timeout, &token)
+                .await
+                .context("can't determine if package is published")?;
+        registries.push(RegistryPlan {
+            name: registry_name(registry.name.as_deref()),
+            already_published,
+            publish: !tag_exists && !already_published && input.is_publish_enabled(&package.name),
+        });
+    }
+
+    let git_release = if input.is_git_release_enabled(&package.name) {
+        let create = !tag_exists;
+        let body = if create {
+            let changelog = last_changelog_entry(input, package);
+            let prs = prs_from_text(&changelog);
+            let release_info = ReleaseInfo {
+                package,
+                git_tag: &git_tag,
+                release_name: &release_name,
+                changelog: &changelog,
+                prs: &prs,
+            };
+            let remote = remote(&release_info, git_client).await;
+            release_body(input, package, &changelog, &remote)
+        } else {
+            String::new()
+        };
+        let release_config = input.get_package_config(&package.name).git_release;
+        let assets = git_release_assets(input, package)?
+            .into_iter()
+            .map(|asset| asset.name)
+            .collect();
+        Some(GitReleasePlan {
+            name: release_name.clone(),
+            body,
+            draft: release_config.draft,
+            pre_release: release_config.is_pre_release(&package.version),
+            assets,
+            create,
+        })
+    } else {
+        None
+    };
+
+    Ok(PackageReleasePlan {
+        package_name: package.name.clone(),
+        version: package.version.clone(),
+        registries,
+        tag: TagPlan {
+            name: git_tag,
+            exists: tag_exists,
+            create: !tag_exists && input.is_git_tag_enabled(&package.name),
+        },
+        git_release,
+    })
+}
+
+/// First cargo version able to publish several packages with a single `cargo publish`.
+const MIN_CARGO_VERSION_WORKSPACE_PUBLISH: Version = Version::new(1, 90, 0);
+
+/// If enabled, publish the packages that need to be published
+/// with a single `cargo publish --package a --package b` invocation.
+/// Cargo publishes the packages in the right order and waits for them to be in the index.
+///
+/// The publish steps of the published packages are recorded in the `journal`,
+/// so that the release of these packages is completed like an interrupted release.
+/// Return the names of the published packages.
+/// If the packages can't be published together, return an empty set,
+/// so that they are published one by one.
+async fn publish_workspace(
+    input: &ReleaseRequest,
+    project: &Project,
+    repo: &Repo,
+    packages: &[&Package],
+    journal: &ReleaseJournal,
+) -> anyhow::Result<BTreeSet<String>> {
+    if !input.publish_workspace || input.resume {
+        return Ok(BTreeSet::new());
+    }
+    match cargo_version(&input.metadata.workspace_root) {
+        Ok(version) if version >= MIN_CARGO_VERSION_WORKSPACE_PUBLISH => {}
+        Ok(version) => {
+            warn!("cargo {version} can't publish several packages at once, publishing packages one by one. Update cargo to {MIN_CARGO_VERSION_WORKSPACE_PUBLISH} or newer to publish them together");
+            return Ok(BTreeSet::new());
+        }
+        Err(e) => {
+            warn!("can't determine cargo version, publishing packages one by one: {e:?}");
+            return Ok(BTreeSet::new());
+        }
+    }
+
+    let mut registry_name = None;
+    let mut to_publish: Vec<(&Package, CargoRegistry)> = vec![];
+    for &package in packages {
+        if !input.is_publish_enabled(&package.name) || journal.is_interrupted(package) {
+            continue;
+        }
+        let git_tag = project.git_tag(&package.name, &package.version.to_string());
+        if repo.tag_exists(&git_tag)? {
+            continue;
+        }
+        let mut registries = registry_indexes(package, input.registry.clone())
+            .context("can't determine registry indexes")?;
+        if registries.len() != 1 {
+            info!(
+                "{} is published to more than one registry, publishing packages one by one",
+                package.name
+            );
+            return Ok(BTreeSet::new());
+        }
+        let mut registry = registries.remove(0);
+        match &registry_name {
+            None => registry_name = Some(registry.name.clone()),
+            Some(name) if name != &registry.name => {
+                info!("packages are published to different registries, publishing packages one by one");
+                return Ok(BTreeSet::new());
+            }
+            Some(_) => {}
+        }
+        let token = input.find_registry_token(registry.name.as_deref())?;
+        if is_published(&mut registry.index, package, input.publish_timeout, &token)
+            .await
+            .context("can't determine if package is published")?
+        {
+            continue;
+        }
+        to_publish.push((package, registry));
+    }
+    if to_publish.len() < 2 {
+        // Nothing to gain from publishing the packages together.
+        return Ok(BTreeSet::new());
+    }
+    let packages_to_publish: Vec<&Package> = to_publish.iter().map(|(p, _)| *p).collect();
+    if !have_same_publish_flags(input, &packages_to_publish) {
+        info!("packages have different `cargo publish` flags, publishing packages one by one");
+        return Ok(BTreeSet::new());
+    }
+    let registry = registry_name.flatten();
+
+    // Package the crates checked by the publish policy together, because
+    // `cargo package` can't find the dependencies that aren't published yet otherwise.
+    let packages_to_size: Vec<&Package> = packages_to_publish
+        .iter()
+        .copied()
+        .filter(|package| {
+            input
+                .get_package_config(&package.name)
+                .publish_policy
+                .checks_size(registry.as_deref())
+        })
+        .collect();
+    let sizes =
+        crate_sizes(&packages_to_size).context("cannot determine the size of the packages")?;
+    for package in &packages_to_publish {
+        input
+            .get_package_config(&package.name)
+            .publish_policy
+            .check_with_sizes(package, registry.as_deref(), &sizes)?;
+    }
+    for package in &packages_to_publish {
+        let git_tag = project.git_tag(&package.name, &package.version.to_string());
+        let release_name = project.release_name(&package.name, &package.version.to_string());
+        let changelog = last_changelog_entry(input, package);
+        let release_info = ReleaseInfo {
+            package,
+            git_tag: &git_tag,
+            release_name: &release_name,
+            changelog: &changelog,
+            prs: &[],
+        };
+        if run_release_hook(
+            input,
+            &release_info,
+            HookKind::PrePublish,
+            registry.as_deref(),
+        )
+        .await?
+        {
+            // If the packages are published one by one, the hook isn't run again.
+            journal.record(
+                package,
+                ReleaseStep::PrePublishHook {
+                    registry: registry.clone(),
+                },
+            )?;
+        }
+    }
+
+    let names = packages_to_publish
+        .iter()
+        .map(|p| p.name.as_str())
+        .join(", ");
+    info!("publishing {names} with a single cargo publish");
+    let args = cargo_publish_workspace_args(input, &packages_to_publish, registry.as_deref())?;
+    let workspace_root = input.metadata.workspace_root.clone();
+    let output = tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_cargo(&workspace_root, &args)
+    })
+    .await
+    .context("cargo publish task failed")??;
+    let is_success = is_publish_successful(&output);
+    if !is_success {
+        // Some packages might have been uploaded anyway.
+        // The other packages are published one by one.
+        warn!(
+            "failed to publish {names} together, publishing them one by one: {}",
+            output.stderr
+        );
+    }
+
+    let mut published = BTreeSet::new();
+    for (package, mut registry) in to_publish {
+        if input.dry_run {
+            if is_success {
+                // The journal isn't saved in dry run, so this only skips the publish step.
+                journal.record(
+                    package,
+                    ReleaseStep::Publish {
+                        registry: registry.name.clone(),
+                    },
+                )?;
+                published.insert(package.name.clone());
+            }
+            continue;
+        }
+        let token = input.find_registry_token(registry.name.as_deref())?;
+        if is_published(&mut registry.index, package, input.publish_timeout, &token).await? {
+            journal.record(
+                package,
+                ReleaseStep::Publish {
+                    registry: registry.name.clone(),
+                },
+            )?;
+            journal.record(
+                package,
+                ReleaseStep::Wait {
+                    registry: registry.name.clone(),
+                },
+            )?;
+            published.insert(package.name.clone());
+        }
+    }
+    Ok(published)
+}
+
+/// Return true if the packages can be published with the same `cargo publish` flags.
+fn have_same_publish_flags(input: &ReleaseRequest, packages: &[&Package]) -> bool {
+    packages
+        .iter()
+        .map(|p| {
+            let has_features = !input.features(&p.name).is_empty() || input.all_features(&p.name);
+            (
+                input.allow_dirty(&p.name),
+                input.no_verify(&p.name),
+                has_features,
+            )
+        })
+        .all_equal_value()
+        .is_ok_and(|(_, _, has_features)| !has_features)
+}
+
+fn cargo_publish_workspace_args(
+    input: &ReleaseRequest,
+    packages: &[&Package],
+    registry: Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+    let manifest_path = input.local_manifest();
+    let mut args = vec![
+        "publish",
+        "--color",
+        "always",
+        "--manifest-path",
+        manifest_path.as_str(),
+    ];
+    for package in packages {
+        args.push("--package");
+        args.push(&package.name);
+    }
+    if let Some(registry) = registry {
+        args.push("--registry");
+        args.push(registry);
+    }
+    let token = input.find_registry_token(registry)?;
+    if let Some(token) = &token {
+        args.push("--token");
+        args.push(token.expose_secret());
+    } else {
+        verify_ci_cargo_registry_token()?;
+    }
+    if input.dry_run {
+        args.push("--dry-run");
+    }
+    // All the packages have the same flags.
+    let package = packages.first().context("no package to publish")?;
+    if input.allow_dirty(&package.name) {
+        args.push("--allow-dirty");
+    }
+    if input.no_verify(&package.name) {
+        args.push("--no-verify");
+    }
+    Ok(args.into_iter().map(ToOwned::to_owned).collect())
+}
+
+fn cargo_version(root: &Utf8Path) -> anyhow::Result<Version> {
+    let output = run_cargo(root, &["--version"])?;
+    parse_cargo_version(&output.stdout)
+}
+
+/// Parse the output of `cargo --version`, e.g. `cargo 1.90.0 (840b83a10 2025-07-30)`.
+fn parse_cargo_version(output: &str) -> anyhow::Result<Version> {
+    let version = output
+        .split_whitespace()
+        .nth(1)
+        .with_context(|| format!("unexpected cargo version output: {output}"))?;
+    let version =
+        Version::parse(version).with_context(|| format!("can't parse cargo version {version}"))?;
+    // Ignore the pre-release, so that nightly versions are considered equal to the stable one.
+    Ok(Version::new(version.major, version.minor, version.patch))
+}
+
 async fn release_package_if_needed(
     input: &ReleaseRequest,
     project: &Project,
     package: &Package,
     repo: &Repo,
     git_client: &GitClient,
+    journal: &ReleaseJournal,
+    is_published_with_workspace: bool,
 ) -> anyhow::Result<Option<PackageRelease>> {
     let git_tag = project.git_tag(&package.name, &package.version.to_string());
     let release_name = project.release_name(&package.name, &package.version.to_string());
-    if repo.tag_exists(&git_tag)? {
+    // Packages published with the workspace have their publish steps in the journal,
+    // so we complete their release like interrupted releases.
+    let is_interrupted = journal.is_interrupted(package) && !is_published_with_workspace;
+    let has_journal_steps = is_interrupted || is_published_with_workspace;
+    if is_published_with_workspace {
+        debug!(
+            "{} {}: published with the workspace",
+            package.name, package.version
+        );
+    } else if is_interrupted {
+        info!(
+            "{} {}: resuming release interrupted in a previous run",
+            package.name, package.version
+        );
+    } else if input.resume {
+        debug!(
+            "{} {}: no interrupted release to resume",
+            package.name, package.version
+        );
+        return Ok(None);
+    } else if repo.tag_exists(&git_tag)? {
         info!(
             "{} {}: Already published - Tag {} already exists",
             package.name, package.version, &git_tag
         );
         return Ok(None);
     }
+    let steps_before_release = journal.steps(package);
 
     let registry_indexes = registry_indexes(package, input.registry.clone())
         .context("can't determine registry indexes")?;
     let mut package_was_released = false;
+    let mut published_registries = vec![];
+    let mut git_release = None;
+    let mut semver_check = SemverCheck::Skipped;
     let changelog = last_changelog_entry(input, package);
     let prs = prs_from_text(&changelog);
     let release_info = ReleaseInfo {
@@ -576,31 +1480,109 @@ async fn release_package_if_needed(
         changelog: &changelog,
         prs: &prs,
     };
-    for CargoRegistry { name, mut index } in registry_indexes {
-        let token = input.find_registry_token(name.as_deref())?;
-        if is_published(&mut index, package, input.publish_timeout, &token)
+    for mut registry in registry_indexes {
+        let token = input.find_registry_token(registry.name.as_deref())?;
+        if is_published(&mut registry.index, package, input.publish_timeout, &token)
             .await
             .context("can't determine if package is published")?
         {
-            info!("{} {}: already published", package.name, package.version);
-            continue;
+            if !has_journal_steps {
+                info!("{} {}: already published", package.name, package.version);
+                continue;
+            }
+            // The registry already has the package, even if the journal might not know it yet.
+            for step in [
+                ReleaseStep::Publish {
+                    registry: registry.name.clone(),
+                },
+                ReleaseStep::Wait {
+                    registry: registry.name.clone(),
+                },
+            ] {
+                if !journal.is_done(package, &step) {
+                    journal.record(package, step)?;
+                }
+            }
         }
-        let package_was_released_at_index =
-            release_package(&mut index, input, repo, git_client, &release_info, &token)
-                .await
-                .context("failed to release package")?;
+        if registry.name.is_none()
+            && !input.dry_run
+            && !journal.is_done(
+                package,
+                &ReleaseStep::Publish {
+                    registry: registry.name.clone(),
+                },
+            )
+        {
+            semver_check = release_semver_check(input, package).await;
+        }
+        let outcome = release_package(
+            &mut registry,
+            input,
+            repo,
+            git_client,
+            &release_info,
+            &token,
+            journal,
+        )
+        .await
+        .context("failed to release package")?;
 
-        if package_was_released_at_index {
+        if let Some(outcome) = outcome {
             package_was_released = true;
+            if input.is_publish_enabled(&package.name) {
+                published_registries.push(registry_name(registry.name.as_deref()));
+            }
+            if outcome.git_release.is_some() {
+                git_release = outcome.git_release;
+            }
         }
     }
-    let package_release = package_was_released.then_some(PackageRelease {
+    let resumed_steps = if is_interrupted {
+        journal
+            .steps(package)
+            .difference(&steps_before_release)
+            .cloned()
+            .collect()
+    } else {
+        vec![]
+    };
+    for step in &resumed_steps {
+        info!("{} {}: {step} (resumed)", package.name, package.version);
+    }
+    if package_was_released && !input.dry_run && input.is_in_combined_git_release(&package.name) {
+        // If the combined git release fails, the next run resumes the package
+        // to include it in the combined git release.
+        if !journal.is_done(package, &ReleaseStep::Released) {
+            journal.record(package, ReleaseStep::Released)?;
+        }
+    } else {
+        journal.complete(package)?;
+    }
+    if !package_was_released {
+        return Ok(None);
+    }
+    let commit = match repo.get_tag_commit(&git_tag) {
+        Some(commit) => commit,
+        // The git tag is disabled.
+        None => repo.current_commit_hash()?,
+    };
+    let pre_release = input
+        .get_package_config(&package.name)
+        .git_release
+        .is_pre_release(&package.version);
+    Ok(Some(PackageRelease {
         package_name: package.name.clone(),
         version: package.version.clone(),
         tag: git_tag,
         prs,
-    });
-    Ok(package_release)
+        changelog,
+        registries: published_registries,
+        git_release_url: git_release.and_then(|r| r.html_url().map(ToOwned::to_owned)),
+        commit,
+        pre_release,
+        semver_check: (&semver_check).into(),
+        resumed_steps,
+    }))
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -612,9 +1594,24 @@ enum ShouldRelease {
 
 async fn should_release(
     input: &ReleaseRequest,
+    project: &Project,
     repo: &Repo,
     git_client: &GitClient,
 ) -> anyhow::Result<ShouldRelease> {
+    if let ReleaseTrigger::VersionBump { commit_message } = &input.release_trigger {
+        // Don't use the git forge API to find the release PR.
+        if input.release_always
+            || is_release_commit(
+                repo,
+                &project.publishable_packages(),
+                commit_message.as_ref(),
+            )?
+        {
+            return Ok(ShouldRelease::Yes);
+        }
+        info!("skipping release: current commit doesn't bump the version of any package");
+        return Ok(ShouldRelease::No);
+    }
     let last_commit = repo.current_commit_hash()?;
     let prs = git_client.associated_prs(&last_commit).await?;
     let associated_release_pr = prs
@@ -664,7 +1661,7 @@ fn is_pr_commit_in_original_branch(repo: &Repo, commit: &crate::git::backend::Pr
 /// Get the indexes where the package should be published.
 /// If `registry` is specified, it takes precedence over the `publish` field
 /// of the package manifest.
-fn registry_indexes(
+pub(crate) fn registry_indexes(
     package: &Package,
     registry: Option<String>,
 ) -> anyhow::Result<Vec<CargoRegistry>> {
@@ -711,64 +1708,197 @@ struct ReleaseInfo<'a> {
     prs: &'a [Pr],
 }
 
-/// Return `true` if package was published, `false` otherwise.
+/// Outcome of the release of a package in a registry.
+struct ReleaseOutcome {
+    /// Git release of the package, if enabled.
+    git_release: Option<RemoteRelease>,
+}
+
+/// Run cargo-semver-checks against the version of the package published in crates.io.
+/// Failures are logged, because the semver check is informative.
+/// The check runs in a blocking thread, so that packages of the
+/// same release level can be released concurrently.
+async fn release_semver_check(input: &ReleaseRequest, package: &Package) -> SemverCheck {
+    let run_semver_check = input.get_package_config(&package.name).semver_check;
+    if !should_check_semver(package, run_semver_check) {
+        return SemverCheck::Skipped;
+    }
+    info!(
+        "{} {}: running cargo-semver-checks",
+        package.name, package.version
+    );
+    let manifest_path = package.manifest_path.clone();
+    tokio::task::spawn_blocking(move || {
+        semver_check::run_semver_check_against_registry(&manifest_path)
+    })
+    .await
+    .context("cargo-semver-checks task failed")
+    .and_then(|result| result)
+    .inspect_err(|e| {
+        warn!(
+            "{} {}: semver check failed: {e:?}",
+            package.name, package.version
+        );
+    })
+    .unwrap_or(SemverCheck::Skipped)
+}
+
+fn registry_name(registry: Option<&str>) -> String {
+    registry.unwrap_or("crates.io").to_string()
+}
+
+/// Return `Some` if package was published, `None` otherwise.
+/// Steps that are already recorded in the `journal` are skipped.
 async fn release_package(
-    index: &mut CargoIndex,
+    registry: &mut CargoRegistry,
     input: &ReleaseRequest,
     repo: &Repo,
     git_client: &GitClient,
     release_info: &ReleaseInfo<'_>,
     token: &Option<SecretString>,
-) -> anyhow::Result<bool> {
+    journal: &ReleaseJournal,
+) -> anyhow::Result<Option<ReleaseOutcome>> {
     let workspace_root = &input.metadata.workspace_root;
+    let package = release_info.package;
+    let publish_step = ReleaseStep::Publish {
+        registry: registry.name.clone(),
+    };
+    let wait_step = ReleaseStep::Wait {
+        registry: registry.name.clone(),
+    };
+
+    // Render the tag message before publishing, so that an invalid template
+    // doesn't leave a published package without its git tag.
+    let tag_message = if input.is_git_tag_enabled(&package.name) {
+        let git_tag_config = input.get_package_config(&package.name).git_tag;
+        let message = crate::tera::tag_message_from_template(
+            &package.name,
+            &package.version.to_string(),
+            release_info.changelog,
+            git_tag_config.message_template.as_deref(),
+        )
+        .context("invalid git_tag_message")?;
+        Some((message, git_tag_config.sign))
+    } else {
+        None
+    };
 
-    let publish = input.is_publish_enabled(&release_info.package.name);
-    if publish {
-        let output = run_cargo_publish(release_info.package, input, workspace_root)
-            .context("failed to run cargo publish")?;
-        if !output.status.success()
-            || !output.stderr.contains("Uploading")
-            || output.stderr.contains("error:")
+    let publish = input.is_publish_enabled(&package.name);
+    if publish && !journal.is_done(package, &publish_step) {
+        input
+            .get_package_config(&package.name)
+            .publish_policy
+            .check(package, registry.name.as_deref())?;
+        let pre_publish_hook_step = ReleaseStep::PrePublishHook {
+            registry: registry.name.clone(),
+        };
+        if !journal.is_done(package, &pre_publish_hook_step)
+            && run_release_hook(
+                input,
+                release_info,
+                HookKind::PrePublish,
+                registry.name.as_deref(),
+            )
+            .await?
         {
-            anyhow::bail!(
-                "failed to publish {}: {}",
-                release_info.package.name,
-                output.stderr
-            );
+            journal.record(package, pre_publish_hook_step)?;
+        }
+        // A dry run doesn't upload the package, so there are no transient registry errors.
+        let publish_retry = if input.dry_run {
+            PublishRetry::disabled()
+        } else {
+            PublishRetry::default()
+        };
+        publish_with_retry(&publish_retry, &package.name, || async {
+            run_cargo_publish(package, input, workspace_root)
+                .await
+                .context("failed to run cargo publish")
+        })
+        .await?;
+        if !input.dry_run {
+            journal.record(package, publish_step)?;
         }
     }
 
+    let assets = git_release_assets(input, package)?;
+
     if input.dry_run {
+        for asset in &assets {
+            info!(
+                "{} {}: found git release asset {}",
+                package.name, package.version, asset.name
+            );
+        }
         info!(
             "{} {}: aborting upload due to dry run",
             release_info.package.name, release_info.package.version
         );
-        Ok(false)
+        Ok(None)
     } else {
-        if publish {
-            wait_until_published(index, release_info.package, input.publish_timeout, token).await?;
+        if publish && !journal.is_done(package, &wait_step) {
+            wait_until_published(
+                &mut registry.index,
+                release_info.package,
+                input.publish_timeout,
+                token,
+            )
+            .await?;
+            journal.record(package, wait_step)?;
         }
-
-        if input.is_git_tag_enabled(&release_info.package.name) {
-            // Use same tag message of cargo-release
-            let message = format!(
-                "chore: Release package {} version {}",
-                release_info.package.name, release_info.package.version
-            );
-            repo.tag(release_info.git_tag, &message)?;
-            repo.push(release_info.git_tag)?;
+        let integrity_check = input.get_package_config(&package.name).integrity_check;
+        if let Some(integrity_check) = integrity_check {
+            if publish && !journal.is_done(package, &ReleaseStep::Tag) {
+                verify_published_package(
+                    integrity_check,
+                    registry,
+                    package,
+                    &input.metadata.target_directory,
+                    repo,
+                    token,
+                )
+                .await
+                .context("the published package doesn't match the local package")?;
+            }
+        }
+        let post_publish_hook_step = ReleaseStep::PostPublishHook {
+            registry: registry.name.clone(),
+        };
+        if publish
+            && !journal.is_done(package, &post_publish_hook_step)
+            && run_release_hook(
+                input,
+                release_info,
+                HookKind::PostPublish,
+                registry.name.as_deref(),
+            )
+            .await?
+        {
+            journal.record(package, post_publish_hook_step)?;
         }
 
-        let contributors = get_contributors(release_info, git_client).await;
+        if let Some((message, sign)) = &tag_message {
+            // The tag might exist already if the release was interrupted.
+            if !repo.tag_exists(release_info.git_tag)? {
+                if *sign {
+                    repo.tag_signed(release_info.git_tag, message)?;
+                } else {
+                    repo.tag(release_info.git_tag, message)?;
+                }
+            }
+            if !journal.is_done(package, &ReleaseStep::Tag) {
+                journal.record(package, ReleaseStep::Tag)?;
+            }
+            if !journal.is_done(package, &ReleaseStep::Push) {
+                repo.push(release_info.git_tag)?;
+                journal.record(package, ReleaseStep::Push)?;
+            }
+        }
 
-        // TODO fill the rest
-        let remote = Remote {
-            owner: "".to_string(),
-            repo: "".to_string(),
-            link: "".to_string(),
-            contributors,
-        };
-        if input.is_git_release_enabled(&release_info.package.name) {
+        let remote = remote(release_info, git_client).await;
+        let mut created_git_release = None;
+        if input.is_git_release_enabled(&release_info.package.name)
+            && !journal.is_done(package, &ReleaseStep::GitRelease)
+        {
             let release_body =
                 release_body(input, release_info.package, release_info.changelog, &remote);
             let release_config = input
@@ -780,17 +1910,138 @@ async fn release_package(
                 release_name: release_info.release_name.to_string(),
                 release_body,
                 draft: release_config.draft,
-                latest: release_config.latest,
+                latest: input.is_latest_release(&release_config),
                 pre_release: is_pre_release,
             };
-            git_client.create_release(&git_release_info).await?;
+            let git_release = git_client
+                .create_or_update_release(&git_release_info, release_config.update)
+                .await?;
+            journal.record(package, ReleaseStep::GitRelease)?;
+            created_git_release = Some(git_release);
+        }
+        let mut git_release = created_git_release;
+        if git_release.is_none() && input.is_git_release_enabled(&package.name) {
+            // The release was created in a previous run or for another registry.
+            git_release = git_client
+                .get_release_by_tag(release_info.git_tag)
+                .await
+                .inspect_err(|e| {
+                    warn!(
+                        "can't find the git release of tag {}: {e:?}",
+                        release_info.git_tag
+                    );
+                })
+                .ok()
+                .flatten();
+        }
+        if !assets.is_empty() && !journal.is_done(package, &ReleaseStep::GitReleaseAssets) {
+            let git_release = git_release.as_ref().with_context(|| {
+                format!(
+                    "can't find the git release of tag {} to upload the assets",
+                    release_info.git_tag
+                )
+            })?;
+            git_client
+                .upload_release_assets(
+                    git_release,
+                    &package.name,
+                    &package.version.to_string(),
+                    &assets,
+                )
+                .await?;
+            journal.record(package, ReleaseStep::GitReleaseAssets)?;
+        }
+        if !journal.is_done(package, &ReleaseStep::PostReleaseHook)
+            && run_release_hook(input, release_info, HookKind::PostRelease, None).await?
+        {
+            journal.record(package, ReleaseStep::PostReleaseHook)?;
         }
 
         info!(
             "published {} {}",
             release_info.package.name, release_info.package.version
         );
-        Ok(true)
+        Ok(Some(ReleaseOutcome { git_release }))
+    }
+}
+
+/// Run the hook of the given `kind`, if configured.
+/// In dry run, hooks are skipped.
+/// `registry` is [`Option::None`] for hooks that don't refer to a registry.
+/// Return `true` if the hook ran.
+async fn run_release_hook(
+    input: &ReleaseRequest,
+    release_info: &ReleaseInfo<'_>,
+    kind: HookKind,
+    registry: Option<&str>,
+) -> anyhow::Result<bool> {
+    let package = release_info.package;
+    let hooks = input.get_package_config(&package.name).hooks;
+    let Some(command) = hooks.get(kind) else {
+        return Ok(false);
+    };
+    if input.dry_run {
+        info!(
+            "{} {}: skipping {kind} due to dry run",
+            package.name, package.version
+        );
+        return Ok(false);
+    }
+    let version = package.version.to_string();
+    let package_path = package.package_path()?;
+    let mut env = vec![
+        ("RELEASE_PLZ_PACKAGE_NAME", package.name.as_str()),
+        ("RELEASE_PLZ_PACKAGE_VERSION", version.as_str()),
+        ("RELEASE_PLZ_PACKAGE_PATH", package_path.as_str()),
+        ("RELEASE_PLZ_GIT_TAG", release_info.git_tag),
+        ("RELEASE_PLZ_CHANGELOG", release_info.changelog),
+    ];
+    if matches!(kind, HookKind::PrePublish | HookKind::PostPublish) {
+        env.push(("RELEASE_PLZ_REGISTRY", registry.unwrap_or("crates.io")));
+    }
+    run_hook(kind, command, &input.metadata.workspace_root, &env)
+        .await
+        .with_context(|| format!("{kind} of package {} failed", package.name))?;
+    Ok(true)
+}
+
+/// Files to upload to the git release of the package.
+fn git_release_assets(
+    input: &ReleaseRequest,
+    package: &Package,
+) -> anyhow::Result<Vec<ReleaseAsset>> {
+    if !input.is_git_release_enabled(&package.name) {
+        return Ok(vec![]);
+    }
+    package_git_release_assets(input, &package.name)
+}
+
+/// Assets configured for the package, regardless of whether the package has its own git release.
+fn package_git_release_assets(
+    input: &ReleaseRequest,
+    package_name: &str,
+) -> anyhow::Result<Vec<ReleaseAsset>> {
+    let release_config = input.get_package_config(package_name).git_release;
+    if release_config.assets.is_empty() {
+        return Ok(vec![]);
+    }
+    find_release_assets(
+        &input.metadata.workspace_root,
+        &release_config.assets,
+        release_config.assets_checksum,
+    )
+    .with_context(|| format!("can't find git release assets of {package_name}"))
+}
+
+async fn remote(release_info: &ReleaseInfo<'_>, git_client: &GitClient) -> Remote {
+    let contributors = get_contributors(release_info, git_client).await;
+
+    // TODO fill the rest
+    Remote {
+        owner: "".to_string(),
+        repo: "".to_string(),
+        link: "".to_string(),
+        contributors,
     }
 }
 
@@ -817,7 +2068,7 @@ async fn get_contributors(
     contributors
 }
 
-fn get_git_client(input: &ReleaseRequest) -> anyhow::Result<GitClient> {
+pub(crate) fn get_git_client(input: &ReleaseRequest) -> anyhow::Result<GitClient> {
     let git_release = input
         .git_release
         .as_ref()
@@ -853,11 +2104,24 @@ fn verify_ci_cargo_registry_token() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn run_cargo_publish(
+/// Run `cargo publish` in a blocking thread, so that packages of the
+/// same release level can be published concurrently.
+async fn run_cargo_publish(
     package: &Package,
     input: &ReleaseRequest,
     workspace_root: &Utf8Path,
 ) -> anyhow::Result<CmdOutput> {
+    let args = cargo_publish_args(package, input)?;
+    let workspace_root = workspace_root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_cargo(&workspace_root, &args)
+    })
+    .await
+    .context("cargo publish task failed")?
+}
+
+fn cargo_publish_args(package: &Package, input: &ReleaseRequest) -> anyhow::Result<Vec<String>> {
     let mut args = vec!["publish"];
     args.push("--color");
     args.push("always");
@@ -894,7 +2158,7 @@ fn run_cargo_publish(
     if input.all_features(&package.name) {
         args.push("--all-features");
     }
-    run_cargo(workspace_root, &args)
+    Ok(args.into_iter().map(ToOwned::to_owned).collect())
 }
 
 /// Return an empty string if the changelog cannot be parsed.
@@ -961,6 +2225,134 @@ mod tests {
         static ref NO_PARALLEL: Mutex<()> = Mutex::default();
     }
 
+    #[test]
+    fn cargo_version_is_parsed() {
+        assert_eq!(
+            parse_cargo_version("cargo 1.90.0 (840b83a10 2025-07-30)\n").unwrap(),
+            Version::new(1, 90, 0)
+        );
+        assert_eq!(
+            parse_cargo_version("cargo 1.92.0-nightly (f2932725b 2025-09-24)").unwrap(),
+            Version::new(1, 92, 0)
+        );
+        assert!(parse_cargo_version("cargo").is_err());
+    }
+
+    #[test]
+    fn release_follows_latest_config() {
+        let request = ReleaseRequest::new(fake_metadata());
+        for latest in [true, false] {
+            let release_config = GitReleaseConfig::default().set_latest(latest);
+            assert_eq!(request.is_latest_release(&release_config), Some(latest));
+        }
+        assert_eq!(
+            request.is_latest_release(&GitReleaseConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn release_of_maintenance_branch_is_never_latest() {
+        let request = ReleaseRequest::new(fake_metadata()).with_maintenance_branch(true);
+        for release_config in [
+            GitReleaseConfig::default(),
+            GitReleaseConfig::default().set_latest(true),
+        ] {
+            assert_eq!(request.is_latest_release(&release_config), Some(false));
+        }
+    }
+
+    fn workspace_packages(metadata: &Metadata) -> Vec<&Package> {
+        let packages: Vec<&Package> = metadata.workspace_packages().into_iter().take(2).collect();
+        assert_eq!(packages.len(), 2);
+        packages
+    }
+
+    #[test]
+    fn cargo_publish_workspace_args_include_every_package() {
+        let metadata = fake_metadata();
+        let packages = workspace_packages(&metadata);
+        let input = ReleaseRequest::new(metadata.clone())
+            .with_registry("my-registry")
+            .with_token("my-token")
+            .with_dry_run(true)
+            .with_default_package_config(ReleaseConfig::default().with_allow_dirty(true));
+
+        let args = cargo_publish_workspace_args(&input, &packages, Some("my-registry")).unwrap();
+
+        let manifest = input.local_manifest();
+        let mut expected = vec![
+            "publish",
+            "--color",
+            "always",
+            "--manifest-path",
+            manifest.as_str(),
+        ];
+        for package in &packages {
+            expected.extend(["--package", package.name.as_str()]);
+        }
+        expected.extend([
+            "--registry",
+            "my-registry",
+            "--token",
+            "my-token",
+            "--dry-run",
+            "--allow-dirty",
+        ]);
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn packages_with_same_flags_are_published_together() {
+        let metadata = fake_metadata();
+        let packages = workspace_packages(&metadata);
+        let input = ReleaseRequest::new(metadata.clone());
+        assert!(have_same_publish_flags(&input, &packages));
+
+        let input = ReleaseRequest::new(metadata.clone())
+            .with_default_package_config(ReleaseConfig::default().with_no_verify(true));
+        assert!(have_same_publish_flags(&input, &packages));
+    }
+
+    #[test]
+    fn packages_with_different_flags_are_published_one_by_one() {
+        let metadata = fake_metadata();
+        let packages = workspace_packages(&metadata);
+        let first = packages[0].name.clone();
+
+        let input = ReleaseRequest::new(metadata.clone())
+            .with_package_config(&first, ReleaseConfig::default().with_no_verify(true));
+        assert!(!have_same_publish_flags(&input, &packages));
+
+        // `cargo publish --workspace` can't enable features of a single package.
+        let input = ReleaseRequest::new(metadata.clone())
+            .with_default_package_config(ReleaseConfig::default().with_all_features(true));
+        assert!(!have_same_publish_flags(&input, &packages));
+    }
+
+    #[test]
+    fn combined_git_release_uses_workspace_config() {
+        let input = ReleaseRequest::new(fake_metadata())
+            .with_combined_git_release(CombinedGitRelease::default())
+            .with_package_config(
+                "a",
+                ReleaseConfig::default().with_git_tag(
+                    GitTagConfig::enabled(true)
+                        .set_sign(true)
+                        .set_message_template(Some("{{ package }}".to_string())),
+                ),
+            )
+            .with_package_config(
+                "b",
+                ReleaseConfig::default().with_git_release(GitReleaseConfig::enabled(false)),
+            );
+        assert!(!input.combined_release_config().git_tag.sign);
+        assert_eq!(input.combined_tag_message_template(), None);
+        assert!(input.is_in_combined_git_release("a"));
+        assert!(!input.is_in_combined_git_release("b"));
+        assert!(!input.is_git_release_enabled("a"));
+    }
+
     #[test]
     fn git_release_config_pre_release_default_works() {
         let config = GitReleaseConfig::default();
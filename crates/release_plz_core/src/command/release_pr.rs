@@ -1,20 +1,55 @@
-use cargo_metadata::camino::Utf8Path;
+use std::collections::{BTreeMap, BTreeSet};
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
 use cargo_utils::CARGO_TOML;
 use git_cmd::Repo;
 
 use anyhow::Context;
+use regex::Regex;
 use serde::Serialize;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 use url::Url;
 
-use crate::git::backend::{contributors_from_commits, BackendType, GitClient, GitPr, PrEdit};
-use crate::git::github_graphql;
-use crate::pr::{Pr, DEFAULT_BRANCH_PREFIX, OLD_BRANCH_PREFIX};
+use crate::changelog_parser;
+use crate::fs_utils::Utf8TempDir;
+use crate::git::backend::{
+    contributor_commits, contributors_from_commits, BackendType, GitClient, GitPr, PrComment,
+    PrEdit,
+};
+use crate::pr::{MergeMethod, Pr, DEFAULT_BRANCH_PREFIX, OLD_BRANCH_PREFIX};
+use crate::pr_parser::{changelog_comment_package, changelogs_from_pr_body};
 use crate::{
     copy_to_temp_dir, new_manifest_dir_path, new_project_root, publishable_packages_from_manifest,
-    root_repo_path_from_manifest_dir, update, PackagesUpdate, UpdateRequest,
+    root_repo_path_from_manifest_dir, update, write_updates, PackagesUpdate, UpdateRequest,
 };
 
+/// What release-plz does when the release PR contains commits that release-plz didn't author,
+/// e.g. a manual edit of the changelog.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContributorCommits {
+    /// Close the release PR and open a new one without the commits.
+    #[default]
+    Close,
+    /// Regenerate the release PR and apply the commits on top of it.
+    /// If the commits can't be applied, release-plz behaves like [`ContributorCommits::Keep`].
+    Rebase,
+    /// Don't update the release PR and explain why in a comment.
+    Keep,
+}
+
+/// How release-plz splits the packages to release into pull requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReleasePrMode {
+    /// Open a single PR that releases all the packages.
+    #[default]
+    Workspace,
+    /// Open a PR for each package.
+    PerPackage,
+    /// Open a PR for each `version_group`.
+    /// Packages without a version group are released in their own PR.
+    PerGroup,
+}
+
 #[derive(Debug)]
 pub struct ReleasePrRequest {
     /// Tera template for the release pull request name.
@@ -25,8 +60,24 @@ pub struct ReleasePrRequest {
     draft: bool,
     /// Labels to add to the release PR.
     labels: Vec<String>,
+    /// Users asked to review the release PR.
+    reviewers: Vec<String>,
+    /// Teams asked to review the release PR.
+    team_reviewers: Vec<String>,
+    /// Users assigned to the release PR.
+    assignees: Vec<String>,
+    /// Title of the milestone of the release PR.
+    milestone: Option<String>,
     /// PR Branch Prefix
     branch_prefix: String,
+    /// Whether to open a single PR or a PR for each package or version group.
+    mode: ReleasePrMode,
+    /// If set, the created release PR is merged with this method once the CI passes.
+    auto_merge: Option<MergeMethod>,
+    /// What to do with the commits of the release PR that release-plz didn't author.
+    contributor_commits: ContributorCommits,
+    /// If `true`, apply the changelogs edited in the body of the release PR to the changelog files.
+    changelog_edits: bool,
     pub update_request: UpdateRequest,
 }
 
@@ -37,7 +88,15 @@ impl ReleasePrRequest {
             pr_body_template: None,
             draft: false,
             labels: vec![],
+            reviewers: vec![],
+            team_reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
             branch_prefix: DEFAULT_BRANCH_PREFIX.to_string(),
+            mode: ReleasePrMode::default(),
+            auto_merge: None,
+            contributor_commits: ContributorCommits::default(),
+            changelog_edits: false,
             update_request,
         }
     }
@@ -57,6 +116,26 @@ impl ReleasePrRequest {
         self
     }
 
+    pub fn with_reviewers(mut self, reviewers: Vec<String>) -> Self {
+        self.reviewers = reviewers;
+        self
+    }
+
+    pub fn with_team_reviewers(mut self, team_reviewers: Vec<String>) -> Self {
+        self.team_reviewers = team_reviewers;
+        self
+    }
+
+    pub fn with_assignees(mut self, assignees: Vec<String>) -> Self {
+        self.assignees = assignees;
+        self
+    }
+
+    pub fn with_milestone(mut self, milestone: Option<String>) -> Self {
+        self.milestone = milestone;
+        self
+    }
+
     pub fn mark_as_draft(mut self, draft: bool) -> Self {
         self.draft = draft;
         self
@@ -68,6 +147,51 @@ impl ReleasePrRequest {
         }
         self
     }
+
+    pub fn with_auto_merge(mut self, auto_merge: Option<MergeMethod>) -> Self {
+        self.auto_merge = auto_merge;
+        self
+    }
+
+    pub fn with_contributor_commits(mut self, contributor_commits: ContributorCommits) -> Self {
+        self.contributor_commits = contributor_commits;
+        self
+    }
+
+    pub fn with_changelog_edits(mut self, changelog_edits: bool) -> Self {
+        self.changelog_edits = changelog_edits;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: ReleasePrMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Packages to release in each PR, indexed by the name of the package or version group.
+    fn groups(&self, packages_to_update: &PackagesUpdate) -> BTreeMap<String, BTreeSet<String>> {
+        let mut groups: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (package, _) in packages_to_update.updates() {
+            let group = match self.mode {
+                ReleasePrMode::PerGroup => self
+                    .update_request
+                    .get_package_config(&package.name)
+                    .version_group
+                    .unwrap_or_else(|| package.name.clone()),
+                ReleasePrMode::Workspace | ReleasePrMode::PerPackage => package.name.clone(),
+            };
+            groups
+                .entry(group)
+                .or_default()
+                .insert(package.name.clone());
+        }
+        groups
+    }
+
+    /// Prefix of the branch of the PR releasing the given group.
+    fn group_branch_prefix(&self, group: &str) -> String {
+        format!("{}{group}-", self.branch_prefix)
+    }
 }
 
 /// Release pull request that release-plz opened/updated.
@@ -84,61 +208,155 @@ pub struct ReleasePr {
     pub number: u64,
 }
 
-/// Open a pull request with the next packages versions of a local rust project
-/// Returns:
-/// - [`ReleasePr`] if release-plz opened or updated a PR.
-/// - [`None`] if release-plz didn't open any pr. This happens when all packages
-///   are up-to-date.
+/// Open pull requests with the next packages versions of a local rust project.
+/// Returns the PRs that release-plz opened or updated.
+/// With [`ReleasePrMode::Workspace`], there's at most one PR.
+/// The returned vector is empty when all packages are up-to-date.
 #[instrument(skip_all)]
-pub async fn release_pr(input: &ReleasePrRequest) -> anyhow::Result<Option<ReleasePr>> {
-    let manifest_dir = input.update_request.local_manifest_dir()?;
-    let original_project_root = root_repo_path_from_manifest_dir(manifest_dir)?;
-    let tmp_project_root_parent = copy_to_temp_dir(&original_project_root)?;
-    let tmp_project_manifest_dir = new_manifest_dir_path(
-        &original_project_root,
-        manifest_dir,
-        tmp_project_root_parent.path(),
-    )?;
-
-    let tmp_project_root =
-        new_project_root(&original_project_root, tmp_project_root_parent.path())?;
-
-    let local_manifest = tmp_project_manifest_dir.join(CARGO_TOML);
-    let new_update_request = input
-        .update_request
-        .clone()
-        .set_local_manifest(&local_manifest)
-        .context("can't find temporary project")?;
-    let (packages_to_update, _temp_repository) = update(&new_update_request)
-        .await
-        .context("failed to update packages")?;
+pub async fn release_pr(input: &ReleasePrRequest) -> anyhow::Result<Vec<ReleasePr>> {
     let git_client = input
         .update_request
         .git_client()?
         .context("can't find git client")?;
-    if !packages_to_update.updates().is_empty() {
-        let repo = Repo::new(tmp_project_root)?;
-        let there_are_commits_to_push = repo.is_clean().is_err();
-        if there_are_commits_to_push {
-            let pr = open_or_update_release_pr(
-                &local_manifest,
-                &packages_to_update,
-                &git_client,
-                &repo,
-                ReleasePrOptions {
-                    draft: input.draft,
-                    pr_name: input.pr_name_template.clone(),
-                    pr_body: input.pr_body_template.clone(),
-                    pr_labels: input.labels.clone(),
-                    pr_branch_prefix: input.branch_prefix.clone(),
-                },
-            )
-            .await?;
-            return Ok(Some(pr));
+    let project = UpdatedProject::new(&input.update_request).await?;
+    if input.mode == ReleasePrMode::Workspace {
+        let pr = project.open_or_update_pr(input, &git_client, None).await?;
+        return Ok(pr.into_iter().collect());
+    }
+
+    let mut prs = vec![];
+    let mut group_prefixes = vec![];
+    let workspace_version_packages = project.packages_to_update.workspace_version_packages()?;
+    for (group, packages) in input.groups(&project.packages_to_update) {
+        // Reuse the update of the workspace, so that the packages are analyzed only once.
+        let mut packages_to_update = project.packages_to_update.clone();
+        packages_to_update.retain_packages(&packages, &workspace_version_packages);
+        let group_project = project.copy_with_updates(packages_to_update)?;
+        if let Some(pr) = group_project
+            .open_or_update_pr(input, &git_client, Some(&group))
+            .await?
+        {
+            prs.push(pr);
+            group_prefixes.push(input.group_branch_prefix(&group));
         }
     }
+    let base_branch = Repo::new(&project.tmp_project_root)?
+        .original_branch()
+        .to_string();
+    close_stale_prs(
+        &git_client,
+        &input.branch_prefix,
+        &base_branch,
+        &group_prefixes,
+    )
+    .await?;
+    Ok(prs)
+}
 
-    Ok(None)
+/// Copy of the project where release-plz updated the packages.
+struct UpdatedProject {
+    /// Keeps the temporary directory alive.
+    _tmp_project_root_parent: Utf8TempDir,
+    tmp_project_root: Utf8PathBuf,
+    /// Update request of the original project.
+    original_update_request: UpdateRequest,
+    /// Update request of the temporary project.
+    update_request: UpdateRequest,
+    packages_to_update: PackagesUpdate,
+}
+
+impl UpdatedProject {
+    /// Copy the project of the `update_request` and update its packages.
+    async fn new(update_request: &UpdateRequest) -> anyhow::Result<Self> {
+        let mut project = Self::copy(update_request)?;
+        let (packages_to_update, _temp_repository) = update(&project.update_request)
+            .await
+            .context("failed to update packages")?;
+        project.packages_to_update = packages_to_update;
+        Ok(project)
+    }
+
+    /// Copy the original project and write the given updates of this project in it.
+    fn copy_with_updates(&self, mut packages_to_update: PackagesUpdate) -> anyhow::Result<Self> {
+        let mut project = Self::copy(&self.original_update_request)?;
+        packages_to_update.move_to(&self.tmp_project_root, &project.tmp_project_root)?;
+        write_updates(&project.update_request, &packages_to_update)
+            .context("failed to update packages")?;
+        project.packages_to_update = packages_to_update;
+        Ok(project)
+    }
+
+    /// Copy the project of the `update_request` in a temporary directory, without updating it.
+    fn copy(update_request: &UpdateRequest) -> anyhow::Result<Self> {
+        let manifest_dir = update_request.local_manifest_dir()?;
+        let original_project_root = root_repo_path_from_manifest_dir(manifest_dir)?;
+        let tmp_project_root_parent = copy_to_temp_dir(&original_project_root)?;
+        let tmp_project_manifest_dir = new_manifest_dir_path(
+            &original_project_root,
+            manifest_dir,
+            tmp_project_root_parent.path(),
+        )?;
+
+        let tmp_project_root =
+            new_project_root(&original_project_root, tmp_project_root_parent.path())?;
+
+        let local_manifest = tmp_project_manifest_dir.join(CARGO_TOML);
+        let new_update_request = update_request
+            .clone()
+            .set_local_manifest(&local_manifest)
+            .context("can't find temporary project")?;
+        Ok(Self {
+            _tmp_project_root_parent: tmp_project_root_parent,
+            tmp_project_root,
+            original_update_request: update_request.clone(),
+            update_request: new_update_request,
+            packages_to_update: PackagesUpdate::default(),
+        })
+    }
+
+    /// Open or update the PR containing the changes of this project, if any.
+    async fn open_or_update_pr(
+        &self,
+        input: &ReleasePrRequest,
+        git_client: &GitClient,
+        group: Option<&str>,
+    ) -> anyhow::Result<Option<ReleasePr>> {
+        if self.packages_to_update.updates().is_empty() {
+            return Ok(None);
+        }
+        let repo = Repo::new(&self.tmp_project_root)?;
+        let there_are_commits_to_push = repo.is_clean().is_err();
+        if !there_are_commits_to_push {
+            return Ok(None);
+        }
+        let pr_branch_prefix = match group {
+            Some(group) => input.group_branch_prefix(group),
+            None => input.branch_prefix.clone(),
+        };
+        let pr = open_or_update_release_pr(
+            &self.update_request,
+            &self.packages_to_update,
+            git_client,
+            &repo,
+            ReleasePrOptions {
+                draft: input.draft,
+                pr_name: input.pr_name_template.clone(),
+                pr_body: input.pr_body_template.clone(),
+                pr_labels: input.labels.clone(),
+                pr_reviewers: input.reviewers.clone(),
+                pr_team_reviewers: input.team_reviewers.clone(),
+                pr_assignees: input.assignees.clone(),
+                pr_milestone: input.milestone.clone(),
+                pr_branch_prefix,
+                group: group.map(str::to_string),
+                auto_merge: input.auto_merge,
+                contributor_commits: input.contributor_commits,
+                changelog_edits: input.changelog_edits,
+            },
+        )
+        .await?;
+        Ok(Some(pr))
+    }
 }
 
 struct ReleasePrOptions {
@@ -146,29 +364,95 @@ struct ReleasePrOptions {
     pr_name: Option<String>,
     pr_body: Option<String>,
     pr_labels: Vec<String>,
+    pr_reviewers: Vec<String>,
+    pr_team_reviewers: Vec<String>,
+    pr_assignees: Vec<String>,
+    pr_milestone: Option<String>,
     pr_branch_prefix: String,
+    /// Package or version group released by the PR.
+    /// [`None`] if the PR releases all the packages.
+    group: Option<String>,
+    auto_merge: Option<MergeMethod>,
+    contributor_commits: ContributorCommits,
+    changelog_edits: bool,
+}
+
+/// Close the release-plz PRs that don't belong to any of the groups with the given branch prefixes.
+/// E.g. PRs opened with [`ReleasePrMode::Workspace`] or PRs of packages that are up-to-date.
+async fn close_stale_prs(
+    git_client: &GitClient,
+    branch_prefix: &str,
+    base_branch: &str,
+    group_prefixes: &[String],
+) -> anyhow::Result<()> {
+    let opened_release_prs = opened_prs_into_branch(git_client, branch_prefix, base_branch).await?;
+    let stale_prs = opened_release_prs.iter().filter(|pr| {
+        !group_prefixes
+            .iter()
+            .any(|prefix| is_release_branch(pr.branch(), prefix))
+    });
+    for pr in stale_prs {
+        info!("closing stale release pr {}", pr.html_url);
+        git_client
+            .close_pr(pr.number)
+            .await
+            .context("cannot close stale release-plz pr")?;
+    }
+    Ok(())
+}
+
+/// Get the opened PRs with the given `branch_prefix` that want to be merged into `base_branch`.
+/// PRs opened against other branches (e.g. maintenance branches) are ignored.
+async fn opened_prs_into_branch(
+    git_client: &GitClient,
+    branch_prefix: &str,
+    base_branch: &str,
+) -> anyhow::Result<Vec<GitPr>> {
+    let mut prs = git_client
+        .opened_prs(branch_prefix)
+        .await
+        .context("cannot get opened release-plz prs")?;
+    prs.retain(|pr| pr.base_branch().is_none_or(|base| base == base_branch));
+    Ok(prs)
+}
+
+/// Return true if `branch` is a release-plz branch with the given prefix.
+/// Release-plz branches end with a timestamp, so the prefix `release-plz-foo-`
+/// doesn't match the branch `release-plz-foo-bar-2024-01-01T00-00-00Z` of the `foo-bar` package.
+fn is_release_branch(branch: &str, prefix: &str) -> bool {
+    lazy_static::lazy_static! {
+        static ref TIMESTAMP_RE: Regex = Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}Z$").unwrap();
+    }
+    branch
+        .strip_prefix(prefix)
+        .is_some_and(|timestamp| TIMESTAMP_RE.is_match(timestamp))
 }
 
 async fn open_or_update_release_pr(
-    local_manifest: &Utf8Path,
+    update_request: &UpdateRequest,
     packages_to_update: &PackagesUpdate,
     git_client: &GitClient,
     repo: &Repo,
     release_pr_options: ReleasePrOptions,
 ) -> anyhow::Result<ReleasePr> {
-    let mut opened_release_prs = git_client
-        .opened_prs(&release_pr_options.pr_branch_prefix)
-        .await
-        .context("cannot get opened release-plz prs")?;
+    let mut opened_release_prs = opened_prs_into_branch(
+        git_client,
+        &release_pr_options.pr_branch_prefix,
+        repo.original_branch(),
+    )
+    .await?;
+    if release_pr_options.group.is_some() {
+        // Ignore the PRs of other groups with a name starting with the name of this group.
+        opened_release_prs
+            .retain(|pr| is_release_branch(pr.branch(), &release_pr_options.pr_branch_prefix));
+    }
 
     // Check if there are opened release-plz prs with the old prefix.
     // This ensures retro-compatibility with the release-plz versions.
     // TODO: Remove this check on release-plz v0.4.0.
-    if opened_release_prs.is_empty() {
-        opened_release_prs = git_client
-            .opened_prs(OLD_BRANCH_PREFIX)
-            .await
-            .context("cannot get opened release-plz prs")?;
+    if opened_release_prs.is_empty() && release_pr_options.group.is_none() {
+        opened_release_prs =
+            opened_prs_into_branch(git_client, OLD_BRANCH_PREFIX, repo.original_branch()).await?;
     }
 
     // Close all release-plz prs, except one.
@@ -180,19 +464,33 @@ async fn open_or_update_release_pr(
             .context("cannot close old release-plz prs")?;
     }
 
+    let packages_to_update = match opened_release_prs.first() {
+        Some(opened_pr) if release_pr_options.changelog_edits => {
+            apply_changelog_edits(opened_pr, packages_to_update, update_request, repo)?
+        }
+        _ => packages_to_update.clone(),
+    };
+
     let new_pr = {
         let project_contains_multiple_pub_packages =
-            publishable_packages_from_manifest(local_manifest)?.len() > 1;
+            publishable_packages_from_manifest(update_request.local_manifest())?.len() > 1;
         Pr::new(
             repo.original_branch(),
-            packages_to_update,
+            &packages_to_update,
             project_contains_multiple_pub_packages,
             &release_pr_options.pr_branch_prefix,
+            release_pr_options.group.as_deref(),
             release_pr_options.pr_name,
             release_pr_options.pr_body,
         )
         .mark_as_draft(release_pr_options.draft)
         .with_labels(release_pr_options.pr_labels)
+        .with_reviewers(release_pr_options.pr_reviewers)
+        .with_team_reviewers(release_pr_options.pr_team_reviewers)
+        .with_assignees(release_pr_options.pr_assignees)
+        .with_milestone(release_pr_options.pr_milestone)
+        .with_auto_merge(release_pr_options.auto_merge)
+        .with_max_body_len(git_client.backend.max_pr_body_len())
     };
     match opened_release_prs.first() {
         Some(opened_pr) => {
@@ -202,6 +500,7 @@ async fn open_or_update_release_pr(
                 repo,
                 &new_pr,
                 &release_pr_options.pr_branch_prefix,
+                release_pr_options.contributor_commits,
             )
             .await
         }
@@ -209,70 +508,302 @@ async fn open_or_update_release_pr(
     }
 }
 
+/// Write the changelogs edited in the body of the opened PR to the changelog files.
+/// Release-plz considers a changelog edited if it's different from the changelog
+/// of the branch of the opened PR, i.e. the changelog that release-plz generated in a previous run.
+/// The edits are merged with the changes that release-plz generated in this run,
+/// so that new commits of the base branch aren't lost.
+/// Returns the updates with the edited changelogs.
+fn apply_changelog_edits(
+    opened_pr: &GitPr,
+    packages_to_update: &PackagesUpdate,
+    update_request: &UpdateRequest,
+    repo: &Repo,
+) -> anyhow::Result<PackagesUpdate> {
+    let mut packages_to_update = packages_to_update.clone();
+    let Some(body) = &opened_pr.body else {
+        return Ok(packages_to_update);
+    };
+    let edited_changelogs = changelogs_from_pr_body(body);
+    if edited_changelogs.is_empty() {
+        return Ok(packages_to_update);
+    }
+    repo.fetch(opened_pr.branch())
+        .with_context(|| format!("cannot fetch the branch of pr {}", opened_pr.html_url))?;
+    for (package, update) in packages_to_update.updates_mut() {
+        let Some(edited_notes) = edited_changelogs.get(&package.name).map(|n| n.trim()) else {
+            continue;
+        };
+        let (Some(changelog), Some(release)) = (&update.changelog, update.last_changes()?) else {
+            continue;
+        };
+        let generated_notes = release.notes().trim();
+        // An empty changelog in the PR body restores the generated changelog.
+        if edited_notes.is_empty() || generated_notes.is_empty() {
+            continue;
+        }
+        let changelog_path = update_request.changelog_path(package);
+        let Some(pr_notes) = changelog_notes_at_commit(repo, &opened_pr.head.sha, &changelog_path)?
+        else {
+            debug!(
+                "{}: changelog not found in the branch of pr {}",
+                package.name, opened_pr.html_url
+            );
+            continue;
+        };
+        let pr_notes = pr_notes.trim();
+        if edited_notes == pr_notes {
+            // Nobody edited the changelog in the PR body.
+            continue;
+        }
+        let merged_notes = if generated_notes == pr_notes {
+            edited_notes.to_string()
+        } else {
+            match merge_changelog_edits(repo, pr_notes, generated_notes, edited_notes) {
+                Ok(merged_notes) => merged_notes,
+                Err(e) => {
+                    warn!(
+                        "{}: cannot merge the changelog edited in the body of pr {} with the new changes, edit it again: {e}",
+                        package.name, opened_pr.html_url
+                    );
+                    continue;
+                }
+            }
+        };
+        info!(
+            "{}: applying the changelog edited in the body of pr {}",
+            package.name, opened_pr.html_url
+        );
+        let new_changelog = changelog.replacen(generated_notes, merged_notes.trim(), 1);
+        fs_err::write(&changelog_path, &new_changelog).context("cannot write changelog")?;
+        update.changelog = Some(new_changelog);
+    }
+    Ok(packages_to_update)
+}
+
+/// Notes of the last release of the changelog at the given commit.
+fn changelog_notes_at_commit(
+    repo: &Repo,
+    commit: &str,
+    changelog_path: &Utf8Path,
+) -> anyhow::Result<Option<String>> {
+    let repo_dir = repo.directory().canonicalize_utf8()?;
+    let changelog_path = changelog_path.canonicalize_utf8()?;
+    let Ok(relative_path) = changelog_path.strip_prefix(&repo_dir) else {
+        return Ok(None);
+    };
+    let Some(changelog) = repo.file_at_commit(commit, relative_path)? else {
+        return Ok(None);
+    };
+    let notes = changelog_parser::last_release_from_str(&changelog)?
+        .map(|release| release.notes().to_string());
+    Ok(notes)
+}
+
+/// Apply the edits made to `pr_notes` in `edited_notes` to `generated_notes`.
+/// Fails if the edits conflict with the new changes of `generated_notes`.
+fn merge_changelog_edits(
+    repo: &Repo,
+    pr_notes: &str,
+    generated_notes: &str,
+    edited_notes: &str,
+) -> anyhow::Result<String> {
+    let dir = Utf8TempDir::new()?;
+    let file = |name: &str, content: &str| -> anyhow::Result<Utf8PathBuf> {
+        let path = dir.path().join(name);
+        fs_err::write(&path, format!("{content}\n"))?;
+        Ok(path)
+    };
+    let generated = file("generated", generated_notes)?;
+    let base = file("base", pr_notes)?;
+    let edited = file("edited", edited_notes)?;
+    repo.git(&[
+        "merge-file",
+        "--stdout",
+        generated.as_str(),
+        base.as_str(),
+        edited.as_str(),
+    ])
+    .context("the changelog edits conflict with the new changes")
+}
+
+/// What release-plz does with the opened release PR.
+#[derive(Debug, PartialEq, Eq)]
+enum OpenedPrAction {
+    /// Force-push the new changes to the PR branch.
+    Update,
+    /// Close the PR and open a new one.
+    Close,
+    /// Force-push the new changes and apply the commits of the contributors on top of them.
+    Rebase,
+    /// Leave the PR as it is.
+    Keep,
+}
+
+fn opened_pr_action(
+    has_contributor_commits: bool,
+    on_contributor_commits: ContributorCommits,
+) -> OpenedPrAction {
+    if !has_contributor_commits {
+        return OpenedPrAction::Update;
+    }
+    match on_contributor_commits {
+        ContributorCommits::Close => OpenedPrAction::Close,
+        ContributorCommits::Rebase => OpenedPrAction::Rebase,
+        ContributorCommits::Keep => OpenedPrAction::Keep,
+    }
+}
+
+/// The commits of the contributors can't be applied on top of the regenerated release PR,
+/// e.g. because of a conflict.
+#[derive(Debug)]
+struct ContributorCommitsConflict;
+
+impl std::fmt::Display for ContributorCommitsConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot apply the commits of the contributors")
+    }
+}
+
+/// Return true if the release PR wasn't updated because of [`ContributorCommitsConflict`].
+fn is_contributor_commits_conflict(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ContributorCommitsConflict>().is_some()
+}
+
 async fn handle_opened_pr(
     git_client: &GitClient,
     opened_pr: &GitPr,
     repo: &Repo,
     new_pr: &Pr,
     branch_prefix: &str,
+    on_contributor_commits: ContributorCommits,
 ) -> Result<ReleasePr, anyhow::Error> {
     let pr_commits = git_client
         .pr_commits(opened_pr.number)
         .await
         .context("cannot get commits of release-plz pr")?;
-    let pr_contributors = contributors_from_commits(&pr_commits);
-    Ok(if pr_contributors.is_empty() {
-        // There are no contributors, so we can force-push
-        // in this PR, because we don't care about the git history.
-        match update_pr(
-            git_client,
-            opened_pr,
-            pr_commits.len(),
-            repo,
-            new_pr,
-            branch_prefix,
-        )
-        .await
-        {
-            Ok(()) => ReleasePr {
-                number: opened_pr.number,
-                head_branch: opened_pr.branch().to_string(),
-                html_url: opened_pr.html_url.clone(),
-                base_branch: new_pr.base_branch.clone(),
-            },
-            Err(e) => {
-                tracing::error!("cannot update release pr {}: {:?}. I'm closing the old release pr and opening a new one", opened_pr.number, e);
-                git_client
-                    .close_pr(opened_pr.number)
-                    .await
-                    .context("cannot close old release-plz prs")?;
-                create_pr(git_client, repo, new_pr).await?
+    let contributor_commits: Vec<String> = contributor_commits(&pr_commits)
+        .map(|commit| commit.sha.clone())
+        .collect();
+    let opened_release_pr = || ReleasePr {
+        number: opened_pr.number,
+        head_branch: opened_pr.branch().to_string(),
+        html_url: opened_pr.html_url.clone(),
+        base_branch: new_pr.base_branch.clone(),
+    };
+    // If there's a contributor, we don't want to lose their work.
+    let pr_contributors = contributors_from_commits(&pr_commits).join(", ");
+    // TODO improvement: check how many lines the commit added, if no lines (for example a merge to update the branch),
+    //      then don't count it as a contributor.
+    match opened_pr_action(!contributor_commits.is_empty(), on_contributor_commits) {
+        OpenedPrAction::Update => {
+            // There are no contributors, so we can force-push
+            // in this PR, because we don't care about the git history.
+            match update_pr(
+                git_client,
+                opened_pr,
+                pr_commits.len(),
+                repo,
+                new_pr,
+                branch_prefix,
+                &[],
+            )
+            .await
+            {
+                Ok(()) => Ok(opened_release_pr()),
+                Err(e) => {
+                    tracing::error!("cannot update release pr {}: {:?}. I'm closing the old release pr and opening a new one", opened_pr.number, e);
+                    git_client
+                        .close_pr(opened_pr.number)
+                        .await
+                        .context("cannot close old release-plz prs")?;
+                    create_pr(git_client, repo, new_pr).await
+                }
             }
         }
-    } else {
-        // There's a contributor, so we don't want to force-push in this PR.
-        // We close it because we want to save the contributor's work.
-        // TODO improvement: check how many lines the commit added, if no lines (for example a merge to update the branch),
-        //      then don't count it as a contributor.
-        info!("closing pr {} to preserve git history", opened_pr.html_url);
-        git_client
-            .close_pr(opened_pr.number)
+        OpenedPrAction::Close => {
+            // We close the PR because we want to save the contributor's work.
+            info!(
+                "closing pr {} to preserve git history of {pr_contributors}",
+                opened_pr.html_url
+            );
+            git_client
+                .close_pr(opened_pr.number)
+                .await
+                .context("cannot close old release-plz prs")?;
+            create_pr(git_client, repo, new_pr).await
+        }
+        OpenedPrAction::Rebase => {
+            match update_pr(
+                git_client,
+                opened_pr,
+                pr_commits.len(),
+                repo,
+                new_pr,
+                branch_prefix,
+                &contributor_commits,
+            )
             .await
-            .context("cannot close old release-plz prs")?;
-        create_pr(git_client, repo, new_pr).await?
-    })
+            {
+                Ok(()) => Ok(opened_release_pr()),
+                // The PR branch wasn't changed, so we can keep the PR as it is.
+                Err(e) if is_contributor_commits_conflict(&e) => {
+                    warn!(
+                        "cannot apply the commits of the contributors to release pr {}: {e:?}",
+                        opened_pr.html_url
+                    );
+                    keep_pr(git_client, opened_pr, &contributor_commits).await?;
+                    Ok(opened_release_pr())
+                }
+                Err(e) => {
+                    Err(e.context(format!("cannot update release pr {}", opened_pr.html_url)))
+                }
+            }
+        }
+        OpenedPrAction::Keep => {
+            info!(
+                "not updating pr {} to preserve the commits of {pr_contributors}",
+                opened_pr.html_url
+            );
+            keep_pr(git_client, opened_pr, &contributor_commits).await?;
+            Ok(opened_release_pr())
+        }
+    }
+}
+
+/// Leave the PR as it is and explain why in a comment.
+async fn keep_pr(
+    git_client: &GitClient,
+    opened_pr: &GitPr,
+    contributor_commits: &[String],
+) -> anyhow::Result<()> {
+    let commits = contributor_commits
+        .iter()
+        .map(|sha| format!("- {sha}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let comment = format!(
+        "release-plz didn't update this PR because it contains commits that release-plz didn't author:\n\n{commits}\n\n\
+        To let release-plz update the PR, close it or remove these commits from its branch."
+    );
+    git_client
+        .comment_pr_once(opened_pr.number, &comment)
+        .await
+        .context("cannot comment on release-plz pr")
 }
 
 async fn create_pr(git_client: &GitClient, repo: &Repo, pr: &Pr) -> anyhow::Result<ReleasePr> {
     repo.checkout_new_branch(&pr.branch)?;
-    if matches!(git_client.backend, BackendType::Github) {
-        github_create_release_branch(git_client, repo, &pr.branch, &pr.title).await?;
-    } else {
-        create_release_branch(repo, &pr.branch, &pr.title)?;
-    }
+    create_release_branch(git_client, repo, &pr.branch, &pr.title).await?;
     debug!("changes committed to release branch {}", pr.branch);
 
     let git_pr = git_client.open_pr(pr).await.context("Failed to open PR")?;
+    post_pr_comments(git_client, pr, git_pr.number).await?;
+    if let Some(merge_method) = pr.auto_merge {
+        if let Err(e) = git_client.enable_auto_merge(&git_pr, merge_method).await {
+            warn!("cannot enable auto-merge on pr {}: {e:?}", git_pr.html_url);
+        }
+    }
     Ok(ReleasePr {
         number: git_pr.number,
         head_branch: git_pr.branch().to_string(),
@@ -281,6 +812,8 @@ async fn create_pr(git_client: &GitClient, repo: &Repo, pr: &Pr) -> anyhow::Resu
     })
 }
 
+/// Regenerate the PR branch with the new changes,
+/// applying the `contributor_commits` on top of them.
 async fn update_pr(
     git_client: &GitClient,
     opened_pr: &GitPr,
@@ -288,6 +821,7 @@ async fn update_pr(
     repository: &Repo,
     new_pr: &Pr,
     branch_prefix: &str,
+    contributor_commits: &[String],
 ) -> anyhow::Result<()> {
     update_pr_branch(commits_number, opened_pr, repository, branch_prefix).with_context(|| {
         format!(
@@ -295,11 +829,7 @@ async fn update_pr(
             repository.original_branch()
         )
     })?;
-    if matches!(git_client.backend, BackendType::Github) {
-        github_force_push(git_client, opened_pr, repository).await?;
-    } else {
-        force_push(opened_pr, repository)?;
-    }
+    force_push(git_client, opened_pr, repository, contributor_commits).await?;
     let pr_edit = {
         let mut pr_edit = PrEdit::new();
         if opened_pr.title != new_pr.title {
@@ -313,10 +843,56 @@ async fn update_pr(
     if pr_edit.contains_edit() {
         git_client.edit_pr(opened_pr.number, pr_edit).await?;
     }
+    // The PR is already updated, so don't fail if it can't be assigned.
+    if let Err(e) = git_client.assign_pr(new_pr, opened_pr.number).await {
+        warn!(
+            "cannot set reviewers, assignees and milestone of pr {}: {e:?}",
+            opened_pr.html_url
+        );
+    }
+    post_pr_comments(git_client, new_pr, opened_pr.number).await?;
     info!("updated pr {}", opened_pr.html_url);
     Ok(())
 }
 
+/// Post the changelogs that don't fit in the PR body as PR comments.
+/// The changelog comments posted in the previous runs are edited,
+/// or deleted if the changelog of the package isn't in a comment anymore.
+async fn post_pr_comments(git_client: &GitClient, pr: &Pr, pr_number: u64) -> anyhow::Result<()> {
+    let mut previous_comments: BTreeMap<String, PrComment> = git_client
+        .pr_comments(pr_number)
+        .await
+        .context("cannot get the PR comments")?
+        .into_iter()
+        .filter_map(|comment| {
+            let package = changelog_comment_package(comment.body.as_deref()?)?.to_string();
+            Some((package, comment))
+        })
+        .collect();
+    for comment in &pr.comments {
+        let previous_comment = changelog_comment_package(comment)
+            .and_then(|package| previous_comments.remove(package));
+        match previous_comment {
+            Some(previous) if previous.body.as_deref() == Some(comment.as_str()) => {}
+            Some(previous) => git_client
+                .edit_pr_comment(pr_number, previous.id, comment)
+                .await
+                .context("cannot edit changelog in a PR comment")?,
+            None => git_client
+                .comment_pr(pr_number, comment)
+                .await
+                .context("cannot post changelog in a PR comment")?,
+        }
+    }
+    for outdated_comment in previous_comments.into_values() {
+        git_client
+            .delete_pr_comment(pr_number, outdated_comment.id)
+            .await
+            .context("cannot delete outdated changelog PR comment")?;
+    }
+    Ok(())
+}
+
 /// Update the PR branch with the latest changes from the
 /// original branch where release-plz was run (by default it's the default branch, e.g. `main`).
 fn update_pr_branch(
@@ -372,16 +948,11 @@ fn reset_branch(
     Ok(())
 }
 
-fn force_push(pr: &GitPr, repository: &Repo) -> anyhow::Result<()> {
-    add_changes_and_commit(repository, &pr.title)?;
-    repository.force_push(pr.branch())?;
-    Ok(())
-}
-
-async fn github_force_push(
+async fn force_push(
     client: &GitClient,
     pr: &GitPr,
     repository: &Repo,
+    contributor_commits: &[String],
 ) -> anyhow::Result<()> {
     // Create a temporary branch.
     let tmp_release_branch = {
@@ -390,24 +961,33 @@ async fn github_force_push(
     }?;
 
     // Push the "Verified" commit in the temporary branch using
-    // the GitHub API.
+    // the API of the git forge.
     // We push the release-plz changes to the temporary branch instead of the release PR branch because:
-    // - You can't force-push with the GitHub API, so we can't commit to the release PR branch
+    // - You can't force-push with the API, so we can't commit to the release PR branch
     //   directly if we want a "Verified" commit.
     // - If we revert the last commit of the release PR branch, GitHub will close the release PR
     //   because the branch is the same as the default branch. So we can't revert the latest release-plz commit and push the new one.
     // To learn more, see https://github.com/release-plz/release-plz/issues/1487
-    github_create_release_branch(client, repository, &tmp_release_branch.name, &pr.title).await?;
+    create_release_branch(client, repository, &tmp_release_branch.name, &pr.title).await?;
 
     repository.fetch(&tmp_release_branch.name)?;
-
-    // Rewrite the PR branch so that it's the same as the temporary branch.
-    repository.force_push(&format!(
-        "{}/{}:{}",
+    let tmp_release_branch_remote = format!(
+        "{}/{}",
         repository.original_remote(),
-        tmp_release_branch.name,
-        pr.branch()
-    ))?;
+        tmp_release_branch.name
+    );
+
+    if contributor_commits.is_empty() {
+        // Rewrite the PR branch so that it's the same as the temporary branch.
+        repository.force_push(&format!("{tmp_release_branch_remote}:{}", pr.branch()))?;
+    } else {
+        // Apply the commits of the contributors on top of the "Verified" commit.
+        repository.git(&["reset", "--hard", &tmp_release_branch_remote])?;
+        repository
+            .cherry_pick(contributor_commits)
+            .map_err(|e| e.context(ContributorCommitsConflict))?;
+        repository.force_push(&format!("HEAD:{}", pr.branch()))?;
+    }
 
     // The temporary branch is deleted in remote when it goes out of scope.
     Ok(())
@@ -438,24 +1018,29 @@ impl Drop for TmpBranch<'_> {
     }
 }
 
-fn create_release_branch(
-    repository: &Repo,
-    release_branch: &str,
-    commit_message: &str,
-) -> anyhow::Result<()> {
-    add_changes_and_commit(repository, commit_message)?;
-    repository.push(release_branch)?;
-    Ok(())
-}
-
-async fn github_create_release_branch(
+/// Push the release branch and commit the changes on it with the API of the git forge,
+/// so that the commit is "Verified" without a signing key.
+/// If the API of GitLab, Gitea or Bitbucket can't commit the changes
+/// (e.g. because the API isn't available in self-hosted instances),
+/// commit the changes locally and push them.
+async fn create_release_branch(
     client: &GitClient,
     repository: &Repo,
     release_branch: &str,
     commit_message: &str,
 ) -> anyhow::Result<()> {
     repository.push(release_branch)?;
-    github_graphql::commit_changes(client, repository, commit_message, release_branch).await
+    match client
+        .commit_changes(repository, commit_message, release_branch)
+        .await
+    {
+        Err(e) if !matches!(client.backend, BackendType::Github) => {
+            warn!("cannot commit the changes with the API of the git forge, committing them locally: {e:?}");
+            add_changes_and_commit(repository, commit_message)?;
+            repository.push(release_branch)
+        }
+        result => result,
+    }
 }
 
 fn add_changes_and_commit(repository: &Repo, commit_message: &str) -> anyhow::Result<()> {
@@ -464,3 +1049,567 @@ fn add_changes_and_commit(repository: &Repo, commit_message: &str) -> anyhow::Re
     repository.commit_signed(commit_message)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use fake_package::metadata::fake_metadata;
+    use secrecy::SecretString;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::pr_parser::changelog_comment_with_marker;
+    use crate::{
+        GitBackend, GitHub, Gitea, PackageUpdateConfig, RepoUrl, UpdateConfig, UpdateResult,
+    };
+
+    fn packages_update(packages: &[&str]) -> PackagesUpdate {
+        let updates = packages
+            .iter()
+            .map(|name| {
+                (
+                    fake_package::FakePackage::new(*name).into(),
+                    UpdateResult {
+                        version: cargo_metadata::semver::Version::new(0, 2, 0),
+                        changelog: None,
+                        semver_check: crate::semver_check::SemverCheck::Skipped,
+                    },
+                )
+            })
+            .collect();
+        PackagesUpdate::new(updates)
+    }
+
+    fn release_pr_request(mode: ReleasePrMode) -> ReleasePrRequest {
+        let version_group = |group: &str| PackageUpdateConfig {
+            generic: UpdateConfig::default(),
+            changelog_include: vec![],
+            version_group: Some(group.to_string()),
+        };
+        let update_request = UpdateRequest::new(fake_metadata())
+            .unwrap()
+            .with_package_config("a", version_group("core"))
+            .with_package_config("b", version_group("core"));
+        ReleasePrRequest::new(update_request).with_mode(mode)
+    }
+
+    fn groups(mode: ReleasePrMode) -> Vec<(String, Vec<String>)> {
+        release_pr_request(mode)
+            .groups(&packages_update(&["a", "b", "c"]))
+            .into_iter()
+            .map(|(group, packages)| (group, packages.into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn packages_are_grouped_by_version_group() {
+        assert_eq!(
+            groups(ReleasePrMode::PerGroup),
+            [
+                ("c".to_string(), vec!["c".to_string()]),
+                ("core".to_string(), vec!["a".to_string(), "b".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn packages_are_grouped_by_package() {
+        assert_eq!(
+            groups(ReleasePrMode::PerPackage),
+            [
+                ("a".to_string(), vec!["a".to_string()]),
+                ("b".to_string(), vec!["b".to_string()]),
+                ("c".to_string(), vec!["c".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_prs_are_closed() {
+        let server = MockServer::start().await;
+        let pr = |number: u64, branch: &str, base: &str| {
+            json!({
+                "user": { "login": "release-plz" },
+                "number": number,
+                "html_url": format!("{}/owner/repo/pull/{number}", server.uri()),
+                "head": { "ref": branch, "sha": "abc" },
+                "base": { "ref": base },
+                "title": "chore: release",
+                "body": null,
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                // PR of the `foo` package.
+                pr(1, "release-plz-foo-2024-01-01T00-00-00Z", "main"),
+                // PR opened with the workspace mode.
+                pr(2, "release-plz-2024-01-01T00-00-00Z", "main"),
+                // PR of the up-to-date `foo-bar` package.
+                pr(3, "release-plz-foo-bar-2024-01-01T00-00-00Z", "main"),
+                // PR of a maintenance branch.
+                pr(4, "release-plz-2024-01-01T00-00-00Z", "v1"),
+            ])))
+            .mount(&server)
+            .await;
+        for (number, closed) in [(1, false), (2, true), (3, true), (4, false)] {
+            Mock::given(method("PATCH"))
+                .and(path(format!("/repos/owner/repo/pulls/{number}")))
+                .and(body_json(json!({ "state": "closed" })))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(u64::from(closed))
+                .mount(&server)
+                .await;
+        }
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        close_stale_prs(
+            &client,
+            DEFAULT_BRANCH_PREFIX,
+            "main",
+            &["release-plz-foo-".to_string()],
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn changelog_comments_are_updated() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 1, "body": changelog_comment_with_marker("a", "old changelog of a") },
+                { "id": 2, "body": changelog_comment_with_marker("b", "changelog of b") },
+                { "id": 3, "body": changelog_comment_with_marker("c", "changelog of c") },
+                { "id": 4, "body": "comment of a user" },
+            ])))
+            .mount(&server)
+            .await;
+        // The changelog of `a` changed.
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/issues/comments/1"))
+            .and(body_json(json!({
+                "body": changelog_comment_with_marker("a", "new changelog of a"),
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The changelog of `c` fits in the PR body now.
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/issues/comments/3"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The changelog of `d` doesn't fit in the PR body anymore.
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .and(body_json(json!({
+                "body": changelog_comment_with_marker("d", "changelog of d"),
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        let pr = Pr {
+            base_branch: "main".to_string(),
+            branch: "release-plz-2024-01-01T00-00-00Z".to_string(),
+            title: "chore: release".to_string(),
+            body: String::new(),
+            draft: false,
+            labels: vec![],
+            reviewers: vec![],
+            team_reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            auto_merge: None,
+            comments: vec![
+                changelog_comment_with_marker("a", "new changelog of a"),
+                changelog_comment_with_marker("b", "changelog of b"),
+                changelog_comment_with_marker("d", "changelog of d"),
+            ],
+        };
+        post_pr_comments(&client, &pr, 3).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn opened_prs_are_filtered_by_base_branch() {
+        let server = MockServer::start().await;
+        let pr = |number: u64, base: Option<&str>| {
+            json!({
+                "user": { "login": "release-plz" },
+                "number": number,
+                "html_url": format!("{}/owner/repo/pull/{number}", server.uri()),
+                "head": { "ref": "release-plz-2024-01-01T00-00-00Z", "sha": "abc" },
+                "base": base.map(|base| json!({ "ref": base })),
+                "title": "chore: release",
+                "body": null,
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                pr(1, Some("main")),
+                pr(2, Some("v1")),
+                // The base branch isn't returned by every git forge.
+                pr(3, None),
+            ])))
+            .mount(&server)
+            .await;
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        for (base_branch, expected) in [("main", vec![1, 3]), ("v1", vec![2, 3])] {
+            let prs = opened_prs_into_branch(&client, DEFAULT_BRANCH_PREFIX, base_branch)
+                .await
+                .unwrap();
+            let numbers: Vec<u64> = prs.iter().map(|pr| pr.number).collect();
+            assert_eq!(numbers, expected);
+        }
+    }
+
+    #[test]
+    fn opened_pr_without_contributors_is_updated() {
+        for on_contributor_commits in [
+            ContributorCommits::Close,
+            ContributorCommits::Rebase,
+            ContributorCommits::Keep,
+        ] {
+            assert_eq!(
+                opened_pr_action(false, on_contributor_commits),
+                OpenedPrAction::Update
+            );
+        }
+    }
+
+    #[test]
+    fn opened_pr_with_contributors_follows_config() {
+        assert_eq!(
+            opened_pr_action(true, ContributorCommits::Close),
+            OpenedPrAction::Close
+        );
+        assert_eq!(
+            opened_pr_action(true, ContributorCommits::Rebase),
+            OpenedPrAction::Rebase
+        );
+        assert_eq!(
+            opened_pr_action(true, ContributorCommits::Keep),
+            OpenedPrAction::Keep
+        );
+    }
+
+    #[tokio::test]
+    async fn opened_pr_with_contributors_is_kept() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls/3/commits"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "sha": "aaa", "author": { "login": "release-plz[bot]" } },
+                { "sha": "bbb", "author": { "login": "alice" } },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The PR isn't closed or updated.
+        Mock::given(method("PATCH"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        let opened_pr: GitPr = serde_json::from_value(json!({
+            "user": { "login": "release-plz[bot]" },
+            "number": 3,
+            "html_url": format!("{}/owner/repo/pull/3", server.uri()),
+            "head": { "ref": "release-plz-2024-01-01T00-00-00Z", "sha": "bbb" },
+            "title": "chore: release",
+            "body": null,
+        }))
+        .unwrap();
+        let new_pr = Pr {
+            base_branch: "main".to_string(),
+            branch: "release-plz-2024-02-01T00-00-00Z".to_string(),
+            title: "chore: release".to_string(),
+            body: String::new(),
+            draft: false,
+            labels: vec![],
+            reviewers: vec![],
+            team_reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            auto_merge: None,
+            comments: vec![],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repo::init(dir.path());
+        let release_pr = handle_opened_pr(
+            &client,
+            &opened_pr,
+            &repo,
+            &new_pr,
+            DEFAULT_BRANCH_PREFIX,
+            ContributorCommits::Keep,
+        )
+        .await
+        .unwrap();
+        assert_eq!(release_pr.number, 3);
+        assert_eq!(release_pr.head_branch, "release-plz-2024-01-01T00-00-00Z");
+    }
+
+    /// Clone the repository in `origin_dir` in the `local` directory of `dir`.
+    fn clone_repo(dir: &tempfile::TempDir, origin_dir: &std::path::Path) -> Repo {
+        git_cmd::git_in_dir(
+            Utf8Path::from_path(dir.path()).unwrap(),
+            &["clone", origin_dir.to_str().unwrap(), "local"],
+        )
+        .unwrap();
+        let repo = Repo::new(Utf8Path::from_path(&dir.path().join("local")).unwrap()).unwrap();
+        repo.git(&["config", "user.name", "author_name"]).unwrap();
+        repo.git(&["config", "user.email", "author@example.com"])
+            .unwrap();
+        repo
+    }
+
+    #[tokio::test]
+    async fn changes_are_committed_locally_if_the_api_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin_dir = dir.path().join("origin");
+        fs_err::create_dir(&origin_dir).unwrap();
+        let origin = Repo::init(&origin_dir);
+        let repo = clone_repo(&dir, &origin_dir);
+        let branch = "release-plz-2024-01-01T00-00-00Z";
+        repo.checkout_new_branch(branch).unwrap();
+        fs_err::write(repo.directory().join("README.md"), "# new version").unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/owner/repo/contents"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let repo_url = RepoUrl::new(&format!("{}/owner/repo", server.uri())).unwrap();
+        let gitea = Gitea::new(repo_url, SecretString::from("token")).unwrap();
+        let client = GitClient::new(GitBackend::Gitea(gitea)).unwrap();
+        create_release_branch(&client, &repo, branch, "chore: release")
+            .await
+            .unwrap();
+
+        let pushed_commit = origin.git(&["log", "-1", "--format=%s", branch]).unwrap();
+        assert_eq!(pushed_commit, "chore: release");
+        let readme = origin
+            .git(&["show", &format!("{branch}:README.md")])
+            .unwrap();
+        assert_eq!(readme, "# new version");
+    }
+
+    #[tokio::test]
+    async fn github_changes_are_not_committed_locally_if_the_api_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin_dir = dir.path().join("origin");
+        fs_err::create_dir(&origin_dir).unwrap();
+        let origin = Repo::init(&origin_dir);
+        let repo = clone_repo(&dir, &origin_dir);
+        let branch = "release-plz-2024-01-01T00-00-00Z";
+        repo.checkout_new_branch(branch).unwrap();
+        fs_err::write(repo.directory().join("README.md"), "# new version").unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        create_release_branch(&client, &repo, branch, "chore: release")
+            .await
+            .unwrap_err();
+
+        let pushed_commit = origin.git(&["log", "-1", "--format=%s", branch]).unwrap();
+        assert_eq!(pushed_commit, "add README");
+    }
+
+    fn changelog(notes: &str) -> String {
+        format!("# Changelog\n\n## [Unreleased]\n\n## [0.2.0] - 2024-01-01\n\n{notes}\n")
+    }
+
+    /// Apply the changelog of package `a` edited in the body of a PR.
+    /// `pr_notes` are the notes in the CHANGELOG of the PR branch,
+    /// `generated_notes` are the notes generated in this run.
+    /// Returns the notes of the updated changelog.
+    fn apply_edits(pr_notes: &str, generated_notes: &str, edited_notes: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let origin_dir = dir.path().join("origin");
+        fs_err::create_dir(&origin_dir).unwrap();
+        let origin = Repo::init(&origin_dir);
+        let branch = "release-plz-2024-01-01T00-00-00Z";
+        origin.checkout_new_branch(branch).unwrap();
+        fs_err::write(origin_dir.join("CHANGELOG.md"), changelog(pr_notes)).unwrap();
+        origin.add_all_and_commit("chore: release").unwrap();
+        let pr_sha = origin.current_commit_hash().unwrap();
+        origin.checkout(origin.original_branch()).unwrap();
+
+        let repo = clone_repo(&dir, &origin_dir);
+        let local_dir = repo.directory();
+        let changelog_path = local_dir.join("CHANGELOG.md");
+        let generated_changelog = changelog(generated_notes);
+        fs_err::write(&changelog_path, &generated_changelog).unwrap();
+
+        let update_request = UpdateRequest::new(fake_metadata())
+            .unwrap()
+            .with_package_config(
+                "a",
+                PackageUpdateConfig {
+                    generic: UpdateConfig {
+                        changelog_path: Some(changelog_path.clone()),
+                        ..UpdateConfig::default()
+                    },
+                    changelog_include: vec![],
+                    version_group: None,
+                },
+            );
+        let mut packages_update = packages_update(&["a"]);
+        packages_update.updates_mut()[0].1.changelog = Some(generated_changelog);
+        let opened_pr: GitPr = serde_json::from_value(json!({
+            "user": { "login": "release-plz[bot]" },
+            "number": 3,
+            "html_url": "https://github.com/owner/repo/pull/3",
+            "head": { "ref": branch, "sha": pr_sha },
+            "title": "chore: release",
+            "body": crate::pr_parser::changelog_with_markers("a", edited_notes),
+        }))
+        .unwrap();
+
+        let packages_update =
+            apply_changelog_edits(&opened_pr, &packages_update, &update_request, &repo).unwrap();
+        let changelog = packages_update.updates()[0].1.changelog.clone().unwrap();
+        assert_eq!(fs_err::read_to_string(&changelog_path).unwrap(), changelog);
+        changelog_parser::last_release_from_str(&changelog)
+            .unwrap()
+            .unwrap()
+            .notes()
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn edited_changelog_is_applied() {
+        let notes = apply_edits(
+            "### Added\n\n- feature a",
+            "### Added\n\n- feature a",
+            "### Added\n\n- feature A",
+        );
+        assert_eq!(notes, "### Added\n\n- feature A");
+    }
+
+    #[test]
+    fn edited_changelog_is_merged_with_new_changes() {
+        let notes = apply_edits(
+            "### Added\n\n- feature a\n- feature b",
+            "### Added\n\n- feature a\n- feature b\n\n### Fixed\n\n- fix c",
+            "### Added\n\n- feature A\n- feature b",
+        );
+        assert_eq!(
+            notes,
+            "### Added\n\n- feature A\n- feature b\n\n### Fixed\n\n- fix c"
+        );
+    }
+
+    #[test]
+    fn unedited_changelog_does_not_overwrite_new_changes() {
+        let notes = apply_edits(
+            "### Added\n\n- feature a",
+            "### Added\n\n- feature a\n- feature b",
+            "### Added\n\n- feature a",
+        );
+        assert_eq!(notes, "### Added\n\n- feature a\n- feature b");
+    }
+
+    #[test]
+    fn conflicting_changelog_edits_are_discarded() {
+        let notes = apply_edits(
+            "### Added\n\n- feature a",
+            "### Added\n\n- feature a\n- feature b",
+            "### Added\n\n- feature A",
+        );
+        assert_eq!(notes, "### Added\n\n- feature a\n- feature b");
+    }
+
+    #[test]
+    fn only_cherry_pick_failures_keep_the_pr() {
+        let conflict = anyhow::anyhow!("conflict")
+            .context(ContributorCommitsConflict)
+            .context("failed to update pr");
+        assert!(is_contributor_commits_conflict(&conflict));
+        let edit_error = anyhow::anyhow!("cannot edit pr 3");
+        assert!(!is_contributor_commits_conflict(&edit_error));
+    }
+
+    #[test]
+    fn release_branch_of_group_is_recognized() {
+        let prefix = "release-plz-foo-";
+        assert!(is_release_branch(
+            "release-plz-foo-2024-01-01T00-00-00Z",
+            prefix
+        ));
+        assert!(!is_release_branch(
+            "release-plz-foo-bar-2024-01-01T00-00-00Z",
+            prefix
+        ));
+        assert!(!is_release_branch(
+            "release-plz-2024-01-01T00-00-00Z",
+            prefix
+        ));
+        // The name of the package `foo-2024` starts with a digit after the prefix.
+        assert!(!is_release_branch(
+            "release-plz-foo-2024-2024-01-01T00-00-00Z",
+            prefix
+        ));
+        assert!(!is_release_branch(
+            "release-plz-foo-2024-01-01T00-00-00Z-tmp-123",
+            prefix
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::pr_parser::changelog_with_markers;
 use crate::root_repo_path_from_manifest_dir;
 use crate::semver_check::SemverCheck;
 use crate::{tmp_repo::TempRepo, PackagePath, UpdateRequest, UpdateResult};
@@ -8,6 +9,7 @@ use cargo_utils::LocalManifest;
 use cargo_utils::{upgrade_requirement, CARGO_TOML};
 use git_cmd::Repo;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
 use std::iter;
 use tracing::{info, warn};
 
@@ -35,6 +37,55 @@ impl PackagesUpdate {
         self.workspace_version = Some(workspace_version);
     }
 
+    /// Keep only the updates of the given `packages`.
+    /// The workspace version is updated only if one of the kept packages
+    /// is in `workspace_version_pkgs`, i.e. it inherits the workspace version.
+    pub(crate) fn retain_packages(
+        &mut self,
+        packages: &BTreeSet<String>,
+        workspace_version_pkgs: &HashSet<String>,
+    ) {
+        self.updates.retain(|(p, _)| packages.contains(&p.name));
+        let updates_workspace_version = self
+            .updates
+            .iter()
+            .any(|(p, _)| workspace_version_pkgs.contains(&p.name));
+        if !updates_workspace_version {
+            self.workspace_version = None;
+        }
+    }
+
+    /// Names of the updated packages that inherit the workspace version.
+    pub(crate) fn workspace_version_packages(&self) -> anyhow::Result<HashSet<String>> {
+        let mut packages = HashSet::new();
+        for (p, _) in &self.updates {
+            let local_manifest = LocalManifest::try_new(&p.package_path()?.join(CARGO_TOML))?;
+            if local_manifest.version_is_inherited() {
+                packages.insert(p.name.clone());
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Move the updated packages from the project in `old_root` to the copy
+    /// of the project in `new_root`.
+    pub(crate) fn move_to(
+        &mut self,
+        old_root: &Utf8Path,
+        new_root: &Utf8Path,
+    ) -> anyhow::Result<()> {
+        let canonical_old_root = old_root.canonicalize_utf8()?;
+        for (p, _) in &mut self.updates {
+            let relative_path = p
+                .manifest_path
+                .strip_prefix(old_root)
+                .or_else(|_| p.manifest_path.strip_prefix(&canonical_old_root))
+                .with_context(|| format!("package {} isn't in the project {old_root}", p.name))?;
+            p.manifest_path = new_root.join(relative_path);
+        }
+        Ok(())
+    }
+
     pub fn updates(&self) -> &[(Package, UpdateResult)] {
         &self.updates
     }
@@ -100,7 +151,7 @@ impl PackagesUpdate {
                         "{}<blockquote>\n\n## {}\n\n{}\n</blockquote>\n\n",
                         entry_prefix,
                         release.title(),
-                        release.notes()
+                        changelog_with_markers(&package.name, release.notes())
                     )
                 }
                 Ok(None) => {
@@ -171,14 +222,24 @@ pub async fn update(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, Te
     let (packages_to_update, repository) = crate::next_versions(input)
         .await
         .context("failed to determine next versions")?;
+    write_updates(input, &packages_to_update)?;
+    Ok((packages_to_update, repository))
+}
+
+/// Write the versions and the changelogs of the `packages_to_update`
+/// in the project of the `input`.
+pub(crate) fn write_updates(
+    input: &UpdateRequest,
+    packages_to_update: &PackagesUpdate,
+) -> anyhow::Result<()> {
     let local_manifest_path = input.local_manifest();
     let local_metadata = cargo_utils::get_manifest_metadata(local_manifest_path)?;
     // Read packages from `local_metadata` to update the manifest of local
     // workspace dependencies.
     let all_packages: Vec<Package> = cargo_utils::workspace_members(&local_metadata)?.collect();
     let all_packages_ref: Vec<&Package> = all_packages.iter().collect();
-    update_manifests(&packages_to_update, local_manifest_path, &all_packages_ref)?;
-    update_changelogs(input, &packages_to_update)?;
+    update_manifests(packages_to_update, local_manifest_path, &all_packages_ref)?;
+    update_changelogs(input, packages_to_update)?;
     if !packages_to_update.updates.is_empty() {
         let local_manifest_dir = input.local_manifest_dir()?;
         update_cargo_lock(local_manifest_dir, input.should_update_dependencies())?;
@@ -189,8 +250,7 @@ pub async fn update(input: &UpdateRequest) -> anyhow::Result<(PackagesUpdate, Te
             info!("the repository is already up-to-date");
         }
     }
-
-    Ok((packages_to_update, repository))
+    Ok(())
 }
 
 fn update_manifests(
@@ -418,11 +478,13 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
 
             ## [1.1.1] - 2015-05-15
 
+            <!-- release-plz-changelog-start: foo -->
             ### Fixed
             - myfix
 
             ### Other
             - simple update
+            <!-- release-plz-changelog-end: foo -->
             </blockquote>
 
             ## `bar`
@@ -430,11 +492,13 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
 
             ## [1.1.1] - 2015-05-15
 
+            <!-- release-plz-changelog-start: bar -->
             ### Fixed
             - myfix
 
             ### Other
             - simple update
+            <!-- release-plz-changelog-end: bar -->
             </blockquote>
 
         "#]]
@@ -483,14 +547,136 @@ and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0
 
             ## [1.1.1] - 2015-05-15
 
+            <!-- release-plz-changelog-start: foo -->
             ### Fixed
             - myfix
 
             ### Other
             - simple update
+            <!-- release-plz-changelog-end: foo -->
             </blockquote>
 
         "#]]
         .assert_eq(&pkgs.changes(false));
     }
+
+    fn packages_update(packages: &[&str]) -> PackagesUpdate {
+        let updates = packages
+            .iter()
+            .map(|name| {
+                (
+                    fake_package::FakePackage::new(*name).into(),
+                    UpdateResult {
+                        version: Version::new(0, 2, 0),
+                        changelog: None,
+                        semver_check: SemverCheck::Skipped,
+                    },
+                )
+            })
+            .collect();
+        let mut packages_update = PackagesUpdate::new(updates);
+        packages_update.with_workspace_version(Version::new(0, 2, 0));
+        packages_update
+    }
+
+    fn names(packages_update: &PackagesUpdate) -> Vec<&str> {
+        packages_update
+            .updates()
+            .iter()
+            .map(|(p, _)| p.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn workspace_version_is_kept_if_retained_package_inherits_it() {
+        let mut packages_update = packages_update(&["a", "b", "c"]);
+        let workspace_version_pkgs = HashSet::from(["a".to_string()]);
+        packages_update.retain_packages(
+            &BTreeSet::from(["a".to_string(), "b".to_string()]),
+            &workspace_version_pkgs,
+        );
+        assert_eq!(names(&packages_update), ["a", "b"]);
+        assert_eq!(
+            packages_update.workspace_version(),
+            Some(&Version::new(0, 2, 0))
+        );
+    }
+
+    #[test]
+    fn workspace_version_is_removed_if_no_retained_package_inherits_it() {
+        let mut packages_update = packages_update(&["a", "b", "c"]);
+        let workspace_version_pkgs = HashSet::from(["a".to_string()]);
+        packages_update
+            .retain_packages(&BTreeSet::from(["c".to_string()]), &workspace_version_pkgs);
+        assert_eq!(names(&packages_update), ["c"]);
+        assert_eq!(packages_update.workspace_version(), None);
+    }
+
+    #[test]
+    fn updates_of_a_group_are_written_in_a_copy_of_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        fs_err::write(
+            root.join(CARGO_TOML),
+            "[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n\n[workspace.package]\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        for (name, version) in [
+            ("a", "version.workspace = true"),
+            ("b", "version = \"0.1.0\""),
+        ] {
+            fs_err::create_dir_all(root.join(name).join("src")).unwrap();
+            fs_err::write(
+                root.join(name).join(CARGO_TOML),
+                format!("[package]\nname = \"{name}\"\n{version}\nedition = \"2021\"\n"),
+            )
+            .unwrap();
+            fs_err::write(root.join(name).join("src/lib.rs"), "").unwrap();
+        }
+        Repo::init(root);
+
+        let metadata = cargo_utils::get_manifest_metadata(&root.join(CARGO_TOML)).unwrap();
+        let updates = ["a", "b"]
+            .into_iter()
+            .map(|name| {
+                let package = metadata
+                    .workspace_packages()
+                    .into_iter()
+                    .find(|p| p.name == name)
+                    .unwrap()
+                    .clone();
+                let update = UpdateResult {
+                    version: Version::new(0, 2, 0),
+                    changelog: None,
+                    semver_check: SemverCheck::Skipped,
+                };
+                (package, update)
+            })
+            .collect();
+        let mut packages_update = PackagesUpdate::new(updates);
+        packages_update.with_workspace_version(Version::new(0, 2, 0));
+        let workspace_version_pkgs = packages_update.workspace_version_packages().unwrap();
+        assert_eq!(workspace_version_pkgs, HashSet::from(["a".to_string()]));
+        packages_update
+            .retain_packages(&BTreeSet::from(["b".to_string()]), &workspace_version_pkgs);
+
+        let copy_parent = crate::copy_to_temp_dir(root).unwrap();
+        let copy_root = crate::new_project_root(root, copy_parent.path()).unwrap();
+        packages_update.move_to(root, &copy_root).unwrap();
+        let copy_metadata =
+            cargo_utils::get_manifest_metadata(&copy_root.join(CARGO_TOML)).unwrap();
+        write_updates(
+            &UpdateRequest::new(copy_metadata).unwrap(),
+            &packages_update,
+        )
+        .unwrap();
+
+        let manifest =
+            |root: &Utf8Path, path: &str| fs_err::read_to_string(root.join(path)).unwrap();
+        assert!(manifest(&copy_root, "b/Cargo.toml").contains("version = \"0.2.0\""));
+        // The workspace version isn't updated, because `a` isn't in the group.
+        assert!(manifest(&copy_root, CARGO_TOML).contains("version = \"0.1.0\""));
+        // The original project isn't changed.
+        assert!(manifest(root, "b/Cargo.toml").contains("version = \"0.1.0\""));
+    }
 }
@@ -0,0 +1,448 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Context;
+use cargo_metadata::{semver::Version, Package};
+use git_cmd::Repo;
+use secrecy::{ExposeSecret, SecretString};
+use serde::Serialize;
+use tracing::{info, instrument, warn};
+
+use super::release::{get_git_client, registry_indexes};
+use crate::{
+    cargo::{is_published, run_cargo},
+    git::backend::{GitClient, ReleaseEdit},
+    Project, ReleaseRequest,
+};
+
+/// Placeholder used to find the version in a git tag.
+const VERSION_PLACEHOLDER: &str = "__RELEASE_PLZ_VERSION__";
+
+#[derive(Debug)]
+pub struct YankRequest {
+    /// Configuration used to release the packages.
+    /// It's used to find the registries, the tokens and the git tags.
+    release_request: ReleaseRequest,
+    spec: YankSpec,
+    tag_action: YankTagAction,
+    git_release_action: YankGitReleaseAction,
+}
+
+/// What to yank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YankSpec {
+    /// Versions of the given packages.
+    Packages(BTreeMap<String, Version>),
+    /// Packages released with the given git tag.
+    Tag(String),
+}
+
+/// What to do with the git tag of the yanked version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YankTagAction {
+    /// Leave the git tag as it is.
+    #[default]
+    Keep,
+    /// Delete the git tag locally and in the remote.
+    Delete,
+    /// Create and push the `<tag>-yanked` tag, pointing to the same commit of the git tag.
+    Mark,
+}
+
+/// What to do with the GitHub/Gitea/GitLab release of the yanked version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YankGitReleaseAction {
+    /// Leave the git release as it is.
+    #[default]
+    Keep,
+    /// Turn the git release into a draft. Not supported by GitLab.
+    Draft,
+    /// Delete the git release.
+    Delete,
+}
+
+impl YankRequest {
+    pub fn new(release_request: ReleaseRequest, spec: YankSpec) -> Self {
+        Self {
+            release_request,
+            spec,
+            tag_action: YankTagAction::default(),
+            git_release_action: YankGitReleaseAction::default(),
+        }
+    }
+
+    pub fn with_tag_action(mut self, tag_action: YankTagAction) -> Self {
+        self.tag_action = tag_action;
+        self
+    }
+
+    pub fn with_git_release_action(mut self, git_release_action: YankGitReleaseAction) -> Self {
+        self.git_release_action = git_release_action;
+        self
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct Yank {
+    yanks: Vec<PackageYank>,
+}
+
+#[derive(Serialize)]
+pub struct PackageYank {
+    package_name: String,
+    version: Version,
+    /// Git tag of the yanked version.
+    tag: String,
+    /// Registries where the version was yanked.
+    /// `crates.io` is used for the default registry.
+    registries: Vec<String>,
+}
+
+/// Yank the packages from their registries and, optionally,
+/// delete or mark their git tags and git releases.
+#[instrument(skip(input))]
+pub async fn yank(input: &YankRequest) -> anyhow::Result<Option<Yank>> {
+    let release_request = &input.release_request;
+    let project = release_request.project()?;
+    let repo = Repo::new(release_request.workspace_root())?;
+    let packages = packages_to_yank(&project, &input.spec)?;
+
+    let mut package_yanks = vec![];
+    for package in &packages {
+        let git_tag = project.git_tag(&package.name, &package.version.to_string());
+        let registries = yank_package(release_request, package).await?;
+        package_yanks.push(PackageYank {
+            package_name: package.name.clone(),
+            version: package.version.clone(),
+            tag: git_tag,
+            registries,
+        });
+    }
+
+    // Packages might share the same git tag.
+    let tags: BTreeSet<&str> = package_yanks.iter().map(|y| y.tag.as_str()).collect();
+    if input.git_release_action != YankGitReleaseAction::Keep {
+        let git_client = get_git_client(release_request)?;
+        for tag in &tags {
+            update_git_release(
+                &git_client,
+                tag,
+                input.git_release_action,
+                release_request.is_dry_run(),
+            )
+            .await?;
+        }
+    }
+    for tag in &tags {
+        update_git_tag(&repo, tag, input.tag_action, release_request.is_dry_run())?;
+    }
+
+    if release_request.is_dry_run() {
+        return Ok(None);
+    }
+    let yank = (!package_yanks.is_empty()).then_some(Yank {
+        yanks: package_yanks,
+    });
+    Ok(yank)
+}
+
+/// Return the workspace packages to yank, with the version to yank.
+fn packages_to_yank(project: &Project, spec: &YankSpec) -> anyhow::Result<Vec<Package>> {
+    let packages = project.publishable_packages();
+    let packages_to_yank: Vec<Package> = match spec {
+        YankSpec::Packages(versions) => versions
+            .iter()
+            .map(|(name, version)| {
+                let package = packages
+                    .iter()
+                    .find(|p| &p.name == name)
+                    .with_context(|| format!("package `{name}` not found in the workspace"))?;
+                Ok(with_version(package, version.clone()))
+            })
+            .collect::<anyhow::Result<_>>()?,
+        YankSpec::Tag(tag) => packages
+            .iter()
+            .filter_map(|package| {
+                let tag_template = project.git_tag(&package.name, VERSION_PLACEHOLDER);
+                version_in_tag(tag, &tag_template).map(|version| with_version(package, version))
+            })
+            .collect(),
+    };
+    anyhow::ensure!(
+        !packages_to_yank.is_empty(),
+        "no package to yank found for {spec:?}"
+    );
+    Ok(packages_to_yank)
+}
+
+fn with_version(package: &Package, version: Version) -> Package {
+    let mut package = package.clone();
+    package.version = version;
+    package
+}
+
+/// Extract the version from `tag`, where `tag_template` is the git tag
+/// rendered with [`VERSION_PLACEHOLDER`] as version.
+fn version_in_tag(tag: &str, tag_template: &str) -> Option<Version> {
+    let (prefix, suffix) = tag_template.split_once(VERSION_PLACEHOLDER)?;
+    let version = tag.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    Version::parse(version).ok()
+}
+
+/// Yank the package from all its registries.
+/// Return the names of the registries where the package was yanked.
+async fn yank_package(input: &ReleaseRequest, package: &Package) -> anyhow::Result<Vec<String>> {
+    let registry_indexes = registry_indexes(package, input.registry().map(ToOwned::to_owned))
+        .context("can't determine registry indexes")?;
+    let mut yanked_registries = vec![];
+    for mut registry in registry_indexes {
+        let registry_name = registry.name.as_deref().unwrap_or("crates.io").to_string();
+        let token = input.find_registry_token(registry.name.as_deref())?;
+        if !is_published(
+            &mut registry.index,
+            package,
+            input.publish_timeout(),
+            &token,
+        )
+        .await
+        .context("can't determine if package is published")?
+        {
+            warn!(
+                "{} {}: not published in {registry_name}, skipping",
+                package.name, package.version
+            );
+            continue;
+        }
+        if input.is_dry_run() {
+            info!(
+                "{} {}: aborting yank from {registry_name} due to dry run",
+                package.name, package.version
+            );
+        } else {
+            run_cargo_yank(input, package, registry.name.as_deref(), &token)?;
+            info!(
+                "{} {}: yanked from {registry_name}",
+                package.name, package.version
+            );
+        }
+        yanked_registries.push(registry_name);
+    }
+    Ok(yanked_registries)
+}
+
+fn run_cargo_yank(
+    input: &ReleaseRequest,
+    package: &Package,
+    registry: Option<&str>,
+    token: &Option<SecretString>,
+) -> anyhow::Result<()> {
+    let version = package.version.to_string();
+    let mut args = vec!["yank", "--version", &version, &package.name];
+    if let Some(registry) = registry {
+        args.push("--registry");
+        args.push(registry);
+    }
+    if let Some(token) = token {
+        args.push("--token");
+        args.push(token.expose_secret());
+    }
+    let output = run_cargo(input.workspace_root(), &args).context("failed to run cargo yank")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to yank {} {}: {}",
+        package.name,
+        package.version,
+        output.stderr
+    );
+    Ok(())
+}
+
+async fn update_git_release(
+    git_client: &GitClient,
+    tag: &str,
+    action: YankGitReleaseAction,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let Some(release) = git_client.get_release_by_tag(tag).await? else {
+        warn!("git release of tag {tag} not found, skipping");
+        return Ok(());
+    };
+    if dry_run {
+        info!("{tag}: aborting git release update due to dry run");
+        return Ok(());
+    }
+    match action {
+        YankGitReleaseAction::Keep => {}
+        YankGitReleaseAction::Draft => {
+            git_client
+                .edit_release(&release, ReleaseEdit::new().with_draft(true))
+                .await?;
+            info!("{tag}: git release turned into a draft");
+        }
+        YankGitReleaseAction::Delete => {
+            git_client.delete_release(&release).await?;
+            info!("{tag}: git release deleted");
+        }
+    }
+    Ok(())
+}
+
+fn update_git_tag(
+    repo: &Repo,
+    tag: &str,
+    action: YankTagAction,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    if action == YankTagAction::Keep {
+        return Ok(());
+    }
+    if !repo.tag_exists(tag)? {
+        warn!("git tag {tag} not found, skipping");
+        return Ok(());
+    }
+    if dry_run {
+        info!("{tag}: aborting git tag update due to dry run");
+        return Ok(());
+    }
+    match action {
+        YankTagAction::Keep => {}
+        YankTagAction::Delete => {
+            repo.delete_tag_in_remote(tag)?;
+            repo.delete_tag(tag)?;
+            info!("{tag}: git tag deleted");
+        }
+        YankTagAction::Mark => {
+            let yanked_tag = format!("{tag}-yanked");
+            let commit = repo
+                .get_tag_commit(tag)
+                .with_context(|| format!("can't find commit of git tag {tag}"))?;
+            repo.git(&[
+                "tag",
+                "-m",
+                &format!("chore: Yank {tag}"),
+                &yanked_tag,
+                &commit,
+            ])?;
+            repo.push(&yanked_tag)?;
+            info!("{tag}: git tag marked as yanked with {yanked_tag}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+    use crate::{git::backend::GitBackend, GitHub};
+
+    /// GitHub client with a mocked release of tag `v1.0.0`.
+    /// The release of tag `v2.0.0` doesn't exist.
+    async fn github_with_release() -> (MockServer, GitClient) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases/tags/v1.0.0"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "id": 1, "tag_name": "v1.0.0" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases/tags/v2.0.0"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        let client = GitClient::new(GitBackend::Github(github)).unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn yanked_git_release_is_turned_into_draft() {
+        let (server, client) = github_with_release().await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/releases/1"))
+            .and(body_json(json!({ "draft": true })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        update_git_release(&client, "v1.0.0", YankGitReleaseAction::Draft, false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn yanked_git_release_is_deleted() {
+        let (server, client) = github_with_release().await;
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/releases/1"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        update_git_release(&client, "v1.0.0", YankGitReleaseAction::Delete, false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn git_release_is_not_changed_in_dry_run() {
+        let (server, client) = github_with_release().await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&server)
+            .await;
+        update_git_release(&client, "v1.0.0", YankGitReleaseAction::Delete, true)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_git_release_is_skipped() {
+        let (server, client) = github_with_release().await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&server)
+            .await;
+        update_git_release(&client, "v2.0.0", YankGitReleaseAction::Delete, false)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn version_is_extracted_from_tag() {
+        let template = format!("my-crate-v{VERSION_PLACEHOLDER}");
+        assert_eq!(
+            version_in_tag("my-crate-v1.2.3-rc.1", &template),
+            Some(Version::parse("1.2.3-rc.1").unwrap())
+        );
+        assert_eq!(version_in_tag("other-crate-v1.2.3", &template), None);
+        assert_eq!(version_in_tag("my-crate-vfoo", &template), None);
+    }
+
+    #[test]
+    fn version_is_extracted_from_tag_with_suffix() {
+        let template = format!("v{VERSION_PLACEHOLDER}-stable");
+        assert_eq!(
+            version_in_tag("v0.1.0-stable", &template),
+            Some(Version::parse("0.1.0").unwrap())
+        );
+    }
+}
@@ -14,6 +14,9 @@ pub struct PackageDownloader {
     packages: Vec<String>,
     directory: String,
     registry: Option<String>,
+    /// Version requirement of the packages to download, e.g. `=1.2.3`.
+    /// If [`Option::None`], download the latest version.
+    version: Option<String>,
     cargo_cwd: Option<Utf8PathBuf>,
 }
 
@@ -26,6 +29,7 @@ impl PackageDownloader {
             packages: packages.into_iter().map(Into::into).collect(),
             directory: directory.into(),
             registry: None,
+            version: None,
             cargo_cwd: None,
         }
     }
@@ -37,6 +41,15 @@ impl PackageDownloader {
         }
     }
 
+    /// Download the latest version matching the `version` requirement,
+    /// instead of the latest version.
+    pub fn with_version(self, version: String) -> Self {
+        Self {
+            version: Some(version),
+            ..self
+        }
+    }
+
     pub fn with_cargo_cwd(self, cargo_cwd: Utf8PathBuf) -> Self {
         Self {
             cargo_cwd: Some(cargo_cwd),
@@ -57,7 +70,7 @@ impl PackageDownloader {
         let crates: Vec<Crate> = self
             .packages
             .iter()
-            .map(|package_name| Crate::new(package_name.to_string(), None))
+            .map(|package_name| Crate::new(package_name.to_string(), self.version.clone()))
             .collect();
         let mut cloner_builder = Cloner::builder()
             .with_directory(&self.directory)
@@ -127,6 +140,22 @@ mod tests {
         assert_eq!(&packages[1].name, second_package);
     }
 
+    #[test]
+    #[ignore]
+    fn latest_package_matching_version_is_downloaded() {
+        let package_name = "rand";
+        let temp_dir = tempdir().unwrap();
+        let directory = temp_dir.as_ref().to_str().expect("invalid tempdir path");
+        let packages = PackageDownloader::new([package_name], directory)
+            .with_version("^0.7".to_string())
+            .download()
+            .unwrap();
+        let rand = &packages[0];
+        assert_eq!(rand.name, package_name);
+        assert_eq!((rand.version.major, rand.version.minor), (0, 7));
+        assert!(rand.version.patch >= 3);
+    }
+
     #[test]
     #[ignore]
     fn downloading_non_existing_package_does_not_error() {
@@ -1,8 +1,12 @@
-use crate::git::{gitea_client::Gitea, gitlab_client::GitLab};
-use crate::{GitHub, GitReleaseInfo};
+use crate::git::{bitbucket_client::Bitbucket, gitea_client::Gitea, gitlab_client::GitLab};
+use crate::{GitHub, GitReleaseInfo, GitReleaseUpdate};
 
-use crate::pr::Pr;
+use crate::git::github_graphql;
+use crate::pr::{MergeMethod, Pr};
+use crate::release_assets::ReleaseAsset;
 use anyhow::Context;
+use base64::prelude::*;
+use git_cmd::Repo;
 use http::StatusCode;
 use itertools::Itertools;
 use reqwest::header::HeaderMap;
@@ -19,6 +23,7 @@ pub enum GitBackend {
     Github(GitHub),
     Gitea(Gitea),
     Gitlab(GitLab),
+    Bitbucket(Bitbucket),
 }
 
 impl GitBackend {
@@ -27,6 +32,7 @@ impl GitBackend {
             GitBackend::Github(g) => g.default_headers(),
             GitBackend::Gitea(g) => g.default_headers(),
             GitBackend::Gitlab(g) => g.default_headers(),
+            GitBackend::Bitbucket(g) => g.default_headers(),
         }
     }
 }
@@ -36,6 +42,20 @@ pub enum BackendType {
     Github,
     Gitea,
     Gitlab,
+    Bitbucket,
+}
+
+impl BackendType {
+    /// Maximum number of characters of the body of a PR.
+    pub fn max_pr_body_len(self) -> usize {
+        match self {
+            // Gitea doesn't document a limit, so we use the GitHub one.
+            Self::Github | Self::Gitea => 65_536,
+            Self::Gitlab => 1_048_576,
+            // Bitbucket Cloud doesn't document a limit, so we use the Bitbucket Data Center one.
+            Self::Bitbucket => 32_768,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -70,6 +90,84 @@ pub struct Author {
     pub login: String,
 }
 
+#[derive(Deserialize, Debug)]
+struct RemoteMilestone {
+    id: u64,
+    /// Only returned by GitHub.
+    number: Option<u64>,
+    title: String,
+}
+
+/// Comment of a PR. In GitLab, it's called note.
+#[derive(Deserialize, Debug)]
+pub struct PrComment {
+    pub id: u64,
+    pub body: Option<String>,
+}
+
+/// Merge request of GitLab, with the fields needed to enable auto-merge.
+#[derive(Deserialize, Debug)]
+struct GitLabMrPipeline {
+    head_pipeline: Option<GitLabPipeline>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitLabPipeline {
+    id: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct GitLabUser {
+    id: u64,
+}
+
+/// Page of a paginated Bitbucket response.
+#[derive(Deserialize, Debug)]
+struct BitbucketPage<T> {
+    values: Vec<T>,
+}
+
+/// Comment of a Bitbucket PR.
+#[derive(Deserialize, Debug)]
+struct BitbucketComment {
+    id: u64,
+    content: BitbucketCommentContent,
+}
+
+#[derive(Deserialize, Debug)]
+struct BitbucketCommentContent {
+    raw: Option<String>,
+}
+
+// https://developer.atlassian.com/cloud/bitbucket/rest/api-group-pullrequests/#api-repositories-workspace-repo-slug-pullrequests-pull-request-id-commits-get
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketCommit {
+    pub hash: String,
+    pub author: BitbucketCommitAuthor,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketCommitAuthor {
+    /// Bitbucket user with the email of the commit author, if any.
+    pub user: Option<BitbucketUser>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketUser {
+    pub nickname: String,
+}
+
+impl From<BitbucketCommit> for PrCommit {
+    fn from(value: BitbucketCommit) -> Self {
+        PrCommit {
+            author: value.author.user.map(|user| Author {
+                login: user.nickname,
+            }),
+            sha: value.hash,
+        }
+    }
+}
+
 // https://docs.gitlab.com/ee/api/merge_requests.html#get-single-merge-request-commits
 #[derive(Deserialize, Clone, Debug)]
 pub struct GitLabMrCommit {
@@ -103,8 +201,14 @@ pub struct GitPr {
     pub number: u64,
     pub html_url: Url,
     pub head: Commit,
+    /// Branch the PR wants to be merged into.
+    #[serde(default)]
+    pub base: Option<PrBase>,
     pub title: String,
     pub body: Option<String>,
+    /// GraphQL id of the pull request. Only returned by GitHub.
+    #[serde(default)]
+    pub node_id: Option<String>,
 }
 
 /// Pull request.
@@ -112,6 +216,11 @@ impl GitPr {
     pub fn branch(&self) -> &str {
         self.head.ref_field.as_str()
     }
+
+    /// Branch the PR wants to be merged into, if returned by the git forge.
+    pub fn base_branch(&self) -> Option<&str> {
+        self.base.as_ref().map(|base| base.ref_field.as_str())
+    }
 }
 
 impl From<GitLabMr> for GitPr {
@@ -128,8 +237,10 @@ impl From<GitLabMr> for GitPr {
                 ref_field: value.source_branch,
                 sha: value.sha,
             },
+            base: value.target_branch.map(|ref_field| PrBase { ref_field }),
             title: value.title,
             body,
+            node_id: None,
             user: Author {
                 login: value.author.username,
             },
@@ -137,6 +248,72 @@ impl From<GitLabMr> for GitPr {
     }
 }
 
+/// Bitbucket pull request.
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketPr {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub author: BitbucketUser,
+    pub source: BitbucketPrEndpoint,
+    pub destination: BitbucketPrEndpoint,
+    pub links: BitbucketPrLinks,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketPrEndpoint {
+    pub branch: BitbucketBranch,
+    pub commit: Option<BitbucketCommitRef>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketBranch {
+    pub name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketCommitRef {
+    pub hash: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketPrLinks {
+    pub html: BitbucketLink,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BitbucketLink {
+    pub href: Url,
+}
+
+impl From<BitbucketPr> for GitPr {
+    fn from(value: BitbucketPr) -> Self {
+        let body = if value.description.is_empty() {
+            None
+        } else {
+            Some(value.description)
+        };
+        GitPr {
+            number: value.id,
+            html_url: value.links.html.href,
+            head: Commit {
+                ref_field: value.source.branch.name,
+                sha: value.source.commit.map(|c| c.hash).unwrap_or_default(),
+            },
+            base: Some(PrBase {
+                ref_field: value.destination.branch.name,
+            }),
+            title: value.title,
+            body,
+            node_id: None,
+            user: Author {
+                login: value.author.nickname,
+            },
+        }
+    }
+}
+
 /// Merge request.
 #[derive(Deserialize, Clone, Debug)]
 pub struct GitLabMr {
@@ -145,6 +322,7 @@ pub struct GitLabMr {
     pub web_url: Url,
     pub sha: String,
     pub source_branch: String,
+    pub target_branch: Option<String>,
     pub title: String,
     pub description: String,
 }
@@ -168,6 +346,7 @@ impl From<GitPr> for GitLabMr {
             web_url: value.html_url,
             sha: value.head.sha,
             source_branch: value.head.ref_field,
+            target_branch: value.base.map(|base| base.ref_field),
             title: value.title,
             description,
         }
@@ -181,6 +360,12 @@ pub struct Commit {
     pub sha: String,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct PrBase {
+    #[serde(rename = "ref")]
+    pub ref_field: String,
+}
+
 /// Representation of a remote contributor.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
 pub struct RemoteCommit {
@@ -208,6 +393,24 @@ pub struct PrEdit {
     state: Option<String>,
 }
 
+#[derive(Serialize)]
+pub struct BitbucketPrEdit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl From<PrEdit> for BitbucketPrEdit {
+    /// Bitbucket PRs can't be closed by editing them, so the state is ignored.
+    fn from(value: PrEdit) -> Self {
+        BitbucketPrEdit {
+            title: value.title,
+            description: value.body,
+        }
+    }
+}
+
 impl From<PrEdit> for GitLabMrEdit {
     fn from(value: PrEdit) -> Self {
         GitLabMrEdit {
@@ -243,6 +446,85 @@ impl PrEdit {
     }
 }
 
+/// Release of a git forge.
+#[derive(Deserialize, Clone, Debug)]
+pub struct RemoteRelease {
+    /// Id of the release. GitLab identifies releases by tag, so it's [`Option::None`].
+    pub id: Option<u64>,
+    pub tag_name: String,
+    /// GitLab doesn't support draft releases.
+    #[serde(default)]
+    pub draft: bool,
+    /// Url used by GitHub to upload release assets.
+    pub upload_url: Option<String>,
+    /// Url of the release web page in GitHub and Gitea.
+    html_url: Option<String>,
+    /// Links of the release in GitLab.
+    #[serde(rename = "_links")]
+    gitlab_links: Option<GitlabReleaseLinks>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct GitlabReleaseLinks {
+    #[serde(rename = "self")]
+    self_url: Option<String>,
+}
+
+impl RemoteRelease {
+    /// Url of the release web page.
+    pub fn html_url(&self) -> Option<&str> {
+        self.html_url
+            .as_deref()
+            .or_else(|| self.gitlab_links.as_ref()?.self_url.as_deref())
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct ReleaseEdit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prerelease: Option<bool>,
+    /// Only supported by GitHub.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    make_latest: Option<String>,
+}
+
+impl ReleaseEdit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    pub fn with_prerelease(mut self, prerelease: bool) -> Self {
+        self.prerelease = Some(prerelease);
+        self
+    }
+
+    pub fn with_latest(mut self, latest: bool) -> Self {
+        self.make_latest = Some(latest.to_string());
+        self
+    }
+}
+
 impl GitClient {
     pub fn new(backend: GitBackend) -> anyhow::Result<Self> {
         let client = {
@@ -264,6 +546,7 @@ impl GitClient {
             GitBackend::Github(g) => (BackendType::Github, g.remote),
             GitBackend::Gitea(g) => (BackendType::Gitea, g.remote),
             GitBackend::Gitlab(g) => (BackendType::Gitlab, g.remote),
+            GitBackend::Bitbucket(g) => (BackendType::Bitbucket, g.remote),
         };
         Ok(Self {
             remote,
@@ -276,22 +559,84 @@ impl GitClient {
         match self.backend {
             BackendType::Github | BackendType::Gitlab => "per_page",
             BackendType::Gitea => "limit",
+            BackendType::Bitbucket => "pagelen",
         }
     }
 
     /// Creates a GitHub/Gitea release.
-    pub async fn create_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
+    pub async fn create_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<RemoteRelease> {
         match self.backend {
             BackendType::Github | BackendType::Gitea => {
                 self.create_github_release(release_info).await
             }
             BackendType::Gitlab => self.create_gitlab_release(release_info).await,
+            BackendType::Bitbucket => Err(bitbucket_releases_error()),
         }
         .context("Failed to create release")
     }
 
+    /// Create the release of the tag. If the release exists already,
+    /// `update` decides whether to update it, keep it as it is or fail.
+    pub async fn create_or_update_release(
+        &self,
+        release_info: &GitReleaseInfo,
+        update: GitReleaseUpdate,
+    ) -> anyhow::Result<RemoteRelease> {
+        let Some(mut release) = self
+            .get_release_by_tag(&release_info.git_tag)
+            .await
+            .context("can't check if the release exists")?
+        else {
+            return self.create_release(release_info).await;
+        };
+        match update {
+            GitReleaseUpdate::Fail => anyhow::bail!(
+                "the release of tag {} already exists. Set `git_release_update` to `overwrite` or `skip` to release anyway",
+                release_info.git_tag
+            ),
+            GitReleaseUpdate::Skip => {
+                info!(
+                    "release of tag {} already exists, skipping it",
+                    release_info.git_tag
+                );
+            }
+            GitReleaseUpdate::Overwrite => {
+                info!(
+                    "release of tag {} already exists, updating it",
+                    release_info.git_tag
+                );
+                let mut release_edit = ReleaseEdit::new()
+                    .with_name(&release_info.release_name)
+                    .with_body(&release_info.release_body);
+                if self.backend != BackendType::Gitlab {
+                    release_edit = release_edit
+                        .with_draft(release_info.draft)
+                        .with_prerelease(release_info.pre_release);
+                    release.draft = release_info.draft;
+                }
+                if let Some(latest) = release_info.latest {
+                    match self.backend {
+                        BackendType::Github => release_edit = release_edit.with_latest(latest),
+                        BackendType::Gitea => anyhow::bail!(
+                            "Gitea does not support the `git_release_latest` option"
+                        ),
+                        BackendType::Gitlab | BackendType::Bitbucket => {}
+                    }
+                }
+                self.edit_release(&release, release_edit).await?;
+            }
+        }
+        Ok(release)
+    }
+
     /// Same as Gitea.
-    pub async fn create_github_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
+    pub async fn create_github_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<RemoteRelease> {
         if release_info.latest.is_some() && self.backend == BackendType::Gitea {
             anyhow::bail!("Gitea does not support the `git_release_latest` option");
         }
@@ -318,11 +663,16 @@ impl GitClient {
                     }
                 }
                 anyhow::anyhow!(e)
-            })?;
-        Ok(())
+            })?
+            .json()
+            .await
+            .context("can't parse created release")
     }
 
-    pub async fn create_gitlab_release(&self, release_info: &GitReleaseInfo) -> anyhow::Result<()> {
+    pub async fn create_gitlab_release(
+        &self,
+        release_info: &GitReleaseInfo,
+    ) -> anyhow::Result<RemoteRelease> {
         #[derive(Serialize)]
         pub struct GitlabReleaseOption<'a> {
             name: &'a str,
@@ -349,99 +699,372 @@ impl GitClient {
                     }
                 }
                 anyhow::anyhow!(e)
-            })?;
-        Ok(())
+            })?
+            .json()
+            .await
+            .context("can't parse created release")
     }
 
-    pub fn pulls_url(&self) -> String {
-        match self.backend {
-            BackendType::Github | BackendType::Gitea => {
-                format!("{}/pulls", self.repo_url())
-            }
-            BackendType::Gitlab => {
-                format!("{}/merge_requests", self.repo_url())
+    /// Upload the `assets` to the `release`.
+    /// GitLab stores the assets in the generic package registry
+    /// with the given `package_name` and `version`, and links them to the release.
+    pub async fn upload_release_assets(
+        &self,
+        release: &RemoteRelease,
+        package_name: &str,
+        version: &str,
+        assets: &[ReleaseAsset],
+    ) -> anyhow::Result<()> {
+        for asset in assets {
+            match self.backend {
+                BackendType::Github => self.upload_github_release_asset(release, asset).await,
+                BackendType::Gitea => self.upload_gitea_release_asset(release, asset).await,
+                BackendType::Gitlab => {
+                    self.upload_gitlab_release_asset(release, package_name, version, asset)
+                        .await
+                }
+                BackendType::Bitbucket => Err(bitbucket_releases_error()),
             }
+            .with_context(|| format!("failed to upload release asset {}", asset.name))?;
+            info!("uploaded release asset {}", asset.name);
         }
+        Ok(())
     }
 
-    pub fn issues_url(&self) -> String {
-        format!("{}/issues", self.repo_url())
+    async fn upload_github_release_asset(
+        &self,
+        release: &RemoteRelease,
+        asset: &ReleaseAsset,
+    ) -> anyhow::Result<()> {
+        let upload_url = release
+            .upload_url
+            .as_deref()
+            .context("GitHub didn't return the upload url of the release")?;
+        // The upload url is a hypermedia template, e.g. `https://uploads.github.com/repos/o/r/releases/1/assets{?name,label}`
+        let upload_url = upload_url
+            .split_once('{')
+            .map_or(upload_url, |(url, _template)| url);
+        self.client
+            .post(upload_url)
+            .query(&[("name", &asset.name)])
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(asset.content.clone())
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        Ok(())
     }
 
-    pub fn param_value_pr_state_open(&self) -> &'static str {
-        match self.backend {
-            BackendType::Github | BackendType::Gitea => "open",
-            BackendType::Gitlab => "opened",
-        }
+    async fn upload_gitea_release_asset(
+        &self,
+        release: &RemoteRelease,
+        asset: &ReleaseAsset,
+    ) -> anyhow::Result<()> {
+        let (boundary, body) = multipart_file("attachment", &asset.name, &asset.content);
+        self.client
+            .post(format!("{}/assets", self.release_url(release)?))
+            .query(&[("name", &asset.name)])
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        Ok(())
     }
 
-    fn repo_url(&self) -> String {
-        match self.backend {
-            BackendType::Github | BackendType::Gitea => {
-                format!(
-                    "{}repos/{}",
-                    self.remote.base_url,
-                    self.remote.owner_slash_repo()
-                )
-            }
-            BackendType::Gitlab => self.remote.base_url.to_string(),
-        }
+    async fn upload_gitlab_release_asset(
+        &self,
+        release: &RemoteRelease,
+        package_name: &str,
+        version: &str,
+        asset: &ReleaseAsset,
+    ) -> anyhow::Result<()> {
+        let encode =
+            |s: &str| -> String { url::form_urlencoded::byte_serialize(s.as_bytes()).collect() };
+        let package_url = format!(
+            "{}/packages/generic/{}/{}/{}",
+            self.repo_url(),
+            encode(package_name),
+            encode(version),
+            encode(&asset.name)
+        );
+        self.client
+            .put(&package_url)
+            .body(asset.content.clone())
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        self.client
+            .post(format!("{}/assets/links", self.release_url(release)?))
+            .json(&json!({
+                "name": asset.name,
+                "url": package_url,
+                "link_type": "package",
+            }))
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        Ok(())
     }
 
-    /// Get all opened Prs which branch starts with the given `branch_prefix`.
-    pub async fn opened_prs(&self, branch_prefix: &str) -> anyhow::Result<Vec<GitPr>> {
+    /// Get the release associated to the given git tag, if any.
+    /// Find the release of the given tag, including draft releases.
+    /// GitHub and Gitea don't return draft releases when searching by tag,
+    /// so the most recent releases are searched, too.
+    pub async fn get_release_by_tag(&self, tag: &str) -> anyhow::Result<Option<RemoteRelease>> {
+        if self.backend == BackendType::Bitbucket {
+            // Bitbucket doesn't support releases.
+            return Ok(None);
+        }
+        if let Some(release) = self.published_release_by_tag(tag).await? {
+            return Ok(Some(release));
+        }
+        if self.backend == BackendType::Gitlab {
+            // GitLab doesn't support draft releases.
+            return Ok(None);
+        }
         let mut page = 1;
-        let page_size = 30;
-        let mut release_prs: Vec<GitPr> = vec![];
         loop {
-            debug!(
-                "Loading prs from {}, page {page}",
-                self.remote.owner_slash_repo()
-            );
-            let prs: Vec<GitPr> = self
-                .opened_prs_page(page, page_size)
-                .await
-                .context("Failed to retrieve open PRs")?;
-            let prs_len = prs.len();
-            let current_release_prs: Vec<GitPr> = prs
-                .into_iter()
-                .filter(|pr| pr.head.ref_field.starts_with(branch_prefix))
-                .collect();
-            release_prs.extend(current_release_prs);
-            if prs_len < page_size {
-                break;
+            let releases = self.releases_page(page).await?;
+            // The page size might be limited by the server (e.g. in Gitea),
+            // so we stop at the first empty page.
+            if releases.is_empty() {
+                return Ok(None);
+            }
+            if let Some(release) = releases.into_iter().find(|r| r.tag_name == tag) {
+                return Ok(Some(release));
             }
             page += 1;
         }
-        Ok(release_prs)
     }
 
-    async fn opened_prs_page(&self, page: i32, page_size: usize) -> anyhow::Result<Vec<GitPr>> {
-        let resp = self
-            .client
-            .get(self.pulls_url())
-            .query(&[("state", self.param_value_pr_state_open())])
+    async fn releases_page(&self, page: u32) -> anyhow::Result<Vec<RemoteRelease>> {
+        self.client
+            .get(format!("{}/releases", self.repo_url()))
             .query(&[("page", page)])
-            .query(&[(self.per_page(), page_size)])
+            .query(&[(self.per_page(), 100)])
             .send()
             .await?
             .successful_status()
-            .await?;
-
-        self.prs_from_response(resp).await
+            .await?
+            .json()
+            .await
+            .context("can't parse releases")
     }
 
-    async fn prs_from_response(&self, resp: Response) -> anyhow::Result<Vec<GitPr>> {
-        match self.backend {
-            BackendType::Github | BackendType::Gitea => {
-                resp.json().await.context("failed to parse pr")
-            }
-            BackendType::Gitlab => {
-                let gitlab_mrs: Vec<GitLabMr> =
-                    resp.json().await.context("failed to parse gitlab mr")?;
-                let git_prs: Vec<GitPr> = gitlab_mrs.into_iter().map(|mr| mr.into()).collect();
-                Ok(git_prs)
-            }
+    async fn published_release_by_tag(&self, tag: &str) -> anyhow::Result<Option<RemoteRelease>> {
+        let response = self
+            .client
+            .get(self.release_by_tag_url(tag)?)
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let release = response
+            .successful_status()
+            .await?
+            .json()
+            .await
+            .context("can't parse release")?;
+        Ok(Some(release))
+    }
+
+    pub async fn edit_release(
+        &self,
+        release: &RemoteRelease,
+        release_edit: ReleaseEdit,
+    ) -> anyhow::Result<()> {
+        let request = match self.backend {
+            BackendType::Github | BackendType::Gitea => self
+                .client
+                .patch(self.release_url(release)?)
+                .json(&release_edit),
+            BackendType::Gitlab => {
+                if release_edit.draft.is_some() {
+                    anyhow::bail!("GitLab does not support draft releases");
+                }
+                #[derive(Serialize)]
+                struct GitlabReleaseEdit {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    name: Option<String>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    description: Option<String>,
+                }
+                let gitlab_release_edit = GitlabReleaseEdit {
+                    name: release_edit.name,
+                    description: release_edit.body,
+                };
+                self.client
+                    .put(self.release_url(release)?)
+                    .json(&gitlab_release_edit)
+            }
+            BackendType::Bitbucket => return Err(bitbucket_releases_error()),
+        };
+        request
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("can't edit release of tag {}", release.tag_name))?;
+        Ok(())
+    }
+
+    /// Delete the release. The git tag is not deleted.
+    pub async fn delete_release(&self, release: &RemoteRelease) -> anyhow::Result<()> {
+        self.client
+            .delete(self.release_url(release)?)
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("can't delete release of tag {}", release.tag_name))?;
+        Ok(())
+    }
+
+    fn release_by_tag_url(&self, tag: &str) -> anyhow::Result<String> {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                Ok(format!("{}/releases/tags/{tag}", self.repo_url()))
+            }
+            BackendType::Gitlab => {
+                let tag: String = url::form_urlencoded::byte_serialize(tag.as_bytes()).collect();
+                Ok(format!("{}/releases/{tag}", self.repo_url()))
+            }
+            BackendType::Bitbucket => Err(bitbucket_releases_error()),
+        }
+    }
+
+    fn release_url(&self, release: &RemoteRelease) -> anyhow::Result<String> {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                let id = release
+                    .id
+                    .with_context(|| format!("release of tag {} has no id", release.tag_name))?;
+                Ok(format!("{}/releases/{id}", self.repo_url()))
+            }
+            BackendType::Gitlab | BackendType::Bitbucket => {
+                self.release_by_tag_url(&release.tag_name)
+            }
+        }
+    }
+
+    pub fn pulls_url(&self) -> String {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                format!("{}/pulls", self.repo_url())
+            }
+            BackendType::Gitlab => {
+                format!("{}/merge_requests", self.repo_url())
+            }
+            BackendType::Bitbucket => {
+                format!("{}/pullrequests", self.repo_url())
+            }
+        }
+    }
+
+    pub fn issues_url(&self) -> String {
+        format!("{}/issues", self.repo_url())
+    }
+
+    pub fn param_value_pr_state_open(&self) -> &'static str {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => "open",
+            BackendType::Gitlab => "opened",
+            BackendType::Bitbucket => "OPEN",
+        }
+    }
+
+    fn repo_url(&self) -> String {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                format!(
+                    "{}repos/{}",
+                    self.remote.base_url,
+                    self.remote.owner_slash_repo()
+                )
+            }
+            BackendType::Gitlab => self.remote.base_url.to_string(),
+            BackendType::Bitbucket => {
+                format!(
+                    "{}repositories/{}",
+                    self.remote.base_url,
+                    self.remote.owner_slash_repo()
+                )
+            }
+        }
+    }
+
+    /// Get all opened Prs which branch starts with the given `branch_prefix`.
+    pub async fn opened_prs(&self, branch_prefix: &str) -> anyhow::Result<Vec<GitPr>> {
+        let mut page = 1;
+        let page_size = 30;
+        let mut release_prs: Vec<GitPr> = vec![];
+        loop {
+            debug!(
+                "Loading prs from {}, page {page}",
+                self.remote.owner_slash_repo()
+            );
+            let prs: Vec<GitPr> = self
+                .opened_prs_page(page, page_size)
+                .await
+                .context("Failed to retrieve open PRs")?;
+            let prs_len = prs.len();
+            let current_release_prs: Vec<GitPr> = prs
+                .into_iter()
+                .filter(|pr| pr.head.ref_field.starts_with(branch_prefix))
+                .collect();
+            release_prs.extend(current_release_prs);
+            if prs_len < page_size {
+                break;
+            }
+            page += 1;
+        }
+        Ok(release_prs)
+    }
+
+    async fn opened_prs_page(&self, page: i32, page_size: usize) -> anyhow::Result<Vec<GitPr>> {
+        let resp = self
+            .client
+            .get(self.pulls_url())
+            .query(&[("state", self.param_value_pr_state_open())])
+            .query(&[("page", page)])
+            .query(&[(self.per_page(), page_size)])
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+
+        self.prs_from_response(resp).await
+    }
+
+    async fn prs_from_response(&self, resp: Response) -> anyhow::Result<Vec<GitPr>> {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                resp.json().await.context("failed to parse pr")
+            }
+            BackendType::Gitlab => {
+                let gitlab_mrs: Vec<GitLabMr> =
+                    resp.json().await.context("failed to parse gitlab mr")?;
+                let git_prs: Vec<GitPr> = gitlab_mrs.into_iter().map(|mr| mr.into()).collect();
+                Ok(git_prs)
+            }
+            BackendType::Bitbucket => {
+                let bitbucket_prs: BitbucketPage<BitbucketPr> =
+                    resp.json().await.context("failed to parse bitbucket prs")?;
+                Ok(bitbucket_prs
+                    .values
+                    .into_iter()
+                    .map(|pr| pr.into())
+                    .collect())
+            }
         }
     }
 
@@ -454,16 +1077,32 @@ impl GitClient {
                 let gitlab_mr: GitLabMr = resp.json().await.context("failed to parse gitlab mr")?;
                 Ok(gitlab_mr.into())
             }
+            BackendType::Bitbucket => {
+                let bitbucket_pr: BitbucketPr =
+                    resp.json().await.context("failed to parse bitbucket pr")?;
+                Ok(bitbucket_pr.into())
+            }
         }
     }
 
     #[instrument(skip(self))]
     pub async fn close_pr(&self, pr_number: u64) -> anyhow::Result<()> {
         debug!("closing pr #{pr_number}");
-        let edit = PrEdit::new().with_state(self.closed_pr_state());
-        self.edit_pr(pr_number, edit)
-            .await
-            .with_context(|| format!("cannot close pr {pr_number}"))?;
+        if self.backend == BackendType::Bitbucket {
+            // Bitbucket PRs are closed by declining them.
+            self.client
+                .post(format!("{}/{pr_number}/decline", self.pulls_url()))
+                .send()
+                .await?
+                .successful_status()
+                .await
+                .with_context(|| format!("cannot close pr {pr_number}"))?;
+        } else {
+            let edit = PrEdit::new().with_state(self.closed_pr_state());
+            self.edit_pr(pr_number, edit)
+                .await
+                .with_context(|| format!("cannot close pr {pr_number}"))?;
+        }
         info!("closed pr #{pr_number}");
         Ok(())
     }
@@ -472,6 +1111,7 @@ impl GitClient {
         match self.backend {
             BackendType::Github | BackendType::Gitea => "closed",
             BackendType::Gitlab => "close",
+            BackendType::Bitbucket => "DECLINED",
         }
     }
 
@@ -487,6 +1127,12 @@ impl GitClient {
                     .put(format!("{}/merge_requests/{pr_number}", self.repo_url()))
                     .json(&edit_mr)
             }
+            BackendType::Bitbucket => {
+                let edit_pr: BitbucketPrEdit = pr_edit.into();
+                self.client
+                    .put(format!("{}/{pr_number}", self.pulls_url()))
+                    .json(&edit_pr)
+            }
         };
         debug!("editing pr: {req:?}");
 
@@ -516,79 +1162,592 @@ impl GitClient {
                 "source_branch": pr.branch,
                 "draft": pr.draft,
             }),
+            BackendType::Bitbucket => json!({
+                "title": pr.title,
+                "description": pr.body,
+                "source": { "branch": { "name": pr.branch } },
+                "destination": { "branch": { "name": pr.base_branch } },
+                "close_source_branch": true,
+                "draft": pr.draft,
+            }),
+        };
+
+        let rep = self
+            .client
+            .post(self.pulls_url())
+            .json(&json_body)
+            .send()
+            .await
+            .context("failed when sending the response")?
+            .successful_status()
+            .await
+            .context("received unexpected response")?;
+
+        let git_pr: GitPr = match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                rep.json().await.context("Failed to parse PR")?
+            }
+            BackendType::Gitlab => {
+                let gitlab_mr: GitLabMr = rep.json().await.context("Failed to parse Gitlab MR")?;
+                gitlab_mr.into()
+            }
+            BackendType::Bitbucket => {
+                let bitbucket_pr: BitbucketPr =
+                    rep.json().await.context("Failed to parse Bitbucket PR")?;
+                bitbucket_pr.into()
+            }
+        };
+
+        info!("opened pr: {}", git_pr.html_url);
+        self.add_labels(pr, git_pr.number)
+            .await
+            .context("Failed to add labels")?;
+        // The PR is already open, so don't fail if it can't be assigned.
+        if let Err(e) = self.assign_pr(pr, git_pr.number).await {
+            warn!(
+                "cannot set reviewers, assignees and milestone of pr {}: {e:?}",
+                git_pr.html_url
+            );
+        }
+        Ok(git_pr)
+    }
+
+    #[instrument(skip(self, pr))]
+    async fn add_labels(&self, pr: &Pr, pr_number: u64) -> anyhow::Result<()> {
+        if pr.labels.is_empty() {
+            return Ok(());
+        }
+        match self.backend {
+            BackendType::Github => {
+                self.client
+                    .post(format!("{}/{}/labels", self.issues_url(), pr_number))
+                    .json(&json!({
+                        "labels": pr.labels
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+
+                Ok(())
+            }
+            BackendType::Gitlab => {
+                self.client
+                    .put(format!("{}/{}", self.pulls_url(), pr_number))
+                    .json(&json!({
+                        "add_labels": pr.labels.iter().join(",")
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+
+                Ok(())
+            }
+            BackendType::Gitea | BackendType::Bitbucket => {
+                warn!("PR labels are only supported on Github and Gitlab");
+                Ok(())
+            }
+        }
+    }
+
+    /// Commit all the changes (except typechanges) that are present in the repository
+    /// on the remote `branch`, using the API of the git forge.
+    /// Commits created with the API are "Verified" without a signing key.
+    #[instrument(skip(self, repo))]
+    pub async fn commit_changes(
+        &self,
+        repo: &Repo,
+        message: &str,
+        branch: &str,
+    ) -> anyhow::Result<()> {
+        match self.backend {
+            BackendType::Github => {
+                github_graphql::commit_changes(self, repo, message, branch).await?;
+            }
+            BackendType::Gitea => {
+                // https://docs.gitea.com/api/1.22/#tag/repository/operation/repoChangeFiles
+                let files: Vec<_> = file_changes(repo)
+                    .await?
+                    .iter()
+                    .map(FileChange::to_gitea_file)
+                    .collect();
+                self.client
+                    .post(format!("{}/contents", self.repo_url()))
+                    .json(&json!({
+                        "branch": branch,
+                        "message": message,
+                        "files": files,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .context("failed to commit changes with the Gitea contents API")?;
+            }
+            BackendType::Gitlab => {
+                // https://docs.gitlab.com/ee/api/commits.html#create-a-commit-with-multiple-files-and-actions
+                let actions: Vec<_> = file_changes(repo)
+                    .await?
+                    .iter()
+                    .map(FileChange::to_gitlab_action)
+                    .collect();
+                self.client
+                    .post(format!("{}/repository/commits", self.repo_url()))
+                    .json(&json!({
+                        "branch": branch,
+                        "commit_message": message,
+                        "actions": actions,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .context("failed to commit changes with the GitLab commits API")?;
+            }
+            BackendType::Bitbucket => {
+                // https://developer.atlassian.com/cloud/bitbucket/rest/api-group-source/#api-repositories-workspace-repo-slug-src-post
+                // We send a url encoded form instead of a multipart one because the
+                // retry middleware can't clone streaming bodies.
+                let mut form = vec![
+                    ("message".to_string(), message.to_string()),
+                    ("branch".to_string(), branch.to_string()),
+                ];
+                for change in file_changes(repo).await? {
+                    match change {
+                        FileChange::Create { path, content }
+                        | FileChange::Update { path, content, .. } => {
+                            let content = String::from_utf8(content).with_context(|| {
+                                format!("can't commit non UTF-8 file {path} to Bitbucket")
+                            })?;
+                            form.push((path, content));
+                        }
+                        // Files listed in the `files` field without content are deleted.
+                        FileChange::Delete { path, .. } => form.push(("files".to_string(), path)),
+                    }
+                }
+                self.client
+                    .post(format!("{}/src", self.repo_url()))
+                    .form(&form)
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await
+                    .context("failed to commit changes with the Bitbucket source API")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge the PR once the CI passes.
+    #[instrument(skip(self, pr), fields(pr_number = pr.number))]
+    pub async fn enable_auto_merge(
+        &self,
+        pr: &GitPr,
+        merge_method: MergeMethod,
+    ) -> anyhow::Result<()> {
+        match self.backend {
+            BackendType::Github => {
+                let node_id = pr
+                    .node_id
+                    .as_deref()
+                    .context("GitHub didn't return the node id of the PR")?;
+                github_graphql::enable_auto_merge(self, node_id, merge_method).await?;
+            }
+            BackendType::Gitea => {
+                let merge_style = match merge_method {
+                    MergeMethod::Merge => "merge",
+                    MergeMethod::Squash => "squash",
+                    MergeMethod::Rebase => "rebase",
+                };
+                self.client
+                    .post(format!("{}/{}/merge", self.pulls_url(), pr.number))
+                    .json(&json!({
+                        "Do": merge_style,
+                        "merge_when_checks_succeed": true,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+            BackendType::Gitlab => {
+                if merge_method == MergeMethod::Rebase {
+                    warn!("GitLab doesn't support the `rebase` merge method for merge requests: the merge method of the project settings is used");
+                }
+                // Without a pipeline, GitLab merges the merge request immediately.
+                let mr: GitLabMrPipeline = self
+                    .client
+                    .get(format!("{}/{}", self.pulls_url(), pr.number))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?
+                    .json()
+                    .await
+                    .context("can't parse merge request")?;
+                let pipeline = mr.head_pipeline.with_context(|| {
+                    format!(
+                        "merge request !{} has no pipeline, so it would be merged without waiting for the CI",
+                        pr.number
+                    )
+                })?;
+                debug!("merge request !{} has pipeline {}", pr.number, pipeline.id);
+                self.client
+                    .put(format!("{}/{}/merge", self.pulls_url(), pr.number))
+                    .json(&json!({
+                        // `auto_merge` replaces `merge_when_pipeline_succeeds` since GitLab 17.11.
+                        "auto_merge": true,
+                        "merge_when_pipeline_succeeds": true,
+                        "squash": merge_method == MergeMethod::Squash,
+                    }))
+                    .send()
+                    .await?
+                    .successful_status()
+                    .await?;
+            }
+            BackendType::Bitbucket => {
+                anyhow::bail!("Bitbucket doesn't support enabling auto-merge with its API")
+            }
+        }
+        info!("enabled auto-merge on pr {}", pr.html_url);
+        Ok(())
+    }
+
+    /// Request the reviews and set the assignees and the milestone of the PR.
+    /// The assignees and the milestone replace the existing ones.
+    #[instrument(skip(self, pr))]
+    pub async fn assign_pr(&self, pr: &Pr, pr_number: u64) -> anyhow::Result<()> {
+        let pr_edit = match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                self.request_reviews(pr, pr_number).await?;
+                self.github_assignment(pr).await?
+            }
+            BackendType::Gitlab => self.gitlab_assignment(pr).await?,
+            BackendType::Bitbucket => {
+                if !pr.reviewers.is_empty()
+                    || !pr.team_reviewers.is_empty()
+                    || !pr.assignees.is_empty()
+                    || pr.milestone.is_some()
+                {
+                    warn!("PR reviewers, assignees and milestones aren't supported on Bitbucket");
+                }
+                return Ok(());
+            }
+        };
+        if pr_edit.is_empty() {
+            return Ok(());
+        }
+        let req = match self.backend {
+            // In GitHub, assignees and milestone are properties of the issue associated to the PR.
+            BackendType::Github | BackendType::Gitea => {
+                self.client
+                    .patch(format!("{}/{}", self.issues_url(), pr_number))
+            }
+            BackendType::Gitlab | BackendType::Bitbucket => {
+                self.client
+                    .put(format!("{}/{}", self.pulls_url(), pr_number))
+            }
+        };
+        req.json(&pr_edit)
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .context("cannot set assignees and milestone")?;
+        Ok(())
+    }
+
+    /// Request the reviews of the GitHub/Gitea PR.
+    async fn request_reviews(&self, pr: &Pr, pr_number: u64) -> anyhow::Result<()> {
+        if pr.reviewers.is_empty() && pr.team_reviewers.is_empty() {
+            return Ok(());
+        }
+        self.client
+            .post(format!(
+                "{}/{}/requested_reviewers",
+                self.pulls_url(),
+                pr_number
+            ))
+            .json(&json!({
+                "reviewers": pr.reviewers,
+                "team_reviewers": pr.team_reviewers,
+            }))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .context("cannot request reviews")?;
+        Ok(())
+    }
+
+    /// Assignees and milestone of the GitHub/Gitea PR.
+    async fn github_assignment(
+        &self,
+        pr: &Pr,
+    ) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+        let mut pr_edit = serde_json::Map::new();
+        if !pr.assignees.is_empty() {
+            pr_edit.insert("assignees".to_string(), json!(pr.assignees));
+        }
+        if let Some(milestone) = self.pr_milestone_id(pr).await? {
+            pr_edit.insert("milestone".to_string(), json!(milestone));
+        }
+        Ok(pr_edit)
+    }
+
+    /// Reviewers, assignees and milestone of the GitLab MR.
+    async fn gitlab_assignment(
+        &self,
+        pr: &Pr,
+    ) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+        if !pr.team_reviewers.is_empty() {
+            warn!("team reviewers are only supported on GitHub and Gitea");
+        }
+        let mut pr_edit = serde_json::Map::new();
+        if !pr.reviewers.is_empty() {
+            let reviewer_ids = self.gitlab_user_ids(&pr.reviewers).await?;
+            pr_edit.insert("reviewer_ids".to_string(), json!(reviewer_ids));
+        }
+        if !pr.assignees.is_empty() {
+            let assignee_ids = self.gitlab_user_ids(&pr.assignees).await?;
+            pr_edit.insert("assignee_ids".to_string(), json!(assignee_ids));
+        }
+        if let Some(milestone) = self.pr_milestone_id(pr).await? {
+            pr_edit.insert("milestone_id".to_string(), json!(milestone));
+        }
+        Ok(pr_edit)
+    }
+
+    async fn pr_milestone_id(&self, pr: &Pr) -> anyhow::Result<Option<u64>> {
+        match &pr.milestone {
+            Some(title) => self.milestone_id(title).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the id of the open milestone with the given title.
+    /// For GitHub, it's the milestone number.
+    async fn milestone_id(&self, title: &str) -> anyhow::Result<u64> {
+        let mut page = 1;
+        let milestone = loop {
+            let milestones = self.milestones_page(title, page).await?;
+            // The page size might be limited by the server (e.g. in Gitea),
+            // so we stop at the first empty page.
+            anyhow::ensure!(
+                !milestones.is_empty(),
+                "cannot find open milestone `{title}`"
+            );
+            if let Some(milestone) = milestones.into_iter().find(|m| m.title == title) {
+                break milestone;
+            }
+            page += 1;
+        };
+        match self.backend {
+            BackendType::Github => milestone.number,
+            BackendType::Gitea | BackendType::Gitlab | BackendType::Bitbucket => Some(milestone.id),
+        }
+        .with_context(|| format!("milestone `{title}` has no number"))
+    }
+
+    async fn milestones_page(
+        &self,
+        title: &str,
+        page: u32,
+    ) -> anyhow::Result<Vec<RemoteMilestone>> {
+        let req = self
+            .client
+            .get(format!("{}/milestones", self.repo_url()))
+            .query(&[("page", page)])
+            .query(&[(self.per_page(), 100)]);
+        let req = match self.backend {
+            BackendType::Github | BackendType::Gitea => req.query(&[("state", "open")]),
+            BackendType::Gitlab => req.query(&[("state", "active"), ("title", title)]),
+            BackendType::Bitbucket => anyhow::bail!("Bitbucket doesn't support milestones"),
+        };
+        req.send()
+            .await?
+            .successful_status()
+            .await?
+            .json()
+            .await
+            .context("failed to parse milestones")
+    }
+
+    /// Get the ids of the GitLab users with the given usernames.
+    async fn gitlab_user_ids(&self, usernames: &[String]) -> anyhow::Result<Vec<u64>> {
+        let base_url = self.remote.base_url.as_str();
+        let api_url = base_url
+            .rsplit_once("/projects/")
+            .map_or(base_url, |(api_url, _project)| api_url);
+        let mut ids = vec![];
+        for username in usernames {
+            let users: Vec<GitLabUser> = self
+                .client
+                .get(format!("{api_url}/users"))
+                .query(&[("username", username)])
+                .send()
+                .await?
+                .successful_status()
+                .await?
+                .json()
+                .await
+                .context("failed to parse gitlab users")?;
+            let user = users
+                .first()
+                .with_context(|| format!("cannot find GitLab user `{username}`"))?;
+            ids.push(user.id);
+        }
+        Ok(ids)
+    }
+
+    fn pr_comments_url(&self, pr_number: u64) -> String {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                format!("{}/{}/comments", self.issues_url(), pr_number)
+            }
+            BackendType::Gitlab => format!("{}/{}/notes", self.pulls_url(), pr_number),
+            BackendType::Bitbucket => format!("{}/{}/comments", self.pulls_url(), pr_number),
+        }
+    }
+
+    fn pr_comment_url(&self, pr_number: u64, comment_id: u64) -> String {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea => {
+                format!("{}/comments/{comment_id}", self.issues_url())
+            }
+            BackendType::Gitlab | BackendType::Bitbucket => {
+                format!("{}/{comment_id}", self.pr_comments_url(pr_number))
+            }
+        }
+    }
+
+    fn pr_comment_body(&self, body: &str) -> serde_json::Value {
+        match self.backend {
+            BackendType::Github | BackendType::Gitea | BackendType::Gitlab => {
+                json!({ "body": body })
+            }
+            BackendType::Bitbucket => json!({ "content": { "raw": body } }),
+        }
+    }
+
+    /// Comment on the PR, unless a comment with the same body exists already.
+    #[instrument(skip(self, body))]
+    pub async fn comment_pr_once(&self, pr_number: u64, body: &str) -> anyhow::Result<()> {
+        let comments = self.pr_comments(pr_number).await?;
+        if comments.iter().any(|c| c.body.as_deref() == Some(body)) {
+            debug!("pr #{pr_number} already contains the comment");
+            return Ok(());
+        }
+        self.comment_pr(pr_number, body).await
+    }
+
+    /// All the comments of the PR.
+    pub async fn pr_comments(&self, pr_number: u64) -> anyhow::Result<Vec<PrComment>> {
+        let page_size = 100;
+        let mut comments = vec![];
+        let mut page = 1;
+        loop {
+            let page_comments = self.pr_comments_page(pr_number, page, page_size).await?;
+            let page_len = page_comments.len();
+            comments.extend(page_comments);
+            // Gitea returns all the comments of the PR in one page.
+            if page_len < page_size || self.backend == BackendType::Gitea {
+                break;
+            }
+            page += 1;
+        }
+        Ok(comments)
+    }
+
+    async fn pr_comments_page(
+        &self,
+        pr_number: u64,
+        page: u32,
+        page_size: usize,
+    ) -> anyhow::Result<Vec<PrComment>> {
+        let resp = self
+            .client
+            .get(self.pr_comments_url(pr_number))
+            .query(&[("page", page)])
+            .query(&[(self.per_page(), page_size)])
+            .send()
+            .await?
+            .successful_status()
+            .await?;
+        let comments: Vec<PrComment> = match self.backend {
+            BackendType::Github | BackendType::Gitea | BackendType::Gitlab => {
+                resp.json().await.context("failed to parse pr comments")?
+            }
+            BackendType::Bitbucket => {
+                let comments: BitbucketPage<BitbucketComment> = resp
+                    .json()
+                    .await
+                    .context("failed to parse bitbucket pr comments")?;
+                comments
+                    .values
+                    .into_iter()
+                    .map(|c| PrComment {
+                        id: c.id,
+                        body: c.content.raw,
+                    })
+                    .collect()
+            }
         };
+        Ok(comments)
+    }
 
-        let rep = self
-            .client
-            .post(self.pulls_url())
-            .json(&json_body)
+    #[instrument(skip(self, body))]
+    pub async fn comment_pr(&self, pr_number: u64, body: &str) -> anyhow::Result<()> {
+        self.client
+            .post(self.pr_comments_url(pr_number))
+            .json(&self.pr_comment_body(body))
             .send()
-            .await
-            .context("failed when sending the response")?
+            .await?
             .successful_status()
             .await
-            .context("received unexpected response")?;
+            .with_context(|| format!("cannot comment on pr {pr_number}"))?;
+        Ok(())
+    }
 
-        let git_pr: GitPr = match self.backend {
-            BackendType::Github | BackendType::Gitea => {
-                rep.json().await.context("Failed to parse PR")?
-            }
-            BackendType::Gitlab => {
-                let gitlab_mr: GitLabMr = rep.json().await.context("Failed to parse Gitlab MR")?;
-                gitlab_mr.into()
-            }
+    #[instrument(skip(self, body))]
+    pub async fn edit_pr_comment(
+        &self,
+        pr_number: u64,
+        comment_id: u64,
+        body: &str,
+    ) -> anyhow::Result<()> {
+        let url = self.pr_comment_url(pr_number, comment_id);
+        let req = match self.backend {
+            BackendType::Github | BackendType::Gitea => self.client.patch(url),
+            BackendType::Gitlab | BackendType::Bitbucket => self.client.put(url),
         };
-
-        info!("opened pr: {}", git_pr.html_url);
-        self.add_labels(pr, git_pr.number)
+        req.json(&self.pr_comment_body(body))
+            .send()
+            .await?
+            .successful_status()
             .await
-            .context("Failed to add labels")?;
-        Ok(git_pr)
+            .with_context(|| format!("cannot edit comment {comment_id} of pr {pr_number}"))?;
+        Ok(())
     }
 
-    #[instrument(skip(self, pr))]
-    async fn add_labels(&self, pr: &Pr, pr_number: u64) -> anyhow::Result<()> {
-        if pr.labels.is_empty() {
-            return Ok(());
-        }
-        match self.backend {
-            BackendType::Github => {
-                self.client
-                    .post(format!("{}/{}/labels", self.issues_url(), pr_number))
-                    .json(&json!({
-                        "labels": pr.labels
-                    }))
-                    .send()
-                    .await?
-                    .successful_status()
-                    .await?;
-
-                Ok(())
-            }
-            BackendType::Gitlab => {
-                self.client
-                    .put(format!("{}/{}", self.pulls_url(), pr_number))
-                    .json(&json!({
-                        "add_labels": pr.labels.iter().join(",")
-                    }))
-                    .send()
-                    .await?
-                    .successful_status()
-                    .await?;
-
-                Ok(())
-            }
-            BackendType::Gitea => {
-                warn!("PR labels are only supported on Github and Gitlab");
-                Ok(())
-            }
-        }
+    #[instrument(skip(self))]
+    pub async fn delete_pr_comment(&self, pr_number: u64, comment_id: u64) -> anyhow::Result<()> {
+        self.client
+            .delete(self.pr_comment_url(pr_number, comment_id))
+            .send()
+            .await?
+            .successful_status()
+            .await
+            .with_context(|| format!("cannot delete comment {comment_id} of pr {pr_number}"))?;
+        Ok(())
     }
 
     pub async fn pr_commits(&self, pr_number: u64) -> anyhow::Result<Vec<PrCommit>> {
         let resp = self
             .client
             .get(format!("{}/{}/commits", self.pulls_url(), pr_number))
+            .query(&[(self.per_page(), 100)])
             .send()
             .await?
             .successful_status()
@@ -610,6 +1769,20 @@ impl GitClient {
                     .collect();
                 Ok(pr_commits)
             }
+            BackendType::Bitbucket => {
+                let bitbucket_commits: BitbucketPage<BitbucketCommit> = resp
+                    .json()
+                    .await
+                    .context("failed to parse bitbucket pr commits")?;
+                // Bitbucket returns the newest commits first.
+                let pr_commits = bitbucket_commits
+                    .values
+                    .into_iter()
+                    .rev()
+                    .map(|commit| commit.into())
+                    .collect();
+                Ok(pr_commits)
+            }
         }
     }
 
@@ -631,6 +1804,9 @@ impl GitClient {
                     commit
                 )
             }
+            BackendType::Bitbucket => {
+                format!("{}/commit/{}/pullrequests", self.repo_url(), commit)
+            }
         };
 
         let response = self.client.get(url).send().await?;
@@ -666,6 +1842,17 @@ impl GitClient {
                 let git_prs: Vec<GitPr> = gitlab_mrs.into_iter().map(|mr| mr.into()).collect();
                 git_prs
             }
+            BackendType::Bitbucket => {
+                let bitbucket_prs: BitbucketPage<BitbucketPr> = response
+                    .json()
+                    .await
+                    .context("can't parse associated Bitbucket PRs")?;
+                bitbucket_prs
+                    .values
+                    .into_iter()
+                    .map(|pr| pr.into())
+                    .collect()
+            }
         };
 
         let prs_numbers = prs.iter().map(|pr| pr.number).collect::<Vec<_>>();
@@ -696,18 +1883,22 @@ impl GitClient {
 
     pub async fn get_remote_commit(&self, commit: &str) -> Result<RemoteCommit, anyhow::Error> {
         let api_path = self.commits_api_path(commit);
-        let github_commit: GitHubCommit = self
+        let resp = self
             .client
             .get(api_path)
             .send()
             .await?
             .successful_status()
-            .await?
-            .json()
-            .await
-            .context("can't parse commits")?;
+            .await?;
 
-        let username = github_commit.author.and_then(|author| author.login);
+        let username = if self.backend == BackendType::Bitbucket {
+            let bitbucket_commit: BitbucketCommit =
+                resp.json().await.context("can't parse commits")?;
+            bitbucket_commit.author.user.map(|user| user.nickname)
+        } else {
+            let github_commit: GitHubCommit = resp.json().await.context("can't parse commits")?;
+            github_commit.author.and_then(|author| author.login)
+        };
         Ok(RemoteCommit { username })
     }
 
@@ -718,6 +1909,7 @@ impl GitClient {
                 format!("git/{commits_path}")
             }
             BackendType::Github => commits_path.to_string(),
+            BackendType::Bitbucket => "commit/".to_string(),
             BackendType::Gitlab => {
                 unimplemented!("Gitlab support for `release-plz release-pr is not implemented yet")
             }
@@ -745,17 +1937,137 @@ pub struct GitHubCommitAuthor {
 /// Returns the list of contributors for the given commits,
 /// excluding the PR author and bots.
 pub fn contributors_from_commits(commits: &[PrCommit]) -> Vec<String> {
-    let mut contributors = commits
-        .iter()
-        .skip(1) // skip pr author
+    let mut contributors = contributor_commits(commits)
         .flat_map(|commit| &commit.author)
-        .filter(|author| !author.login.ends_with("[bot]")) // ignore bots
         .map(|author| author.login.clone())
         .collect::<Vec<_>>();
     contributors.dedup();
     contributors
 }
 
+/// Returns the commits of the given PR commits
+/// that weren't authored by the PR author or by bots.
+/// Commits whose author isn't linked to an account of the git forge
+/// are authored by contributors, too.
+pub fn contributor_commits(commits: &[PrCommit]) -> impl Iterator<Item = &PrCommit> {
+    commits
+        .iter()
+        .skip(1) // skip pr author
+        .filter(|commit| {
+            !commit
+                .author
+                .as_ref()
+                .is_some_and(|author| author.login.ends_with("[bot]")) // ignore bots
+        })
+}
+
+/// Change of a file of the working directory with respect to `HEAD`.
+#[derive(Debug, PartialEq, Eq)]
+enum FileChange {
+    Create {
+        path: String,
+        content: Vec<u8>,
+    },
+    Update {
+        path: String,
+        content: Vec<u8>,
+        /// SHA of the git blob of the file in `HEAD`.
+        sha: String,
+    },
+    Delete {
+        path: String,
+        /// SHA of the git blob of the file in `HEAD`.
+        sha: String,
+    },
+}
+
+impl FileChange {
+    fn to_gitea_file(&self) -> serde_json::Value {
+        match self {
+            Self::Create { path, content } => {
+                let content = BASE64_STANDARD.encode(content);
+                json!({"operation": "create", "path": path, "content": content})
+            }
+            Self::Update { path, content, sha } => {
+                let content = BASE64_STANDARD.encode(content);
+                json!({"operation": "update", "path": path, "content": content, "sha": sha})
+            }
+            Self::Delete { path, sha } => {
+                json!({"operation": "delete", "path": path, "sha": sha})
+            }
+        }
+    }
+
+    fn to_gitlab_action(&self) -> serde_json::Value {
+        match self {
+            Self::Create { path, content } => json!({
+                "action": "create",
+                "file_path": path,
+                "content": BASE64_STANDARD.encode(content),
+                "encoding": "base64",
+            }),
+            Self::Update { path, content, .. } => json!({
+                "action": "update",
+                "file_path": path,
+                "content": BASE64_STANDARD.encode(content),
+                "encoding": "base64",
+            }),
+            Self::Delete { path, .. } => json!({"action": "delete", "file_path": path}),
+        }
+    }
+}
+
+/// Multipart form with the `content` of the file `file_name` in the `field`.
+/// Returns the boundary and the body of the form.
+/// The body isn't built with the multipart form of reqwest because the retry middleware
+/// can't clone streaming bodies.
+fn multipart_file(field: &str, file_name: &str, content: &[u8]) -> (String, Vec<u8>) {
+    let boundary = format!("release-plz-{:016x}", rand::random::<u64>());
+    let file_name = file_name.replace('"', "%22");
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{field}\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    (boundary, body)
+}
+
+/// Get the changes of the repository, except typechanges.
+async fn file_changes(repo: &Repo) -> anyhow::Result<Vec<FileChange>> {
+    let mut changes = vec![];
+    for path in github_graphql::removed_files(repo)? {
+        let sha = blob_sha(repo, &path)
+            .with_context(|| format!("cannot find removed file `{path}` in HEAD"))?;
+        changes.push(FileChange::Delete { path, sha });
+    }
+    for path in github_graphql::changed_files(repo)? {
+        let realpath = repo.directory().join(&path);
+        // Directories are git submodules: skip them like in the GitHub commit.
+        if realpath.is_dir() {
+            debug!("skipping directory `{realpath}` in git additions");
+            continue;
+        }
+        let content = fs_err::tokio::read(realpath).await?;
+        let change = match blob_sha(repo, &path) {
+            Some(sha) => FileChange::Update { path, content, sha },
+            None => FileChange::Create { path, content },
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+/// SHA of the git blob of the file at `path` in `HEAD`, if the file exists.
+fn blob_sha(repo: &Repo, path: &str) -> Option<String> {
+    repo.git(&["rev-parse", "--verify", "--quiet", &format!("HEAD:{path}")])
+        .ok()
+}
+
+fn bitbucket_releases_error() -> anyhow::Error {
+    anyhow::anyhow!("Bitbucket doesn't support git releases: only the git tag is created")
+}
+
 trait ResponseExt {
     /// Better version of [`reqwest::Response::error_for_status`] that
     /// also captures the response body in the error message. It will most
@@ -785,7 +2097,16 @@ impl ResponseExt for reqwest::Response {
 
 #[cfg(test)]
 mod tests {
+    use wiremock::{
+        matchers::{
+            body_bytes, body_json, body_partial_json, body_string_contains, header, header_regex,
+            method, path, query_param,
+        },
+        Mock, MockServer, ResponseTemplate,
+    };
+
     use super::*;
+    use crate::RepoUrl;
 
     #[test]
     fn contributors_are_extracted_from_commits() {
@@ -816,4 +2137,961 @@ mod tests {
         let contributors = contributors_from_commits(&commits);
         assert_eq!(contributors, vec!["marco"]);
     }
+
+    #[test]
+    fn commits_without_author_are_contributor_commits() {
+        let commit = |author: Option<&str>, sha: &str| PrCommit {
+            author: author.map(|login| Author {
+                login: login.to_string(),
+            }),
+            sha: sha.to_string(),
+        };
+        let commits = vec![
+            commit(None, "release-plz"),
+            commit(Some("release[bot]"), "bot"),
+            commit(None, "unlinked"),
+            commit(Some("marco"), "marco"),
+        ];
+        let shas: Vec<&str> = contributor_commits(&commits)
+            .map(|commit| commit.sha.as_str())
+            .collect();
+        assert_eq!(shas, vec!["unlinked", "marco"]);
+    }
+
+    fn github_client(server: &MockServer) -> GitClient {
+        let github = GitHub::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        GitClient::new(GitBackend::Github(github)).unwrap()
+    }
+
+    fn gitea_client(server: &MockServer) -> GitClient {
+        let repo_url = RepoUrl::new(&format!("{}/owner/repo", server.uri())).unwrap();
+        let gitea = Gitea::new(repo_url, SecretString::from("token")).unwrap();
+        GitClient::new(GitBackend::Gitea(gitea)).unwrap()
+    }
+
+    fn gitlab_client(server: &MockServer) -> GitClient {
+        let repo_url = RepoUrl::new(&format!("{}/owner/repo", server.uri())).unwrap();
+        let gitlab = GitLab::new(repo_url, SecretString::from("token")).unwrap();
+        GitClient::new(GitBackend::Gitlab(gitlab)).unwrap()
+    }
+
+    fn asset() -> ReleaseAsset {
+        ReleaseAsset {
+            name: "a.tar.gz".to_string(),
+            content: b"asset content".to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn github_release_asset_is_uploaded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/uploads/repos/owner/repo/releases/1/assets"))
+            .and(query_param("name", "a.tar.gz"))
+            .and(header("content-type", "application/octet-stream"))
+            .and(body_bytes(b"asset content".to_vec()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release: RemoteRelease = serde_json::from_value(json!({
+            "id": 1,
+            "tag_name": "v1.0.0",
+            "upload_url": format!("{}/uploads/repos/owner/repo/releases/1/assets{{?name,label}}", server.uri()),
+        }))
+        .unwrap();
+        let client = github_client(&server);
+        client
+            .upload_release_assets(&release, "a", "1.0.0", &[asset()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitea_release_asset_is_uploaded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/owner/repo/releases/1/assets"))
+            .and(query_param("name", "a.tar.gz"))
+            .and(header_regex(
+                "content-type",
+                "^multipart/form-data; boundary=release-plz-[0-9a-f]{16}$",
+            ))
+            .and(body_string_contains(
+                "Content-Disposition: form-data; name=\"attachment\"; filename=\"a.tar.gz\"\r\nContent-Type: application/octet-stream\r\n\r\nasset content\r\n--release-plz-",
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release: RemoteRelease =
+            serde_json::from_value(json!({ "id": 1, "tag_name": "v1.0.0" })).unwrap();
+        let client = gitea_client(&server);
+        client
+            .upload_release_assets(&release, "a", "1.0.0", &[asset()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitlab_release_asset_is_uploaded_and_linked() {
+        let server = MockServer::start().await;
+        let package_path = "/api/v4/projects/owner%2Frepo/packages/generic/a/1.0.0/a.tar.gz";
+        Mock::given(method("PUT"))
+            .and(path(package_path))
+            .and(body_bytes(b"asset content".to_vec()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/api/v4/projects/owner%2Frepo/releases/v1.0.0/assets/links",
+            ))
+            .and(body_json(json!({
+                "name": "a.tar.gz",
+                "url": format!("{}{package_path}", server.uri()),
+                "link_type": "package",
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release: RemoteRelease =
+            serde_json::from_value(json!({ "tag_name": "v1.0.0" })).unwrap();
+        let client = gitlab_client(&server);
+        client
+            .upload_release_assets(&release, "a", "1.0.0", &[asset()])
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn multipart_file_is_built() {
+        let (boundary, body) = multipart_file("attachment", "a\".txt", b"content");
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"attachment\"; filename=\"a%22.txt\"\r\nContent-Type: application/octet-stream\r\n\r\ncontent\r\n--{boundary}--\r\n"
+            )
+        );
+    }
+
+    fn release_info() -> GitReleaseInfo {
+        GitReleaseInfo {
+            git_tag: "v1.0.0".to_string(),
+            release_name: "v1.0.0".to_string(),
+            release_body: "### Fixed\n\n- fix bug".to_string(),
+            latest: None,
+            draft: false,
+            pre_release: false,
+        }
+    }
+
+    /// GitHub client with a mocked draft release of tag `v1.0.0`.
+    async fn github_with_draft_release() -> (MockServer, GitClient) {
+        let server = MockServer::start().await;
+        // Draft releases aren't returned when searching by tag.
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases/tags/v1.0.0"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        mount_releases_page(
+            &server,
+            1,
+            json!([
+                { "id": 2, "tag_name": "v1.1.0", "draft": true },
+                { "id": 1, "tag_name": "v1.0.0", "draft": true },
+            ]),
+        )
+        .await;
+        mount_releases_page(&server, 2, json!([])).await;
+        let client = github_client(&server);
+        (server, client)
+    }
+
+    async fn mount_releases_page(server: &MockServer, page: u32, releases: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases"))
+            .and(query_param("page", page.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(releases))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn existing_draft_release_is_overwritten() {
+        let (server, client) = github_with_draft_release().await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/releases/1"))
+            .and(body_json(json!({
+                "name": "v1.0.0",
+                "body": "### Fixed\n\n- fix bug",
+                "draft": false,
+                "prerelease": false,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release = client
+            .create_or_update_release(&release_info(), GitReleaseUpdate::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(release.id, Some(1));
+        assert!(!release.draft);
+    }
+
+    #[tokio::test]
+    async fn draft_release_is_found_by_tag() {
+        let (_server, client) = github_with_draft_release().await;
+        let release = client.get_release_by_tag("v1.0.0").await.unwrap().unwrap();
+        assert_eq!(release.id, Some(1));
+        assert!(release.draft);
+        assert!(client.get_release_by_tag("v2.0.0").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn draft_release_is_found_in_the_next_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases/tags/v1.0.0"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let first_page: Vec<_> = (2..=101)
+            .map(|id| json!({ "id": id, "tag_name": format!("v1.{id}.0"), "draft": true }))
+            .collect();
+        mount_releases_page(&server, 1, json!(first_page)).await;
+        mount_releases_page(
+            &server,
+            2,
+            json!([{ "id": 1, "tag_name": "v1.0.0", "draft": true }]),
+        )
+        .await;
+        let client = github_client(&server);
+        let release = client.get_release_by_tag("v1.0.0").await.unwrap().unwrap();
+        assert_eq!(release.id, Some(1));
+    }
+
+    #[tokio::test]
+    async fn published_release_is_found_by_tag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases/tags/v1.0.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": 1,
+                "tag_name": "v1.0.0",
+                "html_url": "https://github.com/owner/repo/releases/tag/v1.0.0",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The releases aren't listed if the release is found by tag.
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/releases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        let release = client.get_release_by_tag("v1.0.0").await.unwrap().unwrap();
+        assert_eq!(release.id, Some(1));
+        assert_eq!(
+            release.html_url(),
+            Some("https://github.com/owner/repo/releases/tag/v1.0.0")
+        );
+    }
+
+    #[tokio::test]
+    async fn gitlab_release_is_not_found_by_tag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/owner%2Frepo/releases/v1.0.0"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        assert!(client.get_release_by_tag("v1.0.0").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn github_release_is_edited() {
+        let (server, client) = github_with_draft_release().await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/releases/1"))
+            .and(body_json(json!({ "draft": true })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release = client.get_release_by_tag("v1.0.0").await.unwrap().unwrap();
+        client
+            .edit_release(&release, ReleaseEdit::new().with_draft(true))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitlab_release_is_edited() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/owner%2Frepo/releases/v1.0.0"))
+            .and(body_json(json!({ "name": "v1.0.0 (yanked)" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        let release: RemoteRelease =
+            serde_json::from_value(json!({ "tag_name": "v1.0.0" })).unwrap();
+        client
+            .edit_release(&release, ReleaseEdit::new().with_name("v1.0.0 (yanked)"))
+            .await
+            .unwrap();
+        let error = client
+            .edit_release(&release, ReleaseEdit::new().with_draft(true))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "GitLab does not support draft releases");
+    }
+
+    #[tokio::test]
+    async fn github_release_is_deleted() {
+        let (server, client) = github_with_draft_release().await;
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/releases/1"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let release = client.get_release_by_tag("v1.0.0").await.unwrap().unwrap();
+        client.delete_release(&release).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn existing_release_is_not_overwritten_if_update_fails() {
+        let (_server, client) = github_with_draft_release().await;
+        let error = client
+            .create_or_update_release(&release_info(), GitReleaseUpdate::Fail)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("already exists"), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn gitea_auto_merge_is_enabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/owner/repo/pulls/3/merge"))
+            .and(body_json(json!({
+                "Do": "squash",
+                "merge_when_checks_succeed": true,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitea_client(&server);
+        let pr: GitPr = serde_json::from_value(json!({
+            "user": { "login": "release-plz" },
+            "number": 3,
+            "html_url": format!("{}/owner/repo/pulls/3", server.uri()),
+            "head": { "ref": "release-plz-2024-01-01T00-00-00Z", "sha": "abc" },
+            "title": "chore: release",
+            "body": null,
+        }))
+        .unwrap();
+        client
+            .enable_auto_merge(&pr, MergeMethod::Squash)
+            .await
+            .unwrap();
+    }
+
+    fn auto_merge_pr(server: &MockServer) -> GitPr {
+        serde_json::from_value(json!({
+            "user": { "login": "release-plz" },
+            "number": 3,
+            "html_url": format!("{}/owner/repo/pull/3", server.uri()),
+            "head": { "ref": "release-plz-2024-01-01T00-00-00Z", "sha": "abc" },
+            "title": "chore: release",
+            "body": null,
+            "node_id": "PR_node",
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn github_auto_merge_is_enabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "variables": {
+                    "input": { "pullRequestId": "PR_node", "mergeMethod": "SQUASH" }
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        client
+            .enable_auto_merge(&auto_merge_pr(&server), MergeMethod::Squash)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn github_auto_merge_errors_are_returned() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errors": [{ "message": "auto-merge is not allowed" }]
+            })))
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        client
+            .enable_auto_merge(&auto_merge_pr(&server), MergeMethod::Merge)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn gitlab_auto_merge_is_enabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "iid": 3, "head_pipeline": { "id": 10 } })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3/merge"))
+            .and(body_json(json!({
+                "auto_merge": true,
+                "merge_when_pipeline_succeeds": true,
+                "squash": true,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        client
+            .enable_auto_merge(&auto_merge_pr(&server), MergeMethod::Squash)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitlab_mr_without_pipeline_is_not_merged() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "iid": 3, "head_pipeline": null })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3/merge"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        let error = client
+            .enable_auto_merge(&auto_merge_pr(&server), MergeMethod::Merge)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "merge request !3 has no pipeline, so it would be merged without waiting for the CI"
+        );
+    }
+
+    #[tokio::test]
+    async fn existing_comment_is_not_duplicated() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 1, "body": "first" },
+                { "id": 2, "body": "second" },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/issues/3/comments"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        client.comment_pr_once(3, "second").await.unwrap();
+        client.comment_pr_once(3, "third").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn comments_are_read_from_all_pages() {
+        let server = MockServer::start().await;
+        let first_page: Vec<_> = (1..=100)
+            .map(|id| json!({ "id": id, "body": "first" }))
+            .collect();
+        for (page, comments) in [
+            (1, json!(first_page)),
+            (2, json!([{ "id": 101, "body": "second" }])),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3/notes"))
+                .and(query_param("page", page.to_string()))
+                .and(query_param("per_page", "100"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(comments))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        let client = gitlab_client(&server);
+        let comments = client.pr_comments(3).await.unwrap();
+        assert_eq!(comments.len(), 101);
+        assert_eq!(comments[100].body.as_deref(), Some("second"));
+    }
+
+    #[tokio::test]
+    async fn github_comment_is_edited_and_deleted() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/issues/comments/10"))
+            .and(body_json(json!({ "body": "edited" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/repos/owner/repo/issues/comments/11"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        client.edit_pr_comment(3, 10, "edited").await.unwrap();
+        client.delete_pr_comment(3, 11).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitlab_comment_is_edited_and_deleted() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path(
+                "/api/v4/projects/owner%2Frepo/merge_requests/3/notes/10",
+            ))
+            .and(body_json(json!({ "body": "edited" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path(
+                "/api/v4/projects/owner%2Frepo/merge_requests/3/notes/11",
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        client.edit_pr_comment(3, 10, "edited").await.unwrap();
+        client.delete_pr_comment(3, 11).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn bitbucket_comment_is_edited() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/repositories/owner/repo/pullrequests/3/comments/10"))
+            .and(body_json(json!({ "content": { "raw": "edited" } })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        client.edit_pr_comment(3, 10, "edited").await.unwrap();
+    }
+
+    fn pr_with_participants() -> Pr {
+        Pr {
+            base_branch: "main".to_string(),
+            branch: "release-plz-2024-01-01T00-00-00Z".to_string(),
+            title: "chore: release".to_string(),
+            body: String::new(),
+            draft: false,
+            labels: vec![],
+            reviewers: vec!["alice".to_string()],
+            team_reviewers: vec!["owners".to_string()],
+            assignees: vec!["bob".to_string()],
+            milestone: Some("v1".to_string()),
+            auto_merge: None,
+            comments: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn github_pr_is_assigned() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/milestones"))
+            .and(query_param("state", "open"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 100, "number": 1, "title": "v0" },
+                { "id": 200, "number": 2, "title": "v1" },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls/3/requested_reviewers"))
+            .and(body_json(json!({
+                "reviewers": ["alice"],
+                "team_reviewers": ["owners"],
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/owner/repo/issues/3"))
+            .and(body_json(json!({
+                "assignees": ["bob"],
+                "milestone": 2,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        client.assign_pr(&pr_with_participants(), 3).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn milestone_is_searched_in_all_pages() {
+        let server = MockServer::start().await;
+        for (page, milestones) in [
+            (1, json!([{ "id": 100, "number": 1, "title": "v0" }])),
+            (2, json!([{ "id": 200, "number": 2, "title": "v1" }])),
+            (3, json!([])),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/repos/owner/repo/milestones"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(milestones))
+                .mount(&server)
+                .await;
+        }
+        let client = github_client(&server);
+        assert_eq!(client.milestone_id("v1").await.unwrap(), 2);
+        let error = client.milestone_id("v2").await.unwrap_err();
+        assert_eq!(error.to_string(), "cannot find open milestone `v2`");
+    }
+
+    #[tokio::test]
+    async fn pr_is_opened_if_it_cannot_be_assigned() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/owner/repo/pulls"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "user": { "login": "release-plz" },
+                "number": 3,
+                "html_url": format!("{}/owner/repo/pull/3", server.uri()),
+                "head": { "ref": "release-plz-2024-01-01T00-00-00Z", "sha": "abc" },
+                "title": "chore: release",
+                "body": null,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // The milestone doesn't exist.
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/milestones"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&server)
+            .await;
+        let client = github_client(&server);
+        let git_pr = client.open_pr(&pr_with_participants()).await.unwrap();
+        assert_eq!(git_pr.number, 3);
+    }
+
+    #[tokio::test]
+    async fn gitlab_mr_is_assigned() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/owner%2Frepo/milestones"))
+            .and(query_param("title", "v1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!([{ "id": 200, "title": "v1" }])),
+            )
+            .mount(&server)
+            .await;
+        for (username, id) in [("alice", 10), ("bob", 20)] {
+            Mock::given(method("GET"))
+                .and(path("/api/v4/users"))
+                .and(query_param("username", username))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!([{ "id": id }])))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("PUT"))
+            .and(path("/api/v4/projects/owner%2Frepo/merge_requests/3"))
+            .and(body_json(json!({
+                "reviewer_ids": [10],
+                "assignee_ids": [20],
+                "milestone_id": 200,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        client.assign_pr(&pr_with_participants(), 3).await.unwrap();
+    }
+
+    /// Repository with a modified, an added and a removed file.
+    fn repo_with_changes(dir: &tempfile::TempDir) -> Repo {
+        let repo = Repo::init(dir.path());
+        fs_err::write(dir.path().join("changed.txt"), "changed").unwrap();
+        fs_err::write(dir.path().join("removed.txt"), "removed").unwrap();
+        repo.add_all_and_commit("initial commit").unwrap();
+        fs_err::write(dir.path().join("changed.txt"), "file changed").unwrap();
+        fs_err::write(dir.path().join("added.txt"), "added").unwrap();
+        fs_err::remove_file(dir.path().join("removed.txt")).unwrap();
+        repo
+    }
+
+    #[tokio::test]
+    async fn gitea_changes_are_committed_with_contents_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_changes(&dir);
+        let changed_sha = repo.git(&["rev-parse", "HEAD:changed.txt"]).unwrap();
+        let removed_sha = repo.git(&["rev-parse", "HEAD:removed.txt"]).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/repos/owner/repo/contents"))
+            .and(body_json(json!({
+                "branch": "release-plz-2024-01-01T00-00-00Z",
+                "message": "chore: release",
+                "files": [
+                    { "operation": "delete", "path": "removed.txt", "sha": removed_sha },
+                    {
+                        "operation": "update",
+                        "path": "changed.txt",
+                        "content": BASE64_STANDARD.encode("file changed"),
+                        "sha": changed_sha,
+                    },
+                    {
+                        "operation": "create",
+                        "path": "added.txt",
+                        "content": BASE64_STANDARD.encode("added"),
+                    },
+                ],
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitea_client(&server);
+        client
+            .commit_changes(&repo, "chore: release", "release-plz-2024-01-01T00-00-00Z")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn gitlab_changes_are_committed_with_commits_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_changes(&dir);
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/owner%2Frepo/repository/commits"))
+            .and(body_json(json!({
+                "branch": "release-plz-2024-01-01T00-00-00Z",
+                "commit_message": "chore: release",
+                "actions": [
+                    { "action": "delete", "file_path": "removed.txt" },
+                    {
+                        "action": "update",
+                        "file_path": "changed.txt",
+                        "content": BASE64_STANDARD.encode("file changed"),
+                        "encoding": "base64",
+                    },
+                    {
+                        "action": "create",
+                        "file_path": "added.txt",
+                        "content": BASE64_STANDARD.encode("added"),
+                        "encoding": "base64",
+                    },
+                ],
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = gitlab_client(&server);
+        client
+            .commit_changes(&repo, "chore: release", "release-plz-2024-01-01T00-00-00Z")
+            .await
+            .unwrap();
+    }
+
+    fn bitbucket_client(server: &MockServer) -> GitClient {
+        let bitbucket = Bitbucket::new(
+            "owner".to_string(),
+            "repo".to_string(),
+            SecretString::from("token"),
+        )
+        .with_base_url(format!("{}/", server.uri()).parse().unwrap());
+        GitClient::new(GitBackend::Bitbucket(bitbucket)).unwrap()
+    }
+
+    fn bitbucket_pr(server: &MockServer, id: u64, branch: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "title": "chore: release",
+            "description": "release notes",
+            "author": { "nickname": "release-plz" },
+            "source": { "branch": { "name": branch }, "commit": { "hash": "abc" } },
+            "destination": { "branch": { "name": "main" }, "commit": { "hash": "def" } },
+            "links": { "html": { "href": format!("{}/owner/repo/pull-requests/{id}", server.uri()) } },
+        })
+    }
+
+    #[tokio::test]
+    async fn bitbucket_release_prs_are_listed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repositories/owner/repo/pullrequests"))
+            .and(query_param("state", "OPEN"))
+            .and(query_param("pagelen", "30"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [
+                    bitbucket_pr(&server, 1, "release-plz-2024-01-01T00-00-00Z"),
+                    bitbucket_pr(&server, 2, "feature"),
+                ],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        let prs = client.opened_prs("release-plz-").await.unwrap();
+        assert_eq!(prs.len(), 1);
+        let pr = &prs[0];
+        assert_eq!(pr.number, 1);
+        assert_eq!(pr.head.sha, "abc");
+        assert_eq!(pr.base_branch(), Some("main"));
+        assert_eq!(pr.body.as_deref(), Some("release notes"));
+        assert_eq!(pr.user.login, "release-plz");
+    }
+
+    #[tokio::test]
+    async fn bitbucket_pr_is_opened() {
+        let server = MockServer::start().await;
+        let branch = "release-plz-2024-01-01T00-00-00Z";
+        Mock::given(method("POST"))
+            .and(path("/repositories/owner/repo/pullrequests"))
+            .and(body_json(json!({
+                "title": "chore: release",
+                "description": "release notes",
+                "source": { "branch": { "name": branch } },
+                "destination": { "branch": { "name": "main" } },
+                "close_source_branch": true,
+                "draft": false,
+            })))
+            .respond_with(
+                ResponseTemplate::new(201).set_body_json(bitbucket_pr(&server, 3, branch)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        let pr = Pr {
+            body: "release notes".to_string(),
+            reviewers: vec![],
+            team_reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            ..pr_with_participants()
+        };
+        let git_pr = client.open_pr(&pr).await.unwrap();
+        assert_eq!(git_pr.number, 3);
+        assert_eq!(git_pr.branch(), branch);
+    }
+
+    #[tokio::test]
+    async fn bitbucket_pr_is_declined() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repositories/owner/repo/pullrequests/3/decline"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        client.close_pr(3).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn bitbucket_pr_commits_are_sorted_from_oldest() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repositories/owner/repo/pullrequests/3/commits"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "values": [
+                    { "hash": "new", "author": { "raw": "Bob <bob@example.com>" } },
+                    { "hash": "old", "author": { "user": { "nickname": "alice" } } },
+                ],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        let commits = client.pr_commits(3).await.unwrap();
+        let shas: Vec<&str> = commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, ["old", "new"]);
+        assert_eq!(commits[0].author.as_ref().unwrap().login, "alice");
+        assert!(commits[1].author.is_none());
+    }
+
+    #[tokio::test]
+    async fn bitbucket_changes_are_committed_with_src_api() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_changes(&dir);
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repositories/owner/repo/src"))
+            .and(body_string_contains("message=chore%3A+release"))
+            .and(body_string_contains(
+                "branch=release-plz-2024-01-01T00-00-00Z",
+            ))
+            .and(body_string_contains("changed.txt=file+changed"))
+            .and(body_string_contains("added.txt=added"))
+            .and(body_string_contains("files=removed.txt"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = bitbucket_client(&server);
+        client
+            .commit_changes(&repo, "chore: release", "release-plz-2024-01-01T00-00-00Z")
+            .await
+            .unwrap();
+    }
 }
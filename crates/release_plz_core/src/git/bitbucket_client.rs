@@ -0,0 +1,45 @@
+use anyhow::Context;
+use reqwest::header::{HeaderMap, HeaderValue};
+use secrecy::{ExposeSecret, SecretString};
+use url::Url;
+
+use crate::git::backend::Remote;
+
+/// Bitbucket Cloud.
+#[derive(Debug, Clone)]
+pub struct Bitbucket {
+    pub remote: Remote,
+}
+
+impl Bitbucket {
+    /// `owner` is the Bitbucket workspace.
+    pub fn new(owner: String, repo: String, token: SecretString) -> Self {
+        Self {
+            remote: Remote {
+                owner,
+                repo,
+                token,
+                base_url: "https://api.bitbucket.org/2.0/".parse().unwrap(),
+            },
+        }
+    }
+
+    pub fn with_base_url(self, base_url: Url) -> Self {
+        Self {
+            remote: Remote {
+                base_url,
+                ..self.remote
+            },
+        }
+    }
+
+    pub fn default_headers(&self) -> anyhow::Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        let mut auth_header: HeaderValue = format!("Bearer {}", self.remote.token.expose_secret())
+            .parse()
+            .context("invalid Bitbucket token")?;
+        auth_header.set_sensitive(true);
+        headers.insert(reqwest::header::AUTHORIZATION, auth_header);
+        Ok(headers)
+    }
+}
@@ -7,7 +7,7 @@ use tracing::{debug, trace};
 use url::Url;
 
 use crate::git::backend::Remote;
-use crate::GitClient;
+use crate::{GitClient, MergeMethod};
 
 /// Commit all the changes (except typestates) that are present in the repository
 /// using GitHub's [GraphQL api](https://docs.github.com/en/graphql/reference/mutations#createcommitonbranch).
@@ -19,19 +19,35 @@ pub async fn commit_changes(
     branch: &str,
 ) -> Result<()> {
     let commit = GithubCommit::new(&client.remote.owner_slash_repo(), repo, message, branch)?;
-    let graphql_endpoint = get_graphql_endpoint(&client.remote);
 
     let commit_query = commit
         .to_query_json()
         .await
         .context("failed to build GitHub commit query")?;
-    debug!("Sending createCommitOnBranch to {}", graphql_endpoint);
-    trace!("{}", commit_query);
+    send_mutation(client, &commit_query, "createCommitOnBranch").await
+}
+
+/// Enable [auto-merge](https://docs.github.com/en/graphql/reference/mutations#enablepullrequestautomerge)
+/// on the pull request with the given GraphQL node id.
+/// The pull request is merged once all the required checks pass.
+pub async fn enable_auto_merge(
+    client: &GitClient,
+    pr_node_id: &str,
+    merge_method: MergeMethod,
+) -> Result<()> {
+    let query = auto_merge_query(pr_node_id, merge_method);
+    send_mutation(client, &query, "enablePullRequestAutoMerge").await
+}
+
+async fn send_mutation(client: &GitClient, query: &Value, mutation_name: &str) -> Result<()> {
+    let graphql_endpoint = get_graphql_endpoint(&client.remote);
+    debug!("Sending {mutation_name} to {}", graphql_endpoint);
+    trace!("{}", query);
 
     let res: Value = client
         .client
         .post(graphql_endpoint)
-        .json(&commit_query)
+        .json(query)
         .send()
         .await?
         .json()
@@ -39,7 +55,7 @@ pub async fn commit_changes(
 
     if let Some(errors) = res.get("errors").and_then(Value::as_array) {
         anyhow::bail!(
-            "createCommitOnBranch returned errors: {:?}",
+            "{mutation_name} returned errors: {:?}",
             serde_json::to_string(errors)?
         );
     }
@@ -47,6 +63,29 @@ pub async fn commit_changes(
     Ok(())
 }
 
+fn auto_merge_query(pr_node_id: &str, merge_method: MergeMethod) -> Value {
+    const MUTATION: &str = r#"
+            mutation($input: EnablePullRequestAutoMergeInput!) {
+              enablePullRequestAutoMerge(input: $input) {
+                clientMutationId
+              }
+            }"#;
+    let merge_method = match merge_method {
+        MergeMethod::Merge => "MERGE",
+        MergeMethod::Squash => "SQUASH",
+        MergeMethod::Rebase => "REBASE",
+    };
+    json!({
+        "query": MUTATION.replace(|c: char| c.is_whitespace(), ""),
+        "variables": {
+            "input": {
+                "pullRequestId": pr_node_id,
+                "mergeMethod": merge_method,
+            }
+        }
+    })
+}
+
 fn get_graphql_endpoint(remote: &Remote) -> Url {
     let mut base_url = remote.base_url.clone();
     base_url.set_path("graphql");
@@ -55,12 +94,12 @@ fn get_graphql_endpoint(remote: &Remote) -> Url {
 }
 
 // get the list of changes in repository excluding typechanges and removed files
-fn changed_files(repo: &Repo) -> Result<Vec<String>> {
+pub(crate) fn changed_files(repo: &Repo) -> Result<Vec<String>> {
     repo.changes(|line| !line.starts_with("T ") && !line.starts_with("D "))
 }
 
 // get the list of removed files in repository
-fn removed_files(repo: &Repo) -> Result<Vec<String>> {
+pub(crate) fn removed_files(repo: &Repo) -> Result<Vec<String>> {
     repo.changes(|line| line.starts_with("D "))
 }
 
@@ -256,4 +295,20 @@ mod tests {
         expect_test::expect![[r#""mutation($input:CreateCommitOnBranchInput!){createCommitOnBranch(input:$input){commit{author{name,email}}}}""#]]
         .assert_eq(&query["query"].to_string());
     }
+
+    #[test]
+    fn github_auto_merge_query() {
+        let query = auto_merge_query("PR_node", MergeMethod::Squash);
+        assert_eq!(
+            query["variables"],
+            json!({
+                "input": {
+                    "pullRequestId": "PR_node",
+                    "mergeMethod": "SQUASH",
+                }
+            })
+        );
+        expect_test::expect![[r#""mutation($input:EnablePullRequestAutoMergeInput!){enablePullRequestAutoMerge(input:$input){clientMutationId}}""#]]
+        .assert_eq(&query["query"].to_string());
+    }
 }
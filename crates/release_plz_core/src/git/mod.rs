@@ -1,4 +1,5 @@
 pub mod backend;
+pub mod bitbucket_client;
 pub mod gitea_client;
 pub mod github_client;
 pub mod github_graphql;
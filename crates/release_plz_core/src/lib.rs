@@ -4,6 +4,7 @@ mod changelog;
 mod changelog_filler;
 mod changelog_parser;
 mod clone;
+mod combined_release;
 mod command;
 mod copy_dir;
 mod diff;
@@ -12,13 +13,22 @@ pub mod fs_utils;
 mod git;
 mod lock_compare;
 mod next_ver;
+mod notification;
 mod package_compare;
 mod package_path;
 mod pr;
 mod pr_parser;
 mod project;
+mod publish_integrity;
+mod publish_policy;
+mod publish_retry;
 mod registry_packages;
+mod release_assets;
+mod release_hooks;
+mod release_journal;
 mod release_order;
+mod release_plan;
+mod release_trigger;
 mod repo_url;
 mod semver_check;
 mod tera;
@@ -27,15 +37,23 @@ mod toml_compare;
 mod version;
 
 pub use changelog::*;
+pub use combined_release::CombinedGitRelease;
 pub use command::*;
 pub use download::{read_package, PackageDownloader};
 pub use git::backend::{GitBackend, GitClient, GitPr};
+pub use git::bitbucket_client::Bitbucket;
 pub use git::gitea_client::Gitea;
 pub use git::github_client::GitHub;
 pub use git::gitlab_client::GitLab;
 pub use next_ver::*;
+pub use notification::{Notification, NotificationPreset, NotificationScope};
 pub use package_compare::*;
 pub use package_path::*;
-pub use pr::DEFAULT_BRANCH_PREFIX;
+pub use pr::{MergeMethod, DEFAULT_BRANCH_PREFIX};
 pub use project::*;
+pub use publish_integrity::IntegrityCheck;
+pub use publish_policy::PublishPolicy;
+pub use release_hooks::ReleaseHooks;
+pub use release_plan::ReleasePlan;
+pub use release_trigger::ReleaseTrigger;
 pub use repo_url::*;
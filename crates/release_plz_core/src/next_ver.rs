@@ -75,6 +75,9 @@ pub struct UpdateRequest {
     /// The registry name needs to be present in the Cargo config.
     /// If unspecified, crates.io is used.
     registry: Option<String>,
+    /// Requirement of the registry version used as the baseline of the update, e.g. `^1`.
+    /// If unspecified, the latest version of the registry is used.
+    registry_version: Option<String>,
     /// - If true, update all the dependencies in Cargo.lock by running `cargo update`.
     /// - If false, updates the workspace packages in Cargo.lock by running `cargo update --workspace`.
     dependencies_update: bool,
@@ -232,6 +235,7 @@ impl UpdateRequest {
             single_package: None,
             changelog_req: ChangelogRequest::default(),
             registry: None,
+            registry_version: None,
             dependencies_update: false,
             allow_dirty: false,
             repo_url: None,
@@ -322,6 +326,16 @@ impl UpdateRequest {
         }
     }
 
+    /// Compare the local packages with the latest registry version matching
+    /// the `version` requirement, instead of the latest registry version.
+    /// Useful to release from maintenance branches, e.g. `^1` for the `1.x` release line.
+    pub fn with_registry_version(self, version: String) -> Self {
+        Self {
+            registry_version: Some(version),
+            ..self
+        }
+    }
+
     pub fn with_single_package(self, package: String) -> Self {
         Self {
             single_package: Some(package),
@@ -414,6 +428,7 @@ pub async fn next_versions(input: &UpdateRequest) -> anyhow::Result<(PackagesUpd
         input.registry_manifest.as_deref(),
         &local_project.publishable_packages(),
         input.registry.as_deref(),
+        input.registry_version.as_deref(),
     )?;
 
     let repository = local_project
@@ -1338,7 +1353,7 @@ fn pathbufs_to_check(package_path: &Utf8Path, package: &Package) -> Vec<Utf8Path
 
 /// Check if release-plz should check the semver compatibility of the package.
 /// - `run_semver_check` is true if the user wants to run the semver check.
-fn should_check_semver(package: &Package, run_semver_check: bool) -> bool {
+pub(crate) fn should_check_semver(package: &Package, run_semver_check: bool) -> bool {
     if run_semver_check && is_library(package) {
         let is_cargo_semver_checks_installed = semver_check::is_cargo_semver_checks_installed();
         if !is_cargo_semver_checks_installed {
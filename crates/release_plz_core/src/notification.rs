@@ -0,0 +1,309 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::Context;
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::pr_parser::Pr;
+
+const RELEASES_VAR: &str = "releases";
+const MESSAGE_VAR: &str = "message";
+const ID_VAR: &str = "id";
+
+/// Payload sent when neither a template nor a preset is configured.
+const DEFAULT_TEMPLATE: &str = r#"{"releases": {{ releases | json_encode() }}}"#;
+const SLACK_TEMPLATE: &str = r#"{"text": {{ message | json_encode() }}}"#;
+/// Discord rejects messages longer than 2000 characters.
+const DISCORD_TEMPLATE: &str =
+    r#"{"content": {{ message | truncate(length=1990) | json_encode() }}}"#;
+const MATRIX_TEMPLATE: &str = r#"{"msgtype": "m.text", "body": {{ message | json_encode() }}}"#;
+
+/// Webhook called after a release.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notification {
+    /// Url of the webhook. It's a tera template.
+    pub url: String,
+    /// HTTP headers. Values are tera templates.
+    pub headers: BTreeMap<String, String>,
+    /// Tera template of the payload.
+    /// If unspecified, the template of the preset is used.
+    pub template: Option<String>,
+    pub preset: Option<NotificationPreset>,
+    pub scope: NotificationScope,
+}
+
+/// Payload format of popular chat services.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationPreset {
+    Slack,
+    Discord,
+    /// Sent with `PUT`, as required by the Matrix client-server API.
+    Matrix,
+}
+
+/// How many times the webhook is called.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationScope {
+    /// Once per run, with all the released packages.
+    #[default]
+    Release,
+    /// Once per released package.
+    Package,
+}
+
+/// Released package, as exposed to the notification templates.
+#[derive(Debug, Serialize)]
+pub(crate) struct NotifiedPackage<'a> {
+    pub package: &'a str,
+    pub version: String,
+    pub tag: &'a str,
+    pub changelog: &'a str,
+    pub prs: &'a [Pr],
+}
+
+impl Notification {
+    fn template(&self) -> &str {
+        if let Some(template) = &self.template {
+            return template;
+        }
+        match self.preset {
+            Some(NotificationPreset::Slack) => SLACK_TEMPLATE,
+            Some(NotificationPreset::Discord) => DISCORD_TEMPLATE,
+            Some(NotificationPreset::Matrix) => MATRIX_TEMPLATE,
+            None => DEFAULT_TEMPLATE,
+        }
+    }
+
+    fn method(&self) -> reqwest::Method {
+        match self.preset {
+            Some(NotificationPreset::Matrix) => reqwest::Method::PUT,
+            _ => reqwest::Method::POST,
+        }
+    }
+}
+
+/// Call the webhooks of the `notifications`.
+/// Failures are logged, because the packages are already released.
+pub(crate) async fn send_notifications(
+    notifications: &[Notification],
+    packages: &[NotifiedPackage<'_>],
+) {
+    if notifications.is_empty() || packages.is_empty() {
+        return;
+    }
+    let client = reqwest::Client::new();
+    for (index, notification) in notifications.iter().enumerate() {
+        let batches: Vec<&[NotifiedPackage]> = match notification.scope {
+            NotificationScope::Release => vec![packages],
+            NotificationScope::Package => packages.chunks(1).collect(),
+        };
+        for batch in batches {
+            if let Err(e) = send_notification(&client, notification, batch).await {
+                warn!("failed to send notification {index}: {e:?}");
+            }
+        }
+    }
+}
+
+async fn send_notification(
+    client: &reqwest::Client,
+    notification: &Notification,
+    packages: &[NotifiedPackage<'_>],
+) -> anyhow::Result<()> {
+    let request = render_request(notification, packages)?;
+    debug!("sending notification to {}", request.url);
+    let mut builder = client
+        .request(notification.method(), &request.url)
+        .timeout(Duration::from_secs(30))
+        .body(request.payload);
+    let has_content_type = request
+        .headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"));
+    if !has_content_type {
+        builder = builder.header(reqwest::header::CONTENT_TYPE, "application/json");
+    }
+    for (name, value) in &request.headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder.send().await.context("can't send notification")?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("webhook responded with status {status}: {body}");
+    }
+    info!("notification sent");
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct RenderedRequest {
+    url: String,
+    headers: BTreeMap<String, String>,
+    payload: String,
+}
+
+fn render_request(
+    notification: &Notification,
+    packages: &[NotifiedPackage<'_>],
+) -> anyhow::Result<RenderedRequest> {
+    let context = notification_context(packages)?;
+    let render = |template: &str, name: &str| {
+        tera::Tera::one_off(template, &context, false)
+            .with_context(|| format!("failed to render notification {name}"))
+    };
+    let url = render(&notification.url, "url")?;
+    let headers = notification
+        .headers
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), render(value, name)?)))
+        .collect::<anyhow::Result<_>>()?;
+    let payload = render(notification.template(), "template")?;
+    Ok(RenderedRequest {
+        url,
+        headers,
+        payload,
+    })
+}
+
+fn notification_context(packages: &[NotifiedPackage<'_>]) -> anyhow::Result<tera::Context> {
+    let mut context = tera::Context::new();
+    if let [package] = packages {
+        // Expose the fields of the package at the top level, to simplify per-package templates.
+        context.extend(tera::Context::from_serialize(package)?);
+    }
+    context.insert(RELEASES_VAR, packages);
+    context.insert(MESSAGE_VAR, &message(packages));
+    // Unique for every notification. Matrix requires it as transaction id.
+    let id = format!(
+        "release-plz-{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        packages
+            .iter()
+            .map(|p| format!("{}-{}", p.package, p.version))
+            .collect::<Vec<_>>()
+            .join("-")
+    );
+    context.insert(ID_VAR, &id);
+    Ok(context)
+}
+
+/// Human-readable summary of the release.
+fn message(packages: &[NotifiedPackage<'_>]) -> String {
+    packages
+        .iter()
+        .map(|p| {
+            let title = format!("Released {} {}", p.package, p.version);
+            let changelog = p.changelog.trim();
+            if changelog.is_empty() {
+                title
+            } else {
+                format!("{title}\n\n{changelog}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages() -> Vec<NotifiedPackage<'static>> {
+        vec![
+            NotifiedPackage {
+                package: "a",
+                version: "0.1.0".to_string(),
+                tag: "a-v0.1.0",
+                changelog: "- fix \"quotes\"",
+                prs: &[],
+            },
+            NotifiedPackage {
+                package: "b",
+                version: "1.0.0".to_string(),
+                tag: "b-v1.0.0",
+                changelog: "",
+                prs: &[],
+            },
+        ]
+    }
+
+    #[test]
+    fn slack_preset_is_rendered() {
+        let notification = Notification {
+            url: "https://hooks.slack.com/x".to_string(),
+            preset: Some(NotificationPreset::Slack),
+            ..Notification::default()
+        };
+        let request = render_request(&notification, &packages()).unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&request.payload).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "text": "Released a 0.1.0\n\n- fix \"quotes\"\n\nReleased b 1.0.0"
+            })
+        );
+    }
+
+    #[test]
+    fn default_template_contains_releases() {
+        let notification = Notification {
+            url: "https://example.com".to_string(),
+            ..Notification::default()
+        };
+        let request = render_request(&notification, &packages()[1..]).unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&request.payload).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "releases": [{
+                    "package": "b",
+                    "version": "1.0.0",
+                    "tag": "b-v1.0.0",
+                    "changelog": "",
+                    "prs": [],
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn package_fields_are_available_in_package_scope() {
+        let notification = Notification {
+            url: "https://example.com/{{ package }}".to_string(),
+            headers: [("X-Version".to_string(), "{{ version }}".to_string())].into(),
+            template: Some("{{ tag }}".to_string()),
+            scope: NotificationScope::Package,
+            ..Notification::default()
+        };
+        let request = render_request(&notification, &packages()[..1]).unwrap();
+        assert_eq!(
+            request,
+            RenderedRequest {
+                url: "https://example.com/a".to_string(),
+                headers: [("X-Version".to_string(), "0.1.0".to_string())].into(),
+                payload: "a-v0.1.0".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_is_called() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("PUT"))
+            .and(wiremock::matchers::header("Authorization", "Bearer token"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let notification = Notification {
+            url: format!("{}/send/m.room.message/{{{{ id }}}}", server.uri()),
+            headers: [("Authorization".to_string(), "Bearer token".to_string())].into(),
+            preset: Some(NotificationPreset::Matrix),
+            ..Notification::default()
+        };
+        send_notification(&reqwest::Client::new(), &notification, &packages())
+            .await
+            .unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    tera::{render_template, PACKAGE_VAR, RELEASES_VAR, VERSION_VAR},
+    pr_parser::{changelog_comment_with_marker, changelog_sections},
+    tera::{render_template, GROUP_VAR, PACKAGE_VAR, RELEASES_VAR, VERSION_VAR},
     PackagesUpdate,
 };
 use chrono::SecondsFormat;
@@ -15,14 +16,41 @@ pub struct Pr {
     pub body: String,
     pub draft: bool,
     pub labels: Vec<String>,
+    /// Usernames of the users asked to review the PR.
+    pub reviewers: Vec<String>,
+    /// Slugs of the teams asked to review the PR.
+    pub team_reviewers: Vec<String>,
+    /// Usernames of the users assigned to the PR.
+    pub assignees: Vec<String>,
+    /// Title of the milestone of the PR.
+    pub milestone: Option<String>,
+    /// If set, the PR is merged with this method once the CI passes.
+    pub auto_merge: Option<MergeMethod>,
+    /// Changelogs that don't fit in the body.
+    /// Release-plz posts them as PR comments.
+    pub comments: Vec<String>,
+}
+
+/// How the release PR is merged into the base branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    /// Add all the commits of the PR to the base branch with a merge commit.
+    Merge,
+    /// Combine all the commits of the PR into a single commit.
+    Squash,
+    /// Add all the commits of the PR to the base branch individually.
+    Rebase,
 }
 
 impl Pr {
+    /// `group` is the name of the package or of the version group released by this PR,
+    /// if release-plz opens a PR for each of them.
     pub fn new(
         default_branch: &str,
         packages_to_update: &PackagesUpdate,
         project_contains_multiple_pub_packages: bool,
         branch_prefix: &str,
+        group: Option<&str>,
         title_template: Option<String>,
         body_template: Option<String>,
     ) -> Self {
@@ -32,15 +60,23 @@ impl Pr {
             title: pr_title(
                 packages_to_update,
                 project_contains_multiple_pub_packages,
+                group,
                 title_template,
             ),
             body: pr_body(
                 packages_to_update,
                 project_contains_multiple_pub_packages,
+                group,
                 body_template,
             ),
             draft: false,
             labels: vec![],
+            reviewers: vec![],
+            team_reviewers: vec![],
+            assignees: vec![],
+            milestone: None,
+            auto_merge: None,
+            comments: vec![],
         }
     }
 
@@ -53,6 +89,40 @@ impl Pr {
         self.labels = labels;
         self
     }
+
+    pub fn with_reviewers(mut self, reviewers: Vec<String>) -> Self {
+        self.reviewers = reviewers;
+        self
+    }
+
+    pub fn with_team_reviewers(mut self, team_reviewers: Vec<String>) -> Self {
+        self.team_reviewers = team_reviewers;
+        self
+    }
+
+    pub fn with_assignees(mut self, assignees: Vec<String>) -> Self {
+        self.assignees = assignees;
+        self
+    }
+
+    pub fn with_milestone(mut self, milestone: Option<String>) -> Self {
+        self.milestone = milestone;
+        self
+    }
+
+    /// Fit the body in the `max_len` characters allowed by the git forge.
+    /// The changelogs that don't fit are moved to [`Pr::comments`].
+    pub fn with_max_body_len(mut self, max_len: usize) -> Self {
+        let (body, comments) = fit_pr_body(std::mem::take(&mut self.body), max_len);
+        self.body = body;
+        self.comments = comments;
+        self
+    }
+
+    pub fn with_auto_merge(mut self, auto_merge: Option<MergeMethod>) -> Self {
+        self.auto_merge = auto_merge;
+        self
+    }
 }
 
 fn release_branch(prefix: &str) -> String {
@@ -67,6 +137,7 @@ fn release_branch(prefix: &str) -> String {
 fn pr_title(
     packages_to_update: &PackagesUpdate,
     project_contains_multiple_pub_packages: bool,
+    group: Option<&str>,
     title_template: Option<String>,
 ) -> String {
     let updates = packages_to_update.updates();
@@ -80,6 +151,9 @@ fn pr_title(
 
     if let Some(title_template) = title_template {
         let mut context = tera::Context::new();
+        if let Some(group) = group {
+            context.insert(GROUP_VAR, group);
+        }
 
         if updates.len() == 1 {
             let (package, _) = &updates[0];
@@ -96,6 +170,13 @@ fn pr_title(
         // The project is a workspace with multiple public packages and we are only updating one of them.
         // Specify which package is being updated in the PR title.
         format!("chore({}): release v{}", package.name, first_version)
+    } else if let Some(group) = group.filter(|_| updates.len() > 1) {
+        // We are updating multiple packages of a version group in their own PR.
+        if are_all_versions_equal() {
+            format!("chore({group}): release v{first_version}")
+        } else {
+            format!("chore({group}): release")
+        }
     } else if updates.len() > 1 && !are_all_versions_equal() {
         // We are updating multiple packages with different versions, so we don't specify the version in the PR title.
         "chore: release".to_string()
@@ -108,27 +189,32 @@ fn pr_title(
     }
 }
 
-/// The Github API allows a max of 65536 characters in the body field when trying to create a new PR
-const MAX_BODY_LEN: usize = 65536;
-
 fn pr_body(
     packages_to_update: &PackagesUpdate,
     project_contains_multiple_pub_packages: bool,
+    group: Option<&str>,
     body_template: Option<String>,
 ) -> String {
     if let Some(body_template) = body_template {
-        pr_body_custom(packages_to_update, body_template.as_str())
+        pr_body_custom(packages_to_update, group, body_template.as_str())
     } else {
         pr_body_default(packages_to_update, project_contains_multiple_pub_packages)
     }
 }
 
-fn pr_body_custom(packages_to_update: &PackagesUpdate, body_template: &str) -> String {
+fn pr_body_custom(
+    packages_to_update: &PackagesUpdate,
+    group: Option<&str>,
+    body_template: &str,
+) -> String {
     let releases = packages_to_update.releases();
     let mut context = tera::Context::new();
     context.insert(RELEASES_VAR, &releases);
+    if let Some(group) = group {
+        context.insert(GROUP_VAR, group);
+    }
 
-    trim_pr_body(render_template(body_template, &context, "pr_body"))
+    render_template(body_template, &context, "pr_body")
 }
 
 fn pr_body_default(
@@ -147,28 +233,84 @@ fn pr_body_default(
 
     let footer =
         "---\nThis PR was generated with [release-plz](https://github.com/release-plz/release-plz/).";
-    let mut formatted = format!("{header}{summary}\n{changes}\n{footer}");
+    format!("{header}{summary}\n{changes}\n{footer}")
+}
 
-    // Make sure we don't go over the Github API's limit for PR body size
-    if formatted.chars().count() > MAX_BODY_LEN {
+/// Make sure the PR body isn't longer than `max_len` characters.
+/// The changelogs that don't fit are replaced with a note, starting from the last one,
+/// and returned in collapsible sections, so that they can be posted as PR comments.
+fn fit_pr_body(mut body: String, max_len: usize) -> (String, Vec<String>) {
+    let mut comments = vec![];
+    // Start from the last changelog, so that the ranges of the previous ones stay valid.
+    for section in changelog_sections(&body).into_iter().rev() {
+        if body.chars().count() <= max_len {
+            break;
+        }
         tracing::info!(
-            "PR body is longer than {MAX_BODY_LEN} characters. Omitting full changelog."
+            "PR body is longer than {max_len} characters. Moving the changelog of {} to a PR comment.",
+            section.package
+        );
+        let comment = changelog_comment_with_marker(
+            &section.package,
+            &format!(
+                "<details><summary><i><b>Changelog of `{}`</b></i></summary><p>\n\n{}\n</p></details>\n",
+                section.package, section.changelog
+            ),
         );
-        formatted = format!("{header}{summary}\n{footer}");
+        comments.push(trim_pr_text(comment, max_len));
+        let note = format!(
+            "*The changelog of `{}` doesn't fit in the PR body: see the PR comments or the `CHANGELOG.md` file of the package.*",
+            section.package
+        );
+        body.replace_range(section.range, &note);
     }
-
-    trim_pr_body(formatted)
+    comments.reverse();
+    (trim_pr_text(body, max_len), comments)
 }
 
-fn trim_pr_body(body: String) -> String {
-    // Make extra sure the body is short enough.
+fn trim_pr_text(text: String, max_len: usize) -> String {
+    // Make extra sure the text is short enough.
     // If it's not, give up trying to fail gracefully by truncating it to the nearest valid UTF-8 boundary.
     // A grapheme cluster may be cut in half in the process.
 
-    if body.chars().count() > MAX_BODY_LEN {
-        tracing::warn!("PR body is still longer than {MAX_BODY_LEN} characters. Truncating as is.");
-        body.chars().take(MAX_BODY_LEN).collect()
+    if text.chars().count() > max_len {
+        tracing::warn!("PR text is still longer than {max_len} characters. Truncating as is.");
+        text.chars().take(max_len).collect()
     } else {
-        body
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pr_parser::{changelog_comment_package, changelog_with_markers};
+
+    use super::*;
+
+    #[test]
+    fn short_pr_body_is_not_changed() {
+        let body = format!("## New release\n{}", changelog_with_markers("a", "- fix"));
+        let (fitted_body, comments) = fit_pr_body(body.clone(), 1000);
+        assert_eq!(fitted_body, body);
+        assert!(comments.is_empty());
+    }
+
+    #[test]
+    fn changelogs_that_dont_fit_are_moved_to_comments() {
+        let long_changelog = "- feat\n".repeat(290);
+        let body = format!(
+            "## New release\n{}\n{}\nfooter",
+            changelog_with_markers("a", "- fix"),
+            changelog_with_markers("b", &long_changelog)
+        );
+        let (fitted_body, comments) = fit_pr_body(body, 2200);
+        assert!(fitted_body.chars().count() <= 2200);
+        assert!(fitted_body.contains(&changelog_with_markers("a", "- fix")));
+        assert!(fitted_body.contains("The changelog of `b` doesn't fit in the PR body"));
+        assert!(fitted_body.ends_with("\nfooter"));
+        assert_eq!(comments.len(), 1);
+        assert_eq!(changelog_comment_package(&comments[0]), Some("b"));
+        assert!(comments[0].contains("<details><summary><i><b>Changelog of `b`</b></i></summary>"));
+        assert!(comments[0].contains(&long_changelog));
     }
 }
@@ -1,7 +1,72 @@
+use std::{collections::BTreeMap, ops::Range};
+
 use regex::Regex;
 use serde::Serialize;
 use url::Url;
 
+const CHANGELOG_START_MARKER: &str = "release-plz-changelog-start";
+const CHANGELOG_END_MARKER: &str = "release-plz-changelog-end";
+const CHANGELOG_COMMENT_MARKER: &str = "release-plz-changelog-comment";
+
+/// Wrap the changelog of the package in markers,
+/// so that it can be found in the body of the release PR.
+/// The markers are html comments, so they aren't rendered.
+pub fn changelog_with_markers(package: &str, changelog: &str) -> String {
+    format!(
+        "<!-- {CHANGELOG_START_MARKER}: {package} -->\n{changelog}\n<!-- {CHANGELOG_END_MARKER}: {package} -->"
+    )
+}
+
+/// Parse the changelogs wrapped with [`changelog_with_markers`] from the body of a PR.
+/// Returns a map from the package name to its changelog.
+pub fn changelogs_from_pr_body(body: &str) -> BTreeMap<String, String> {
+    // Git forges can convert the line endings of the edited PR bodies.
+    let body = body.replace("\r\n", "\n");
+    changelog_sections(&body)
+        .into_iter()
+        .map(|section| (section.package, section.changelog))
+        .collect()
+}
+
+/// Changelog of a package wrapped with [`changelog_with_markers`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChangelogSection {
+    /// Byte range of the changelog in the text, markers included.
+    pub range: Range<usize>,
+    pub package: String,
+    pub changelog: String,
+}
+
+/// Find the changelogs wrapped with [`changelog_with_markers`] in the given text.
+pub fn changelog_sections(text: &str) -> Vec<ChangelogSection> {
+    let re = Regex::new(&format!(
+        r"(?s)<!-- {CHANGELOG_START_MARKER}: (\S+) -->\n(.*?)\n?<!-- {CHANGELOG_END_MARKER}: (\S+) -->"
+    ))
+    .unwrap();
+    re.captures_iter(text)
+        .filter(|capture| capture[1] == capture[3])
+        .map(|capture| ChangelogSection {
+            range: capture.get(0).unwrap().range(),
+            package: capture[1].to_string(),
+            changelog: capture[2].to_string(),
+        })
+        .collect()
+}
+
+/// Prefix the PR comment containing the changelog of the package with a marker,
+/// so that release-plz can find the comment and update it in the next runs.
+pub fn changelog_comment_with_marker(package: &str, comment: &str) -> String {
+    format!("<!-- {CHANGELOG_COMMENT_MARKER}: {package} -->\n{comment}")
+}
+
+/// Package of a PR comment created with [`changelog_comment_with_marker`].
+pub fn changelog_comment_package(comment: &str) -> Option<&str> {
+    let (package, _comment) = comment
+        .strip_prefix(&format!("<!-- {CHANGELOG_COMMENT_MARKER}: "))?
+        .split_once(" -->")?;
+    Some(package)
+}
+
 #[derive(Debug, PartialEq, Serialize)]
 pub struct Pr {
     html_url: Url,
@@ -30,6 +95,30 @@ pub fn prs_from_text(text: &str) -> Vec<Pr> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn changelogs_are_parsed_from_pr_body() {
+        let body = format!(
+            "## 🤖 New release\r\n\r\n{}\r\n\r\n{}\n<!-- {CHANGELOG_START_MARKER}: c -->\nunterminated",
+            changelog_with_markers("a", "### Fixed\r\n\r\n- edited fix"),
+            changelog_with_markers("b-c", "### Added\n\n- feature"),
+        );
+        let changelogs = changelogs_from_pr_body(&body);
+        assert_eq!(
+            changelogs,
+            BTreeMap::from([
+                ("a".to_string(), "### Fixed\n\n- edited fix".to_string()),
+                ("b-c".to_string(), "### Added\n\n- feature".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn package_is_parsed_from_changelog_comment() {
+        let comment = changelog_comment_with_marker("b-c", "### Added\n\n- feature");
+        assert_eq!(changelog_comment_package(&comment), Some("b-c"));
+        assert_eq!(changelog_comment_package("### Added\n\n- feature"), None);
+    }
+
     #[test]
     fn parse_pr_correctly() {
         let changelog_entry = r#"
@@ -0,0 +1,290 @@
+use anyhow::Context;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Package,
+};
+use git_cmd::Repo;
+use secrecy::SecretString;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::{
+    are_packages_equal,
+    cargo::{published_checksum, CargoRegistry},
+    download::PackageDownloader,
+    fs_utils::Utf8TempDir,
+    registry_packages::initialize_registry_package,
+    PackagePath,
+};
+
+/// How to verify that the package in the registry is the package built by `cargo publish`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityCheck {
+    /// Compare the checksum written in the registry index with the checksum
+    /// of the `.crate` file created by `cargo publish`.
+    Checksum,
+    /// Download the published package and compare its files with the local package.
+    Content,
+}
+
+/// Return an error if the package in the registry isn't the package built locally.
+pub(crate) async fn verify_published_package(
+    check: IntegrityCheck,
+    registry: &mut CargoRegistry,
+    package: &Package,
+    target_directory: &Utf8Path,
+    repo: &Repo,
+    token: &Option<SecretString>,
+) -> anyhow::Result<()> {
+    let registry_name = registry.name.as_deref().unwrap_or("crates.io");
+    info!(
+        "{} {}: verifying the integrity of the package published to {registry_name}",
+        package.name, package.version
+    );
+    let local_crate = local_crate_path(target_directory, package);
+    let check = check_to_run(check, &local_crate);
+    if check == IntegrityCheck::Content {
+        warn!(
+            "{} {}: {local_crate} doesn't exist, so the files of the published package are compared with the local files",
+            package.name, package.version
+        );
+    }
+    match check {
+        IntegrityCheck::Checksum => {
+            let local_checksum = local_checksum(&local_crate)?
+                .with_context(|| format!("{local_crate} doesn't exist"))?;
+            let published_checksum = published_checksum(&mut registry.index, package, token)
+                .await
+                .context("can't read the checksum from the registry index")?
+                .with_context(|| {
+                    format!(
+                        "{} {} not found in {registry_name}",
+                        package.name, package.version
+                    )
+                })?;
+            ensure_same_checksum(
+                package,
+                registry_name,
+                &local_crate,
+                &local_checksum,
+                &published_checksum,
+            )?;
+        }
+        IntegrityCheck::Content => {
+            let package = package.clone();
+            let registry = registry.name.clone();
+            let repo_dir = repo.directory().to_path_buf();
+            // Downloading and packaging the package is blocking.
+            tokio::task::spawn_blocking(move || {
+                let repo = Repo::new(&repo_dir)?;
+                compare_content(&package, registry.as_deref(), &repo)
+            })
+            .await
+            .context("package content comparison task failed")??;
+        }
+    }
+    info!(
+        "{} {}: the package published to {registry_name} matches the local package",
+        package.name, package.version
+    );
+    Ok(())
+}
+
+/// The checksum can't be compared without the `.crate` file created by `cargo publish`,
+/// e.g. when the release is resumed on a runner that didn't run `cargo publish`.
+/// In this case, the content of the package is compared instead.
+fn check_to_run(check: IntegrityCheck, local_crate: &Utf8Path) -> IntegrityCheck {
+    if check == IntegrityCheck::Checksum && !local_crate.exists() {
+        IntegrityCheck::Content
+    } else {
+        check
+    }
+}
+
+/// `.crate` file created by `cargo publish`.
+fn local_crate_path(target_directory: &Utf8Path, package: &Package) -> Utf8PathBuf {
+    target_directory
+        .join("package")
+        .join(format!("{}-{}.crate", package.name, package.version))
+}
+
+/// Checksum of the `local_crate` file.
+/// Return [`Option::None`] if the file doesn't exist.
+fn local_checksum(local_crate: &Utf8Path) -> anyhow::Result<Option<[u8; 32]>> {
+    if !local_crate.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs_err::read(local_crate).context("can't read the .crate file created by cargo publish")?;
+    Ok(Some(Sha256::digest(&content).into()))
+}
+
+fn ensure_same_checksum(
+    package: &Package,
+    registry_name: &str,
+    local_crate: &Utf8Path,
+    local_checksum: &[u8; 32],
+    published_checksum: &[u8; 32],
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        local_checksum == published_checksum,
+        "{} {}: the checksum of the package published to {registry_name} ({}) is different from the checksum of {local_crate} ({})",
+        package.name,
+        package.version,
+        hex(published_checksum),
+        hex(local_checksum),
+    );
+    Ok(())
+}
+
+/// Return an error if the package wasn't published from the current commit.
+/// `published_sha1` is the commit written in the `.cargo_vcs_info.json` file of the published package.
+fn ensure_published_from_current_commit(
+    package: &Package,
+    published_sha1: Option<&str>,
+    current_commit: &str,
+) -> anyhow::Result<()> {
+    if let Some(sha1) = published_sha1 {
+        anyhow::ensure!(
+            sha1 == current_commit,
+            "{} {}: the package was published from commit {sha1}, but the current commit is {current_commit}",
+            package.name,
+            package.version
+        );
+    }
+    Ok(())
+}
+
+/// Download the published package and compare it with the local package.
+fn compare_content(package: &Package, registry: Option<&str>, repo: &Repo) -> anyhow::Result<()> {
+    let registry_name = registry.unwrap_or("crates.io");
+    let temp_dir = Utf8TempDir::new()?;
+    let mut downloader = PackageDownloader::new([package.name.as_str()], temp_dir.path().as_str())
+        .with_version(format!("={}", package.version));
+    if let Some(registry) = registry {
+        downloader = downloader.with_registry(registry.to_string());
+    }
+    let registry_package = downloader
+        .download()
+        .context("failed to download the published package")?
+        .into_iter()
+        .find(|p| p.name == package.name && p.version == package.version)
+        .with_context(|| format!("can't download {} {}", package.name, package.version))?;
+    let registry_package = initialize_registry_package(vec![registry_package])
+        .context("failed to initialize the published package")?
+        .remove(0);
+
+    let current_commit = repo.current_commit_hash()?;
+    ensure_published_from_current_commit(
+        package,
+        registry_package.published_at_sha1(),
+        &current_commit,
+    )?;
+
+    // `cargo package` can edit the `Cargo.lock` file.
+    // Revert the changes, so that the next `cargo publish` doesn't fail because of a dirty repository.
+    let modified_lock_files =
+        || repo.changes(|line| line.starts_with("M ") && line.ends_with("Cargo.lock"));
+    let lock_files_before = modified_lock_files()?;
+    let local_package_path = package.package_path()?;
+    let registry_package_path = registry_package.package.package_path()?;
+    let are_equal = are_packages_equal(local_package_path, registry_package_path)
+        .context("cannot compare packages")?;
+    for lock_file in modified_lock_files()? {
+        if !lock_files_before.contains(&lock_file) {
+            repo.checkout(&lock_file)
+                .with_context(|| format!("cannot revert changes of {lock_file}"))?;
+        }
+    }
+    anyhow::ensure!(
+        are_equal,
+        "{} {}: the files of the package published to {registry_name} are different from the local files",
+        package.name,
+        package.version,
+    );
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> Package {
+        let mut package: Package = fake_package::FakePackage::new("my_crate").into();
+        package.version = cargo_metadata::semver::Version::new(1, 2, 3);
+        package
+    }
+
+    #[test]
+    fn local_crate_is_in_target_package_directory() {
+        let path = local_crate_path(Utf8Path::new("target"), &package());
+        assert_eq!(path, "target/package/my_crate-1.2.3.crate");
+    }
+
+    #[test]
+    fn missing_local_crate_has_no_checksum() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let local_crate = temp_dir.path().join("my_crate-1.2.3.crate");
+        assert_eq!(local_checksum(&local_crate).unwrap(), None);
+
+        fs_err::write(&local_crate, "content").unwrap();
+        let checksum = local_checksum(&local_crate).unwrap().unwrap();
+        assert_eq!(
+            hex(&checksum),
+            "ed7002b439e9ac845f22357d822bac1444730fbdb6016d3ec9432297b9ec9f73"
+        );
+    }
+
+    #[test]
+    fn content_is_compared_without_local_crate() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let local_crate = temp_dir.path().join("my_crate-1.2.3.crate");
+        assert_eq!(
+            check_to_run(IntegrityCheck::Checksum, &local_crate),
+            IntegrityCheck::Content
+        );
+        assert_eq!(
+            check_to_run(IntegrityCheck::Content, &local_crate),
+            IntegrityCheck::Content
+        );
+
+        fs_err::write(&local_crate, "content").unwrap();
+        assert_eq!(
+            check_to_run(IntegrityCheck::Checksum, &local_crate),
+            IntegrityCheck::Checksum
+        );
+    }
+
+    #[test]
+    fn different_checksums_are_reported() {
+        let local_crate = Utf8Path::new("target/package/my_crate-1.2.3.crate");
+        ensure_same_checksum(&package(), "crates.io", local_crate, &[1; 32], &[1; 32]).unwrap();
+        let error = ensure_same_checksum(&package(), "crates.io", local_crate, &[1; 32], &[2; 32])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "my_crate 1.2.3: the checksum of the package published to crates.io ({}) is different from the checksum of {local_crate} ({})",
+                "02".repeat(32),
+                "01".repeat(32),
+            )
+        );
+    }
+
+    #[test]
+    fn package_published_from_other_commit_is_reported() {
+        ensure_published_from_current_commit(&package(), Some("abc"), "abc").unwrap();
+        // Packages published with `--allow-dirty` don't contain the commit.
+        ensure_published_from_current_commit(&package(), None, "abc").unwrap();
+        let error =
+            ensure_published_from_current_commit(&package(), Some("def"), "abc").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "my_crate 1.2.3: the package was published from commit def, but the current commit is abc"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Package,
+};
+use tracing::debug;
+
+use crate::{cargo::run_cargo, fs_utils::Utf8TempDir, package_compare::get_cargo_package_files};
+
+/// Prefixes of the file names accepted as license files.
+const LICENSE_FILE_PREFIXES: [&str; 4] = ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+
+/// Maximum size in bytes of the `.crate` file accepted by crates.io.
+pub const CRATES_IO_MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Rules that the files of a package must respect to be published.
+/// The files of the package are the ones listed by `cargo package --list`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishPolicy {
+    /// Maximum size in bytes of the `.crate` file created by `cargo package`.
+    /// If [`Option::None`] and the policy contains other rules, packages published
+    /// to crates.io can't be bigger than [`CRATES_IO_MAX_SIZE`].
+    max_size: Option<u64>,
+    /// Glob patterns of the files that must not be packaged,
+    /// relative to the package directory.
+    forbidden_files: Vec<String>,
+    /// The package must contain a README file.
+    require_readme: bool,
+    /// The package must contain a license file.
+    require_license: bool,
+}
+
+impl PublishPolicy {
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn with_forbidden_files(mut self, forbidden_files: Vec<String>) -> Self {
+        self.forbidden_files = forbidden_files;
+        self
+    }
+
+    pub fn with_require_readme(mut self, require_readme: bool) -> Self {
+        self.require_readme = require_readme;
+        self
+    }
+
+    pub fn with_require_license(mut self, require_license: bool) -> Self {
+        self.require_license = require_license;
+        self
+    }
+
+    /// Return true if the policy doesn't contain any rule.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Maximum size of the `.crate` file published to `registry`.
+    /// `registry` is [`Option::None`] for crates.io.
+    fn max_size(&self, registry: Option<&str>) -> Option<u64> {
+        self.max_size
+            .or_else(|| registry.is_none().then_some(CRATES_IO_MAX_SIZE))
+    }
+
+    /// Return true if [`Self::check`] needs the size of the `.crate` file of the packages
+    /// published to `registry`.
+    pub(crate) fn checks_size(&self, registry: Option<&str>) -> bool {
+        !self.is_empty() && self.max_size(registry).is_some()
+    }
+
+    /// Return an error listing all the rules that the package violates
+    /// when it's published to `registry`.
+    /// `registry` is [`Option::None`] for crates.io.
+    /// If the policy is empty, nothing is checked.
+    pub(crate) fn check(&self, package: &Package, registry: Option<&str>) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let sizes = if self.checks_size(registry) {
+            crate_sizes(&[package])?
+        } else {
+            BTreeMap::new()
+        };
+        self.check_with_sizes(package, registry, &sizes)
+    }
+
+    /// Like [`Self::check`], but the sizes of the `.crate` files are computed in advance
+    /// with [`crate_sizes`], e.g. to package all the crates of the workspace at once.
+    pub(crate) fn check_with_sizes(
+        &self,
+        package: &Package,
+        registry: Option<&str>,
+        sizes: &BTreeMap<String, u64>,
+    ) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let package_dir = package
+            .manifest_path
+            .parent()
+            .context("can't determine package directory")?;
+        let mut violations = vec![];
+        if let Some(max_size) = self.max_size(registry) {
+            debug!("{}: checking package size", package.name);
+            let size = sizes.get(&package.name).with_context(|| {
+                format!("cannot determine the size of package {}", package.name)
+            })?;
+            violations.extend(size_violation(*size, max_size));
+        }
+        debug!("{}: checking packaged files", package.name);
+        let files = get_cargo_package_files(package_dir).with_context(|| {
+            format!(
+                "cannot determine packaged files of package {}",
+                package.name
+            )
+        })?;
+        let declared = DeclaredFiles {
+            readme: package.readme.as_deref(),
+            license_file: package.license_file.as_deref(),
+        };
+        violations.extend(self.violations(&files, &declared)?);
+        anyhow::ensure!(
+            violations.is_empty(),
+            "{} {} doesn't respect the publish policy:\n- {}",
+            package.name,
+            package.version,
+            violations.join("\n- ")
+        );
+        Ok(())
+    }
+
+    /// Violations of the rules about the packaged `files`.
+    fn violations(
+        &self,
+        files: &[Utf8PathBuf],
+        declared: &DeclaredFiles,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut violations = vec![];
+        for pattern in &self.forbidden_files {
+            let glob = glob::Pattern::new(pattern)
+                .with_context(|| format!("invalid forbidden file pattern `{pattern}`"))?;
+            for file in files.iter().filter(|f| glob.matches(f.as_str())) {
+                violations.push(format!("{file} matches the forbidden pattern `{pattern}`"));
+            }
+        }
+        let has_readme = files
+            .iter()
+            .any(|f| has_prefix(f, &["README"]) || is_declared(f, declared.readme));
+        if self.require_readme && !has_readme {
+            violations.push("README file not found".to_string());
+        }
+        let has_license = files.iter().any(|f| {
+            has_prefix(f, &LICENSE_FILE_PREFIXES) || is_declared(f, declared.license_file)
+        });
+        if self.require_license && !has_license {
+            violations.push("license file not found".to_string());
+        }
+        Ok(violations)
+    }
+}
+
+/// Files specified in the `Cargo.toml` of the package.
+#[derive(Default)]
+struct DeclaredFiles<'a> {
+    /// `package.readme` field.
+    readme: Option<&'a Utf8Path>,
+    /// `package.license-file` field.
+    license_file: Option<&'a Utf8Path>,
+}
+
+fn size_violation(size: u64, max_size: u64) -> Option<String> {
+    (size > max_size).then(|| {
+        format!("the .crate file is {size} bytes, more than the maximum of {max_size} bytes")
+    })
+}
+
+/// Size of the compressed `.crate` files created by `cargo package`, by package name.
+/// The packages are packaged together with a single `cargo package` command,
+/// so that packages depending on other packages not published yet can be packaged.
+/// The packages are built in a temporary target directory, so that the `.crate` files
+/// created later by `cargo publish` aren't overwritten.
+pub(crate) fn crate_sizes(packages: &[&Package]) -> anyhow::Result<BTreeMap<String, u64>> {
+    let Some(first_package) = packages.first() else {
+        return Ok(BTreeMap::new());
+    };
+    let package_dir = first_package
+        .manifest_path
+        .parent()
+        .context("can't determine package directory")?;
+    let target_dir = Utf8TempDir::new()?;
+    let mut args = vec![
+        "package",
+        "--no-verify",
+        "--allow-dirty",
+        "--quiet",
+        "--target-dir",
+        target_dir.path().as_str(),
+    ];
+    for package in packages {
+        args.extend(["--package", package.name.as_str()]);
+    }
+    let output = run_cargo(package_dir, &args).context("cannot run `cargo package`")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "error while running `cargo package`: {}",
+        output.stderr
+    );
+    let mut sizes = BTreeMap::new();
+    for package in packages {
+        let crate_file = target_dir
+            .path()
+            .join("package")
+            .join(format!("{}-{}.crate", package.name, package.version));
+        let metadata = fs_err::metadata(&crate_file)?;
+        sizes.insert(package.name.clone(), metadata.len());
+    }
+    Ok(sizes)
+}
+
+/// Return true if `file` is in the root of the package and its name
+/// starts with one of the `prefixes`, ignoring the case.
+fn has_prefix(file: &Utf8Path, prefixes: &[&str]) -> bool {
+    if file.parent() != Some(Utf8Path::new("")) {
+        return false;
+    }
+    let name = file.as_str().to_uppercase();
+    prefixes.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Return true if `file` is the `declared` file.
+/// `cargo package` copies declared files located outside of the package
+/// to the root of the package.
+fn is_declared(file: &Utf8Path, declared: Option<&Utf8Path>) -> bool {
+    declared.is_some_and(|declared| {
+        file == declared
+            || (file.parent() == Some(Utf8Path::new(""))
+                && file.file_name() == declared.file_name())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(files: &[&str]) -> Vec<Utf8PathBuf> {
+        files.iter().map(Utf8PathBuf::from).collect()
+    }
+
+    #[test]
+    fn package_respecting_policy_has_no_violations() {
+        let policy = PublishPolicy::default()
+            .with_forbidden_files(vec!["**/.env".to_string(), "*.pem".to_string()])
+            .with_require_readme(true)
+            .with_require_license(true);
+        let files = files(&["Cargo.toml", "README.md", "LICENSE-MIT", "src/lib.rs"]);
+        let violations = policy
+            .violations(&files, &DeclaredFiles::default())
+            .unwrap();
+        assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn all_violations_are_reported() {
+        let policy = PublishPolicy::default()
+            .with_forbidden_files(vec![".env".to_string()])
+            .with_require_readme(true)
+            .with_require_license(true);
+        let files = files(&["src/lib.rs", ".env", "docs/README.md"]);
+        let violations = policy
+            .violations(&files, &DeclaredFiles::default())
+            .unwrap();
+        expect_test::expect![[r#"
+            [
+                ".env matches the forbidden pattern `.env`",
+                "README file not found",
+                "license file not found",
+            ]
+        "#]]
+        .assert_debug_eq(&violations);
+    }
+
+    #[test]
+    fn readme_declared_in_cargo_toml_is_found() {
+        let policy = PublishPolicy::default().with_require_readme(true);
+        let files = files(&["Cargo.toml", "docs/intro.md", "src/lib.rs"]);
+        let declared = DeclaredFiles {
+            readme: Some(Utf8Path::new("docs/intro.md")),
+            license_file: None,
+        };
+        let violations = policy.violations(&files, &declared).unwrap();
+        assert!(violations.is_empty(), "{violations:?}");
+    }
+
+    #[test]
+    fn crates_io_size_limit_is_the_default() {
+        let policy = PublishPolicy::default();
+        assert_eq!(policy.max_size(None), Some(CRATES_IO_MAX_SIZE));
+        assert_eq!(policy.max_size(Some("my-registry")), None);
+        let policy = policy.with_max_size(100);
+        assert_eq!(policy.max_size(None), Some(100));
+        assert_eq!(policy.max_size(Some("my-registry")), Some(100));
+    }
+
+    #[test]
+    fn size_is_checked_only_with_a_policy() {
+        let policy = PublishPolicy::default();
+        assert!(!policy.checks_size(None));
+        let policy = policy.with_require_readme(true);
+        assert!(policy.checks_size(None));
+        assert!(!policy.checks_size(Some("my-registry")));
+        let policy = policy.with_max_size(100);
+        assert!(policy.checks_size(Some("my-registry")));
+    }
+
+    #[test]
+    fn packages_depending_on_each_other_are_sized_together() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp_dir.path()).unwrap();
+        fs_err::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"lib_dep\", \"lib_user\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        let manifest = |name: &str, dependencies: &str| {
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\ndescription = \"d\"\nlicense = \"MIT\"\n\n[dependencies]\n{dependencies}")
+        };
+        let mut packages = vec![];
+        for (name, dependencies) in [
+            ("lib_dep", ""),
+            (
+                "lib_user",
+                "lib_dep = { path = \"../lib_dep\", version = \"0.1.0\" }\n",
+            ),
+        ] {
+            let package_dir = root.join(name);
+            fs_err::create_dir_all(package_dir.join("src")).unwrap();
+            fs_err::write(package_dir.join("Cargo.toml"), manifest(name, dependencies)).unwrap();
+            fs_err::write(package_dir.join("src/lib.rs"), "").unwrap();
+            let mut package: Package = fake_package::FakePackage::new(name).into();
+            package.manifest_path = package_dir.join("Cargo.toml");
+            packages.push(package);
+        }
+
+        let packages: Vec<&Package> = packages.iter().collect();
+        let sizes = crate_sizes(&packages).unwrap();
+        assert_eq!(
+            sizes.keys().collect::<Vec<_>>(),
+            vec!["lib_dep", "lib_user"]
+        );
+    }
+
+    #[test]
+    fn compressed_crate_size_is_checked() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let package_dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+        fs_err::create_dir_all(package_dir.join("src")).unwrap();
+        fs_err::write(
+            package_dir.join("Cargo.toml"),
+            "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\nedition = \"2021\"\ndescription = \"d\"\nlicense = \"MIT\"\n",
+        )
+        .unwrap();
+        // Repetitive content compresses well.
+        fs_err::write(package_dir.join("src/lib.rs"), "// a\n".repeat(10_000)).unwrap();
+        let mut package: Package = fake_package::FakePackage::new("my_crate").into();
+        package.manifest_path = package_dir.join("Cargo.toml");
+
+        let size = crate_sizes(&[&package]).unwrap()["my_crate"];
+        assert!(size < 10_000, "{size}");
+        assert!(size_violation(size, 10_000).is_none());
+        expect_test::expect![[r#"
+            Some(
+                "the .crate file is 100 bytes, more than the maximum of 50 bytes",
+            )
+        "#]]
+        .assert_debug_eq(&size_violation(100, 50));
+    }
+}
@@ -0,0 +1,335 @@
+use std::{future::Future, time::Duration};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use tracing::warn;
+
+use crate::cargo::CmdOutput;
+
+/// How `cargo publish` failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublishFailure {
+    /// The registry is temporarily unavailable or rate-limited us.
+    Transient { retry_after: Option<Duration> },
+    /// crates.io limits how many new crates can be published in a short period of time.
+    /// The limit is lifted after some minutes, so we need to wait longer than for other errors.
+    NewCrateRateLimit { retry_after: Option<Duration> },
+    /// Retrying won't help, e.g. because the package doesn't compile.
+    Fatal,
+}
+
+/// Retry policy for `cargo publish`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishRetry {
+    /// Maximum number of retries for transient errors.
+    pub max_retries: u32,
+    /// Delay before the first retry. It's doubled at every retry.
+    pub initial_backoff: Duration,
+    /// Maximum delay between retries, also applied to the `Retry-After` hint.
+    pub max_backoff: Duration,
+    /// Maximum number of retries when hitting the rate limit for new crates.
+    pub new_crate_max_retries: u32,
+    /// Wait used when crates.io doesn't tell when the rate limit for new crates is lifted.
+    pub new_crate_wait: Duration,
+    /// Maximum wait for the rate limit for new crates.
+    pub new_crate_max_wait: Duration,
+}
+
+impl Default for PublishRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(120),
+            new_crate_max_retries: 3,
+            // crates.io allows a new crate every 10 minutes after the initial burst.
+            new_crate_wait: Duration::from_secs(10 * 60),
+            new_crate_max_wait: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+impl PublishRetry {
+    /// Don't retry.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            new_crate_max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retrying, or [`Option::None`] if we shouldn't retry.
+    /// `transient_retries` and `new_crate_retries` are the retries already done.
+    fn delay(
+        &self,
+        failure: &PublishFailure,
+        transient_retries: u32,
+        new_crate_retries: u32,
+    ) -> Option<Duration> {
+        match failure {
+            PublishFailure::Fatal => None,
+            PublishFailure::Transient { retry_after } => (transient_retries < self.max_retries)
+                .then(|| {
+                    let backoff = self
+                        .initial_backoff
+                        .saturating_mul(2_u32.saturating_pow(transient_retries));
+                    retry_after.unwrap_or(backoff).min(self.max_backoff)
+                }),
+            PublishFailure::NewCrateRateLimit { retry_after } => {
+                (new_crate_retries < self.new_crate_max_retries).then(|| {
+                    // Add a margin, because the clocks of the registry and of this machine might differ.
+                    let margin = Duration::from_secs(5);
+                    retry_after
+                        .map(|d| d + margin)
+                        .unwrap_or(self.new_crate_wait)
+                        .min(self.new_crate_max_wait)
+                })
+            }
+        }
+    }
+}
+
+/// Return true if the output of `cargo publish` reports a successful upload.
+pub fn is_publish_successful(output: &CmdOutput) -> bool {
+    output.status.success()
+        && output.stderr.contains("Uploading")
+        && !output.stderr.contains("error:")
+}
+
+/// Classify the failure of `cargo publish` from its stderr.
+pub fn classify_publish_failure(stderr: &str, now: DateTime<Utc>) -> PublishFailure {
+    let stderr_lowercase = stderr.to_lowercase();
+    let retry_after = retry_after(stderr, now);
+    if stderr_lowercase.contains("too many new crates") {
+        return PublishFailure::NewCrateRateLimit { retry_after };
+    }
+    let is_transient = [
+        "status 429",
+        "too many requests",
+        "status 500",
+        "status 502",
+        "status 503",
+        "status 504",
+        "internal server error",
+        "bad gateway",
+        "service unavailable",
+        "gateway timeout",
+        "timeout was reached",
+        "timed out",
+        "spurious network error",
+        "couldn't connect to server",
+        "failed to connect",
+        "connection reset",
+        "couldn't resolve host",
+        "failure when receiving data",
+        "ssl connect error",
+    ]
+    .iter()
+    .any(|pattern| stderr_lowercase.contains(pattern));
+    if is_transient {
+        PublishFailure::Transient { retry_after }
+    } else {
+        PublishFailure::Fatal
+    }
+}
+
+/// Parse the hint about when to retry.
+/// crates.io writes it as `Please try again after <HTTP date>`.
+/// Some registries expose the `Retry-After: <seconds>` header in the message.
+fn retry_after(stderr: &str, now: DateTime<Utc>) -> Option<Duration> {
+    lazy_static::lazy_static! {
+        static ref TRY_AGAIN_AFTER_RE: Regex =
+            Regex::new(r"(?i)try again after ([A-Za-z]{3}, \d{1,2} [A-Za-z]{3} \d{4} \d{2}:\d{2}:\d{2} GMT)").unwrap();
+        static ref RETRY_AFTER_SECONDS_RE: Regex =
+            Regex::new(r"(?i)retry-after:?\s*(\d+)").unwrap();
+    }
+    if let Some(captures) = TRY_AGAIN_AFTER_RE.captures(stderr) {
+        let date = DateTime::parse_from_rfc2822(&captures[1]).ok()?;
+        let delay = date.with_timezone(&Utc) - now;
+        return Some(delay.to_std().unwrap_or(Duration::ZERO));
+    }
+    let captures = RETRY_AFTER_SECONDS_RE.captures(stderr)?;
+    let seconds = captures[1].parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Run `publish` until it succeeds, retrying transient failures according to `retry`.
+/// Return the output of the last attempt, and an error if the package wasn't published.
+pub async fn publish_with_retry<F, Fut>(
+    retry: &PublishRetry,
+    package_name: &str,
+    mut publish: F,
+) -> anyhow::Result<CmdOutput>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<CmdOutput>>,
+{
+    let mut transient_retries = 0;
+    let mut new_crate_retries = 0;
+    loop {
+        let output = publish().await?;
+        if is_publish_successful(&output) {
+            return Ok(output);
+        }
+        let is_retry = transient_retries + new_crate_retries > 0;
+        if is_retry && is_already_uploaded(&output.stderr) {
+            // A previous attempt uploaded the package, even if cargo reported an error.
+            warn!("{package_name}: already uploaded by a previous attempt");
+            return Ok(output);
+        }
+        let failure = classify_publish_failure(&output.stderr, Utc::now());
+        let Some(delay) = retry.delay(&failure, transient_retries, new_crate_retries) else {
+            anyhow::bail!("failed to publish {package_name}: {}", output.stderr);
+        };
+        match failure {
+            PublishFailure::NewCrateRateLimit { .. } => {
+                new_crate_retries += 1;
+                warn!("{package_name}: crates.io rate limit for new crates reached, retrying in {delay:?}");
+            }
+            _ => {
+                transient_retries += 1;
+                warn!(
+                    "{package_name}: transient error while publishing, retrying in {delay:?}: {}",
+                    output.stderr.trim()
+                );
+            }
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn is_already_uploaded(stderr: &str) -> bool {
+    stderr.contains("already uploaded") || stderr.contains("already exists")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process::ExitStatus, sync::Mutex};
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-12-20T20:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn output(stderr: &str) -> CmdOutput {
+        CmdOutput {
+            status: ExitStatus::default(),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    fn no_wait() -> PublishRetry {
+        PublishRetry {
+            initial_backoff: Duration::ZERO,
+            new_crate_wait: Duration::ZERO,
+            new_crate_max_wait: Duration::ZERO,
+            ..PublishRetry::default()
+        }
+    }
+
+    #[test]
+    fn new_crate_rate_limit_is_classified() {
+        let stderr = "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): You have published too many new crates in a short period of time. Please try again after Fri, 20 Dec 2024 20:04:31 GMT or email help@crates.io to have your limit increased.";
+        assert_eq!(
+            classify_publish_failure(stderr, now()),
+            PublishFailure::NewCrateRateLimit {
+                retry_after: Some(Duration::from_secs(4 * 60 + 31))
+            }
+        );
+    }
+
+    #[test]
+    fn server_error_is_transient() {
+        let stderr = "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 503 Service Unavailable): Service Unavailable";
+        assert_eq!(
+            classify_publish_failure(stderr, now()),
+            PublishFailure::Transient { retry_after: None }
+        );
+    }
+
+    #[test]
+    fn retry_after_seconds_is_parsed() {
+        let stderr = "error: the remote server responded with an error (status 429 Too Many Requests): Retry-After: 30";
+        assert_eq!(
+            classify_publish_failure(stderr, now()),
+            PublishFailure::Transient {
+                retry_after: Some(Duration::from_secs(30))
+            }
+        );
+    }
+
+    #[test]
+    fn compilation_error_is_fatal() {
+        let stderr = "error[E0425]: cannot find value `x` in this scope\nerror: failed to verify package tarball";
+        assert_eq!(
+            classify_publish_failure(stderr, now()),
+            PublishFailure::Fatal
+        );
+    }
+
+    #[test]
+    fn backoff_is_exponential_and_capped() {
+        let retry = PublishRetry::default();
+        let transient = PublishFailure::Transient { retry_after: None };
+        assert_eq!(retry.delay(&transient, 0, 0), Some(Duration::from_secs(5)));
+        assert_eq!(retry.delay(&transient, 2, 0), Some(Duration::from_secs(20)));
+        let long_hint = PublishFailure::Transient {
+            retry_after: Some(Duration::from_secs(3600)),
+        };
+        assert_eq!(retry.delay(&long_hint, 0, 0), Some(retry.max_backoff));
+        assert_eq!(retry.delay(&transient, retry.max_retries, 0), None);
+        assert_eq!(retry.delay(&PublishFailure::Fatal, 0, 0), None);
+    }
+
+    #[tokio::test]
+    async fn transient_failure_is_retried() {
+        let attempts = Mutex::new(vec![
+            output("Uploading a v0.1.0"),
+            output("error: the remote server responded with an error (status 502 Bad Gateway)"),
+        ]);
+        let result = publish_with_retry(&no_wait(), "a", || {
+            let output = attempts.lock().unwrap().pop().unwrap();
+            async { Ok(output) }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert!(attempts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fatal_failure_is_not_retried() {
+        let attempts = Mutex::new(0);
+        let result = publish_with_retry(&no_wait(), "a", || {
+            *attempts.lock().unwrap() += 1;
+            async { Ok(output("error: failed to verify package tarball")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(*attempts.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn already_uploaded_after_retry_is_success() {
+        let attempts = Mutex::new(vec![
+            output("error: crate version `0.1.0` is already uploaded"),
+            output("error: the remote server responded with an error (status 504 Gateway Timeout)"),
+        ]);
+        let result = publish_with_retry(&no_wait(), "a", || {
+            let output = attempts.lock().unwrap().pop().unwrap();
+            async { Ok(output) }
+        })
+        .await;
+        assert!(result.is_ok());
+    }
+}
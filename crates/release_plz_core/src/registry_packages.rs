@@ -1,7 +1,11 @@
 use std::collections::BTreeMap;
 
 use anyhow::Context;
-use cargo_metadata::{camino::Utf8Path, Package};
+use cargo_metadata::{
+    camino::Utf8Path,
+    semver::{Version, VersionReq},
+    Package,
+};
 use git_cmd::git_in_dir;
 use itertools::Itertools;
 use tempfile::{tempdir, TempDir};
@@ -45,10 +49,16 @@ impl PackagesCollection {
 ///
 /// - If `registry` is provided, the packages are downloaded from the specified registry.
 /// - Otherwise, the packages are downloaded from crates.io.
+///
+/// - If `version` is provided, the latest version matching this requirement is downloaded
+///   (e.g. `^1` for the maintenance branch of the `1.x` release line).
+///   Packages whose local version doesn't match the requirement (e.g. crates of the
+///   workspace with a different major version) are compared with their latest version.
 pub fn get_registry_packages(
     registry_manifest: Option<&Utf8Path>,
     local_packages: &[&Package],
     registry: Option<&str>,
+    version: Option<&str>,
 ) -> anyhow::Result<PackagesCollection> {
     let (temp_dir, registry_packages) = match registry_manifest {
         Some(manifest) => (
@@ -65,24 +75,39 @@ pub fn get_registry_packages(
             let temp_dir = tempdir().context("failed to get a temporary directory")?;
             let directory = temp_dir.as_ref().to_str().context("invalid tempdir path")?;
 
-            // Find the registry from where to download each package.
+            let version_req = version
+                .map(|v| {
+                    VersionReq::parse(v)
+                        .with_context(|| format!("invalid registry version requirement `{v}`"))
+                })
+                .transpose()?;
+            // Find the registry and the version from where to download each package.
             let packages_grouped_by_registry = local_packages.iter().chunk_by(|p| {
                 // If registry is not provided, fallback to the Cargo.toml `publish` field.
-                registry.or_else(|| {
+                let registry = registry.or_else(|| {
                     p.publish
                         .as_ref()
                         // Use the first registry in the `publish` field.
                         .and_then(|p| p.first())
                         .map(|x| x.as_str())
-                })
+                });
+                let version = version.filter(|_| {
+                    version_req
+                        .as_ref()
+                        .is_some_and(|req| matches_version_req(&p.version, req))
+                });
+                (registry, version)
             });
             let mut registry_packages: Vec<Package> = vec![];
-            for (registry, packages) in &packages_grouped_by_registry {
+            for ((registry, version), packages) in &packages_grouped_by_registry {
                 let packages_names: Vec<&str> = packages.map(|p| p.name.as_str()).collect();
                 let mut downloader = download::PackageDownloader::new(packages_names, directory);
                 if let Some(registry) = registry {
                     downloader = downloader.with_registry(registry.to_string());
                 }
+                if let Some(version) = version {
+                    downloader = downloader.with_version(version.to_string());
+                }
                 registry_packages.extend(
                     downloader
                         .download()
@@ -111,7 +136,17 @@ pub fn get_registry_packages(
     })
 }
 
-fn initialize_registry_package(packages: Vec<Package>) -> anyhow::Result<Vec<RegistryPackage>> {
+/// Whether the local `version` of a package belongs to the release line of `version_req`.
+/// Pre-releases match if their major, minor and patch version match, e.g. `1.2.0-rc.1`
+/// matches `^1`.
+fn matches_version_req(version: &Version, version_req: &VersionReq) -> bool {
+    let release = Version::new(version.major, version.minor, version.patch);
+    version_req.matches(version) || version_req.matches(&release)
+}
+
+pub(crate) fn initialize_registry_package(
+    packages: Vec<Package>,
+) -> anyhow::Result<Vec<RegistryPackage>> {
     let mut registry_packages = vec![];
     for p in packages {
         let package_path = p.package_path().unwrap();
@@ -145,3 +180,35 @@ fn initialize_registry_package(packages: Vec<Package>) -> anyhow::Result<Vec<Reg
     }
     Ok(registry_packages)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(version: &str, version_req: &str) -> bool {
+        matches_version_req(
+            &Version::parse(version).unwrap(),
+            &VersionReq::parse(version_req).unwrap(),
+        )
+    }
+
+    #[test]
+    fn version_of_the_release_line_matches() {
+        assert!(matches("1.2.3", "^1"));
+        assert!(matches("1.0.0", "^1"));
+        assert!(matches("0.3.1", "^0.3"));
+    }
+
+    #[test]
+    fn version_of_another_release_line_does_not_match() {
+        assert!(!matches("2.0.0", "^1"));
+        assert!(!matches("0.1.0", "^1"));
+        assert!(!matches("0.4.0", "^0.3"));
+    }
+
+    #[test]
+    fn pre_release_of_the_release_line_matches() {
+        assert!(matches("1.3.0-rc.1", "^1"));
+        assert!(!matches("2.0.0-rc.1", "^1"));
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Context;
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+/// File attached to a GitHub/Gitea/GitLab release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    /// File name shown in the release.
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+/// Find the files matching the glob `patterns`, relative to `root`.
+/// If `checksum` is true, add a `<file>.sha256` asset for each file,
+/// in the same format of the `sha256sum` command.
+pub fn find_release_assets(
+    root: &Utf8Path,
+    patterns: &[String],
+    checksum: bool,
+) -> anyhow::Result<Vec<ReleaseAsset>> {
+    let mut paths: Vec<Utf8PathBuf> = vec![];
+    for pattern in patterns {
+        let matched = matching_files(root, pattern)?;
+        if matched.is_empty() {
+            warn!("no release asset found for pattern `{pattern}`");
+        }
+        paths.extend(matched);
+    }
+    paths.sort();
+    paths.dedup();
+
+    let mut assets = vec![];
+    for path in paths {
+        let name = path
+            .file_name()
+            .with_context(|| format!("invalid release asset path {path:?}"))?
+            .to_string();
+        anyhow::ensure!(
+            !assets.iter().any(|a: &ReleaseAsset| a.name == name),
+            "release assets must have unique file names, but `{name}` is present more than once"
+        );
+        let content = fs_err::read(&path).context("can't read release asset")?;
+        debug!("found release asset {path:?}");
+        if checksum {
+            let checksum = sha256_checksum(&name, &content);
+            assets.push(ReleaseAsset {
+                name: name.clone(),
+                content,
+            });
+            assets.push(ReleaseAsset {
+                name: format!("{name}.sha256"),
+                content: checksum.into_bytes(),
+            });
+        } else {
+            assets.push(ReleaseAsset { name, content });
+        }
+    }
+    Ok(assets)
+}
+
+fn matching_files(root: &Utf8Path, pattern: &str) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    // Escape the root, because it might contain glob special characters.
+    let full_pattern = Utf8Path::new(&glob::Pattern::escape(root.as_str())).join(pattern);
+    glob::glob(full_pattern.as_str())
+        .with_context(|| format!("invalid release asset pattern `{pattern}`"))?
+        .map(|entry| {
+            let path = entry.context("can't read release asset")?;
+            Utf8PathBuf::from_path_buf(path)
+                .map_err(|p| anyhow::anyhow!("release asset path {p:?} is not valid utf8"))
+        })
+        .filter(|path| path.as_ref().map_or(true, |p| p.is_file()))
+        .collect()
+}
+
+/// Same format of `sha256sum <file>`.
+fn sha256_checksum(file_name: &str, content: &[u8]) -> String {
+    let hash = Sha256::digest(content);
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{hex}  {file_name}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assets_are_found_with_checksum() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp_dir.path()).unwrap();
+        fs_err::create_dir_all(root.join("dist")).unwrap();
+        fs_err::write(root.join("dist/a.tar.gz"), "a").unwrap();
+        fs_err::write(root.join("dist/b.zip"), "b").unwrap();
+        fs_err::write(root.join("dist/c.txt"), "c").unwrap();
+
+        let assets = find_release_assets(
+            root,
+            &["dist/*.tar.gz".to_string(), "dist/*.zip".to_string()],
+            true,
+        )
+        .unwrap();
+        let names: Vec<&str> = assets.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["a.tar.gz", "a.tar.gz.sha256", "b.zip", "b.zip.sha256"]
+        );
+        assert_eq!(
+            String::from_utf8(assets[1].content.clone()).unwrap(),
+            "ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb  a.tar.gz\n"
+        );
+    }
+
+    #[test]
+    fn assets_with_same_name_are_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(temp_dir.path()).unwrap();
+        for dir in ["x", "y"] {
+            fs_err::create_dir_all(root.join(dir)).unwrap();
+            fs_err::write(root.join(dir).join("bin"), dir).unwrap();
+        }
+        find_release_assets(root, &["*/bin".to_string()], false).unwrap_err();
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use anyhow::Context;
+use cargo_metadata::camino::Utf8Path;
+use tracing::{debug, info};
+
+/// Commands run around the release steps of a package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseHooks {
+    /// Run before `cargo publish`.
+    pub pre_publish: Option<String>,
+    /// Run after the registry index contains the published package.
+    pub post_publish: Option<String>,
+    /// Run after the git tag is pushed and the git release is created.
+    pub post_release: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    PrePublish,
+    PostPublish,
+    PostRelease,
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PrePublish => write!(f, "pre_publish_hook"),
+            Self::PostPublish => write!(f, "post_publish_hook"),
+            Self::PostRelease => write!(f, "post_release_hook"),
+        }
+    }
+}
+
+impl ReleaseHooks {
+    pub fn get(&self, kind: HookKind) -> Option<&str> {
+        match kind {
+            HookKind::PrePublish => self.pre_publish.as_deref(),
+            HookKind::PostPublish => self.post_publish.as_deref(),
+            HookKind::PostRelease => self.post_release.as_deref(),
+        }
+    }
+}
+
+/// Run `command` with the system shell in `dir`.
+/// The output of the command is logged, so that it doesn't mix with the
+/// output of release-plz.
+/// Return an error if the command fails.
+pub async fn run_hook(
+    kind: HookKind,
+    command: &str,
+    dir: &Utf8Path,
+    env: &[(&str, &str)],
+) -> anyhow::Result<()> {
+    info!("running {kind}: {command}");
+    let output = shell_command(command)
+        .current_dir(dir)
+        .envs(env.iter().copied())
+        .output()
+        .await
+        .with_context(|| format!("can't run {kind} `{command}`"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stdout.trim().is_empty() {
+        info!("{kind} stdout:\n{}", stdout.trim_end());
+    }
+    if !stderr.trim().is_empty() {
+        debug!("{kind} stderr:\n{}", stderr.trim_end());
+    }
+    anyhow::ensure!(
+        output.status.success(),
+        "{kind} `{command}` failed with {}:\n{stderr}",
+        output.status
+    );
+    Ok(())
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+    if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn hook_receives_env_vars() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+        run_hook(
+            HookKind::PostRelease,
+            r#"echo "$RELEASE_PLZ_PACKAGE_NAME" > out.txt"#,
+            dir,
+            &[("RELEASE_PLZ_PACKAGE_NAME", "my_crate")],
+        )
+        .await
+        .unwrap();
+        let out = fs_err::read_to_string(dir.join("out.txt")).unwrap();
+        assert_eq!(out.trim(), "my_crate");
+    }
+
+    #[tokio::test]
+    async fn failing_hook_returns_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+        let err = run_hook(HookKind::PrePublish, "echo oops >&2; exit 3", dir, &[])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("oops"), "{err}");
+    }
+}
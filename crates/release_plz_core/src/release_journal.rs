@@ -0,0 +1,228 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::Mutex,
+};
+
+use anyhow::Context;
+use cargo_metadata::{camino::Utf8PathBuf, Package};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Step of the release of a package.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum ReleaseStep {
+    /// The `pre_publish_hook` succeeded.
+    PrePublishHook {
+        /// [`Option::None`] means crates.io.
+        registry: Option<String>,
+    },
+    /// `cargo publish` succeeded.
+    Publish {
+        /// [`Option::None`] means crates.io.
+        registry: Option<String>,
+    },
+    /// The package is visible in the registry index.
+    Wait {
+        /// [`Option::None`] means crates.io.
+        registry: Option<String>,
+    },
+    /// The `post_publish_hook` succeeded.
+    PostPublishHook {
+        /// [`Option::None`] means crates.io.
+        registry: Option<String>,
+    },
+    /// The git tag was created locally.
+    Tag,
+    /// The git tag was pushed to the remote.
+    Push,
+    /// The GitHub/Gitea/GitLab release was created.
+    GitRelease,
+    /// The assets were uploaded to the GitHub/Gitea/GitLab release.
+    GitReleaseAssets,
+    /// The `post_release_hook` succeeded.
+    PostReleaseHook,
+    /// The release of the package is completed, but the combined git release
+    /// containing the package wasn't created yet.
+    Released,
+}
+
+impl fmt::Display for ReleaseStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registry_name =
+            |registry: &Option<String>| registry.clone().unwrap_or_else(|| "crates.io".to_string());
+        match self {
+            Self::PrePublishHook { registry } => {
+                write!(f, "run pre_publish_hook for {}", registry_name(registry))
+            }
+            Self::Publish { registry } => write!(f, "publish to {}", registry_name(registry)),
+            Self::Wait { registry } => write!(f, "wait for {}", registry_name(registry)),
+            Self::PostPublishHook { registry } => {
+                write!(f, "run post_publish_hook for {}", registry_name(registry))
+            }
+            Self::Tag => write!(f, "create git tag"),
+            Self::Push => write!(f, "push git tag"),
+            Self::GitRelease => write!(f, "create git release"),
+            Self::GitReleaseAssets => write!(f, "upload git release assets"),
+            Self::PostReleaseHook => write!(f, "run post_release_hook"),
+            Self::Released => write!(f, "release package"),
+        }
+    }
+}
+
+/// Persisted record of the release steps completed for each package.
+///
+/// A package is added to the journal when the first step of its release is completed,
+/// and it's removed when its release is completed.
+/// So, if the journal contains a package, its release was interrupted,
+/// and the next run can resume the missing steps.
+#[derive(Debug, Default)]
+pub struct ReleaseJournal {
+    /// Where the journal is saved.
+    /// [`Option::None`] means that the journal is kept in memory only (e.g. during a dry run).
+    path: Option<Utf8PathBuf>,
+    entries: Mutex<JournalEntries>,
+}
+
+/// The key is `<package>@<version>`.
+type JournalEntries = BTreeMap<String, BTreeSet<ReleaseStep>>;
+
+impl ReleaseJournal {
+    /// Load the journal from `path`.
+    /// If the file doesn't exist, the journal is empty.
+    pub fn load(path: Utf8PathBuf) -> anyhow::Result<Self> {
+        let entries = if path.exists() {
+            let content = fs_err::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("invalid release journal at {path:?}"))?
+        } else {
+            JournalEntries::default()
+        };
+        debug!("loaded release journal from {path:?}: {entries:?}");
+        Ok(Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Return true if a previous run didn't complete the release of the package.
+    pub fn is_interrupted(&self, package: &Package) -> bool {
+        self.lock_entries().contains_key(&key(package))
+    }
+
+    pub fn is_done(&self, package: &Package, step: &ReleaseStep) -> bool {
+        self.lock_entries()
+            .get(&key(package))
+            .is_some_and(|steps| steps.contains(step))
+    }
+
+    /// Steps completed for the package.
+    pub fn steps(&self, package: &Package) -> BTreeSet<ReleaseStep> {
+        self.lock_entries()
+            .get(&key(package))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Record that `step` was completed and save the journal.
+    pub fn record(&self, package: &Package, step: ReleaseStep) -> anyhow::Result<()> {
+        let mut entries = self.lock_entries();
+        debug!("{} {}: {step} done", package.name, package.version);
+        entries.entry(key(package)).or_default().insert(step);
+        self.save(&entries)
+    }
+
+    /// Remove the package from the journal because its release is completed.
+    pub fn complete(&self, package: &Package) -> anyhow::Result<()> {
+        let mut entries = self.lock_entries();
+        if entries.remove(&key(package)).is_some() {
+            debug!(
+                "{} {}: release completed, removing it from the release journal",
+                package.name, package.version
+            );
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, JournalEntries> {
+        // The journal is still consistent if a thread panicked while holding the lock,
+        // because we only insert or remove entries.
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn save(&self, entries: &JournalEntries) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if entries.is_empty() {
+            if path.exists() {
+                fs_err::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let content =
+            serde_json::to_string_pretty(entries).context("can't serialize release journal")?;
+        fs_err::write(path, content).context("can't write release journal")
+    }
+}
+
+fn key(package: &Package) -> String {
+    format!("{}@{}", package.name, package.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use fake_package::FakePackage;
+
+    use super::*;
+
+    #[test]
+    fn journal_is_persisted_until_release_is_complete() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::from_path_buf(temp_dir.path().join("journal.json")).unwrap();
+        let package: Package = FakePackage::new("a").into();
+
+        let journal = ReleaseJournal::load(path.clone()).unwrap();
+        assert!(!journal.is_interrupted(&package));
+        journal
+            .record(&package, ReleaseStep::Publish { registry: None })
+            .unwrap();
+        journal.record(&package, ReleaseStep::Tag).unwrap();
+
+        let journal = ReleaseJournal::load(path.clone()).unwrap();
+        assert!(journal.is_interrupted(&package));
+        assert!(journal.is_done(&package, &ReleaseStep::Tag));
+        assert!(!journal.is_done(&package, &ReleaseStep::Push));
+
+        journal.complete(&package).unwrap();
+        assert!(!path.exists());
+        let journal = ReleaseJournal::load(path).unwrap();
+        assert!(!journal.is_interrupted(&package));
+    }
+
+    #[test]
+    fn journal_is_serialized() {
+        let entries: JournalEntries = [(
+            "a@0.1.0".to_string(),
+            [
+                ReleaseStep::PrePublishHook { registry: None },
+                ReleaseStep::Publish { registry: None },
+                ReleaseStep::Wait {
+                    registry: Some("my-registry".to_string()),
+                },
+                ReleaseStep::GitRelease,
+            ]
+            .into(),
+        )]
+        .into();
+        expect_test::expect![[r#"{"a@0.1.0":[{"step":"pre_publish_hook","registry":null},{"step":"publish","registry":null},{"step":"wait","registry":"my-registry"},{"step":"git_release"}]}"#]]
+            .assert_eq(&serde_json::to_string(&entries).unwrap());
+    }
+}
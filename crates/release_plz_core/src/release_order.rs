@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use cargo_metadata::{Dependency, DependencyKind, Package};
 use tracing::debug;
 
@@ -17,6 +19,40 @@ pub fn release_order<'a>(packages: &'a [&Package]) -> anyhow::Result<Vec<&'a Pac
     Ok(order)
 }
 
+/// Group packages in levels that can be released concurrently.
+/// Packages of a level only depend on packages of the previous levels.
+/// Within a level, packages keep the order returned by [`release_order`].
+/// Return an error if a circular dependency is detected.
+pub fn release_levels<'a>(packages: &'a [&Package]) -> anyhow::Result<Vec<Vec<&'a Package>>> {
+    let order = release_order(packages)?;
+    let mut levels: Vec<Vec<&Package>> = vec![];
+    let mut package_levels: HashMap<&str, usize> = HashMap::new();
+    for pkg in order {
+        // A package is released right after the level of its last dependency.
+        let level = pkg
+            .dependencies
+            .iter()
+            .filter(|d| d.name != pkg.name && should_dep_be_released_before(d, pkg))
+            .filter_map(|d| package_levels.get(d.name.as_str()))
+            .map(|dep_level| dep_level + 1)
+            .max()
+            .unwrap_or(0);
+        package_levels.insert(&pkg.name, level);
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Vec::new);
+        }
+        levels[level].push(pkg);
+    }
+    debug!(
+        "Release levels: {:?}",
+        levels
+            .iter()
+            .map(|l| l.iter().map(|p| &p.name).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    );
+    Ok(levels)
+}
+
 /// The `passed` argument is used to track packages that you already visited to
 /// detect circular dependencies.
 fn release_order_inner<'a>(
@@ -133,6 +169,14 @@ mod tests {
             .collect()
     }
 
+    fn levels<'a>(pkgs: &'a [&'a Package]) -> Vec<Vec<&'a str>> {
+        release_levels(pkgs)
+            .unwrap()
+            .iter()
+            .map(|level| level.iter().map(|p| p.name.as_str()).collect())
+            .collect()
+    }
+
     // Diagrams created with https://asciiflow.com/
 
     /// A─►B
@@ -199,6 +243,34 @@ mod tests {
         assert_eq!(order(&pkgs), ["c", "b", "a"]);
     }
 
+    /// A────►C
+    /// │     ▲
+    /// └─►B──┘
+    #[test]
+    fn three_packages_are_grouped_in_levels() {
+        let pkgs = [
+            &pkg("a", &[dep("b"), dep("c")]),
+            &pkg("b", &[dep("c")]),
+            &pkg("c", &[]),
+        ];
+        assert_eq!(levels(&pkgs), [vec!["c"], vec!["b"], vec!["a"]]);
+    }
+
+    /// A──►C◄──B   D
+    ///     ▲
+    /// E───┘
+    #[test]
+    fn independent_packages_are_in_the_same_level() {
+        let pkgs = [
+            &pkg("a", &[dep("c")]),
+            &pkg("b", &[dep("c")]),
+            &pkg("c", &[]),
+            &pkg("d", &[]),
+            &pkg("e", &[dev_dep("c")]),
+        ];
+        assert_eq!(levels(&pkgs), [vec!["c", "d", "e"], vec!["a", "b"]]);
+    }
+
     /// ┌──┐
     /// │  ▼
     /// A  B (dev dependency)
@@ -0,0 +1,267 @@
+use std::fmt;
+
+use cargo_metadata::semver::Version;
+use serde::Serialize;
+
+/// What `release` would do, computed in dry run.
+#[derive(Debug, Default, Serialize)]
+pub struct ReleasePlan {
+    pub(crate) packages: Vec<PackageReleasePlan>,
+    /// [`Option::None`] if the combined git release is disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) combined_git_release: Option<CombinedGitReleasePlan>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackageReleasePlan {
+    pub(crate) package_name: String,
+    pub(crate) version: Version,
+    pub(crate) registries: Vec<RegistryPlan>,
+    pub(crate) tag: TagPlan,
+    /// [`Option::None`] if the git release is disabled.
+    pub(crate) git_release: Option<GitReleasePlan>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegistryPlan {
+    /// `crates.io` for the default registry.
+    pub(crate) name: String,
+    pub(crate) already_published: bool,
+    /// Whether `cargo publish` would run.
+    pub(crate) publish: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagPlan {
+    pub(crate) name: String,
+    pub(crate) exists: bool,
+    /// Whether the git tag would be created and pushed.
+    pub(crate) create: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitReleasePlan {
+    pub(crate) name: String,
+    pub(crate) body: String,
+    pub(crate) draft: bool,
+    pub(crate) pre_release: bool,
+    /// Names of the files that would be uploaded to the git release.
+    pub(crate) assets: Vec<String>,
+    /// Whether the git release would be created.
+    pub(crate) create: bool,
+}
+
+/// Git release containing all the released packages.
+#[derive(Debug, Serialize)]
+pub struct CombinedGitReleasePlan {
+    pub(crate) tag: TagPlan,
+    pub(crate) name: String,
+    pub(crate) body: String,
+    /// Packages included in the release.
+    pub(crate) packages: Vec<String>,
+}
+
+impl fmt::Display for ReleasePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.packages.is_empty() {
+            return writeln!(f, "Nothing to release.");
+        }
+        for (i, package) in self.packages.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{package}")?;
+        }
+        if let Some(combined) = &self.combined_git_release {
+            writeln!(f)?;
+            write!(f, "{combined}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CombinedGitReleasePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "combined git release")?;
+        if self.packages.is_empty() {
+            return writeln!(
+                f,
+                "- don't create combined git release: no package to release"
+            );
+        }
+        if self.tag.create {
+            writeln!(f, "- create and push git tag {}", self.tag.name)?;
+        }
+        writeln!(
+            f,
+            "- create git release \"{}\" of {}",
+            self.name,
+            self.packages.join(", ")
+        )?;
+        write_body(f, &self.body)
+    }
+}
+
+fn write_body(f: &mut fmt::Formatter<'_>, body: &str) -> fmt::Result {
+    if !body.trim().is_empty() {
+        writeln!(f, "  body:")?;
+        for line in body.trim_end().lines() {
+            if line.is_empty() {
+                writeln!(f)?;
+            } else {
+                writeln!(f, "    {line}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for PackageReleasePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", self.package_name, self.version)?;
+        for registry in &self.registries {
+            match (registry.publish, registry.already_published) {
+                (true, _) => writeln!(f, "- publish to {}", registry.name)?,
+                (false, true) => writeln!(f, "- already published to {}", registry.name)?,
+                (false, false) => writeln!(f, "- don't publish to {}", registry.name)?,
+            }
+        }
+        let tag = &self.tag;
+        match (tag.create, tag.exists) {
+            (true, _) => writeln!(f, "- create and push git tag {}", tag.name)?,
+            (false, true) => writeln!(f, "- git tag {} already exists", tag.name)?,
+            (false, false) => writeln!(f, "- don't create git tag {}", tag.name)?,
+        }
+        if let Some(release) = &self.git_release {
+            if release.create {
+                let mut kind = vec![];
+                if release.draft {
+                    kind.push("draft");
+                }
+                if release.pre_release {
+                    kind.push("pre-release");
+                }
+                let kind = if kind.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", kind.join(", "))
+                };
+                writeln!(f, "- create git release \"{}\"{kind}", release.name)?;
+                for asset in &release.assets {
+                    writeln!(f, "- upload git release asset {asset}")?;
+                }
+                write_body(f, &release.body)?;
+            } else {
+                writeln!(f, "- don't create git release \"{}\"", release.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_is_displayed() {
+        let plan = ReleasePlan {
+            packages: vec![
+                PackageReleasePlan {
+                    package_name: "a".to_string(),
+                    version: Version::new(0, 2, 0),
+                    registries: vec![RegistryPlan {
+                        name: "crates.io".to_string(),
+                        already_published: false,
+                        publish: true,
+                    }],
+                    tag: TagPlan {
+                        name: "a-v0.2.0".to_string(),
+                        exists: false,
+                        create: true,
+                    },
+                    git_release: Some(GitReleasePlan {
+                        name: "a-v0.2.0".to_string(),
+                        body: "### Fixed\n\n- fix bug".to_string(),
+                        draft: true,
+                        pre_release: false,
+                        assets: vec!["a.tar.gz".to_string()],
+                        create: true,
+                    }),
+                },
+                PackageReleasePlan {
+                    package_name: "b".to_string(),
+                    version: Version::new(1, 0, 0),
+                    registries: vec![RegistryPlan {
+                        name: "crates.io".to_string(),
+                        already_published: true,
+                        publish: false,
+                    }],
+                    tag: TagPlan {
+                        name: "b-v1.0.0".to_string(),
+                        exists: true,
+                        create: false,
+                    },
+                    git_release: None,
+                },
+            ],
+            combined_git_release: None,
+        };
+        expect_test::expect![[r#"
+            a 0.2.0
+            - publish to crates.io
+            - create and push git tag a-v0.2.0
+            - create git release "a-v0.2.0" (draft)
+            - upload git release asset a.tar.gz
+              body:
+                ### Fixed
+
+                - fix bug
+
+            b 1.0.0
+            - already published to crates.io
+            - git tag b-v1.0.0 already exists
+        "#]]
+        .assert_eq(&plan.to_string());
+    }
+
+    #[test]
+    fn combined_git_release_is_displayed() {
+        let plan = ReleasePlan {
+            packages: vec![PackageReleasePlan {
+                package_name: "a".to_string(),
+                version: Version::new(1, 0, 0),
+                registries: vec![],
+                tag: TagPlan {
+                    name: "a-v1.0.0".to_string(),
+                    exists: false,
+                    create: true,
+                },
+                git_release: None,
+            }],
+            combined_git_release: Some(CombinedGitReleasePlan {
+                tag: TagPlan {
+                    name: "v1.0.0".to_string(),
+                    exists: false,
+                    create: true,
+                },
+                name: "v1.0.0".to_string(),
+                body: "## `a` - 1.0.0\n\n- fix bug".to_string(),
+                packages: vec!["a".to_string()],
+            }),
+        };
+        expect_test::expect![[r#"
+            a 1.0.0
+            - create and push git tag a-v1.0.0
+
+            combined git release
+            - create and push git tag v1.0.0
+            - create git release "v1.0.0" of a
+              body:
+                ## `a` - 1.0.0
+
+                - fix bug
+        "#]]
+        .assert_eq(&plan.to_string());
+    }
+}
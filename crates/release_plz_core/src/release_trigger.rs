@@ -0,0 +1,248 @@
+use anyhow::Context;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
+    Package,
+};
+use cargo_utils::CARGO_TOML;
+use git_cmd::Repo;
+use regex::Regex;
+use tracing::{debug, info};
+
+/// How `release-plz release` decides whether to release when
+/// it doesn't release on every commit.
+#[derive(Debug, Clone, Default)]
+pub enum ReleaseTrigger {
+    /// Release if the current commit comes from a release PR.
+    /// The release PR is found with the API of the git forge.
+    #[default]
+    ReleasePr,
+    /// Release if the current commit changes the version of a package,
+    /// or if its message matches `commit_message`.
+    /// Only git is used.
+    VersionBump { commit_message: Option<Regex> },
+}
+
+/// Return true if the current commit is a release commit, according to
+/// [`ReleaseTrigger::VersionBump`].
+pub(crate) fn is_release_commit(
+    repo: &Repo,
+    packages: &[&Package],
+    commit_message: Option<&Regex>,
+) -> anyhow::Result<bool> {
+    if let Some(commit_message) = commit_message {
+        let message = repo.current_commit_message()?;
+        if commit_message.is_match(&message) {
+            info!("releasing: commit message matches `{commit_message}`");
+            return Ok(true);
+        }
+    }
+    if !repo.commit_exists("HEAD^") {
+        anyhow::ensure!(
+            !repo.is_shallow()?,
+            "the parent of the current commit isn't available because the repository is a shallow clone: \
+            fetch more git history to detect version bumps (e.g. set `fetch-depth: 2` in `actions/checkout`)"
+        );
+        // The current commit is the first commit of the repository.
+        info!("releasing: first commit of the repository");
+        return Ok(true);
+    }
+    for package in packages {
+        let previous_version = previous_version(repo, package)
+            .with_context(|| format!("can't determine previous version of {}", package.name))?;
+        match previous_version {
+            Some(previous) if previous == package.version => {}
+            Some(previous) => {
+                info!(
+                    "releasing: commit bumps {} from {previous} to {}",
+                    package.name, package.version
+                );
+                return Ok(true);
+            }
+            None => {
+                info!(
+                    "releasing: commit adds {} {}",
+                    package.name, package.version
+                );
+                return Ok(true);
+            }
+        }
+    }
+    debug!("no package version changed in the current commit");
+    Ok(false)
+}
+
+/// Version of the package in the parent of the current commit.
+/// Return [`Option::None`] if the package didn't exist.
+fn previous_version(repo: &Repo, package: &Package) -> anyhow::Result<Option<Version>> {
+    let manifest_path = relative_path(repo, &package.manifest_path)?;
+    let Some(manifest) = repo.file_at_commit("HEAD^", &manifest_path)? else {
+        return Ok(None);
+    };
+    let manifest: toml::Table =
+        toml::from_str(&manifest).with_context(|| format!("can't parse {manifest_path}"))?;
+    let version = manifest.get("package").and_then(|p| p.get("version"));
+    let version = match version {
+        None => return Ok(None),
+        Some(toml::Value::String(version)) => version.clone(),
+        // `version.workspace = true`
+        Some(_) => match previous_workspace_version(repo, &manifest_path)? {
+            Some(version) => version,
+            None => return Ok(None),
+        },
+    };
+    let version = Version::parse(&version).with_context(|| format!("invalid version {version}"))?;
+    Ok(Some(version))
+}
+
+/// `workspace.package.version` of the workspace manifest in the parent of the current commit.
+/// The workspace manifest is searched in the ancestor directories of `manifest_path`.
+fn previous_workspace_version(
+    repo: &Repo,
+    manifest_path: &Utf8Path,
+) -> anyhow::Result<Option<String>> {
+    let mut dir = manifest_path.parent();
+    while let Some(current_dir) = dir {
+        let candidate = current_dir.join(CARGO_TOML);
+        if let Some(manifest) = repo.file_at_commit("HEAD^", &candidate)? {
+            let manifest: toml::Table =
+                toml::from_str(&manifest).with_context(|| format!("can't parse {candidate}"))?;
+            if let Some(workspace) = manifest.get("workspace") {
+                let version = workspace
+                    .get("package")
+                    .and_then(|p| p.get("version"))
+                    .and_then(|v| v.as_str())
+                    .map(ToOwned::to_owned);
+                return Ok(version);
+            }
+        }
+        dir = current_dir.parent();
+    }
+    Ok(None)
+}
+
+fn relative_path(repo: &Repo, path: &Utf8Path) -> anyhow::Result<Utf8PathBuf> {
+    let relative = path
+        .strip_prefix(repo.directory())
+        .with_context(|| format!("{path} is not inside the repository {}", repo.directory()))?;
+    Ok(relative.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(manifest_path: Utf8PathBuf, version: &str) -> Package {
+        let mut package: Package = fake_package::FakePackage::new("my_crate").into();
+        package.version = Version::parse(version).unwrap();
+        package.manifest_path = manifest_path;
+        package
+    }
+
+    /// Create a repository with two commits, containing the given files.
+    fn repo_with_manifests(
+        manifests: &[(&str, &str)],
+        new_manifests: &[(&str, &str)],
+    ) -> (tempfile::TempDir, Repo) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = Repo::init(&temp_dir);
+        let root = repo.directory();
+        for (files, message) in [(manifests, "first"), (new_manifests, "second")] {
+            for (path, content) in files {
+                let path = root.join(path);
+                fs_err::create_dir_all(path.parent().unwrap()).unwrap();
+                fs_err::write(path, content).unwrap();
+            }
+            repo.add_all_and_commit(message).unwrap();
+        }
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn version_bump_is_detected() {
+        let (_temp_dir, repo) = repo_with_manifests(
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n",
+            )],
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.2.0\"\n",
+            )],
+        );
+        let package = package(repo.directory().join("Cargo.toml"), "0.2.0");
+        assert!(is_release_commit(&repo, &[&package], None).unwrap());
+    }
+
+    #[test]
+    fn unchanged_version_is_not_released() {
+        let (_temp_dir, repo) = repo_with_manifests(
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n",
+            )],
+            &[("src/lib.rs", "fn f() {}")],
+        );
+        let package = package(repo.directory().join("Cargo.toml"), "0.1.0");
+        assert!(!is_release_commit(&repo, &[&package], None).unwrap());
+    }
+
+    #[test]
+    fn workspace_version_bump_is_detected() {
+        let crate_manifest = "[package]\nname = \"my_crate\"\nversion.workspace = true\n";
+        let (_temp_dir, repo) = repo_with_manifests(
+            &[
+                (
+                    "Cargo.toml",
+                    "[workspace]\nmembers = [\"crates/*\"]\n[workspace.package]\nversion = \"1.0.0\"\n",
+                ),
+                ("crates/my_crate/Cargo.toml", crate_manifest),
+            ],
+            &[(
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"crates/*\"]\n[workspace.package]\nversion = \"1.1.0\"\n",
+            )],
+        );
+        let manifest_path = repo.directory().join("crates/my_crate/Cargo.toml");
+        let bumped = package(manifest_path.clone(), "1.1.0");
+        assert!(is_release_commit(&repo, &[&bumped], None).unwrap());
+        let unchanged = package(manifest_path, "1.0.0");
+        assert!(!is_release_commit(&repo, &[&unchanged], None).unwrap());
+    }
+
+    #[test]
+    fn shallow_clone_without_parent_commit_errors() {
+        let (temp_dir, _repo) = repo_with_manifests(
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n",
+            )],
+            &[("src/lib.rs", "fn f() {}")],
+        );
+        let clone_dir = tempfile::tempdir().unwrap();
+        let source = format!("file://{}", temp_dir.path().display());
+        git_cmd::git_in_dir(
+            Utf8Path::from_path(clone_dir.path()).unwrap(),
+            &["clone", "--depth", "1", &source, "."],
+        )
+        .unwrap();
+        let repo = Repo::new(Utf8Path::from_path(clone_dir.path()).unwrap()).unwrap();
+        let package = package(repo.directory().join("Cargo.toml"), "0.1.0");
+        let error = is_release_commit(&repo, &[&package], None).unwrap_err();
+        assert!(error.to_string().contains("shallow clone"), "{error:?}");
+    }
+
+    #[test]
+    fn release_commit_message_is_detected() {
+        let (_temp_dir, repo) = repo_with_manifests(
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n",
+            )],
+            &[("src/lib.rs", "fn f() {}")],
+        );
+        let package = package(repo.directory().join("Cargo.toml"), "0.1.0");
+        let message = Regex::new("^second$").unwrap();
+        assert!(is_release_commit(&repo, &[&package], Some(&message)).unwrap());
+    }
+}
@@ -51,6 +51,11 @@ impl RepoUrl {
         self.host.contains("github")
     }
 
+    /// Bitbucket Data Center is self-hosted, so its repositories aren't on `bitbucket.org`.
+    pub fn is_on_bitbucket_cloud(&self) -> bool {
+        self.host == "bitbucket.org"
+    }
+
     pub fn full_host(&self) -> String {
         format!("https://{}/{}/{}", self.host, self.owner, self.name)
     }
@@ -129,6 +134,14 @@ mod tests {
         assert_eq!(expected_url, release_link);
     }
 
+    #[test]
+    fn bitbucket_cloud_is_recognized() {
+        let repo = RepoUrl::new("git@bitbucket.org:owner/repo.git").unwrap();
+        assert!(repo.is_on_bitbucket_cloud());
+        let repo = RepoUrl::new("https://bitbucket.example.com/scm/owner/repo.git").unwrap();
+        assert!(!repo.is_on_bitbucket_cloud());
+    }
+
     #[test]
     fn gitlab_api_url() {
         let git_repo = RepoUrl::new("git@host.example.com:ab/cd/myproj.git").unwrap();
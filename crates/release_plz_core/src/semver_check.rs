@@ -3,6 +3,7 @@ use std::process::Command;
 use anyhow::Context;
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
 use cargo_utils::CARGO_TOML;
+use serde::Serialize;
 
 fn target_dir(path: &Utf8Path) -> Utf8PathBuf {
     path.join("target")
@@ -31,6 +32,25 @@ pub enum SemverCheck {
     Skipped,
 }
 
+/// Outcome of semver check, without the details of the incompatibilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SemverCheckOutcome {
+    Compatible,
+    Incompatible,
+    Skipped,
+}
+
+impl From<&SemverCheck> for SemverCheckOutcome {
+    fn from(value: &SemverCheck) -> Self {
+        match value {
+            SemverCheck::Compatible => Self::Compatible,
+            SemverCheck::Incompatible(_) => Self::Incompatible,
+            SemverCheck::Skipped => Self::Skipped,
+        }
+    }
+}
+
 impl SemverCheck {
     pub fn outcome_str(&self) -> &'static str {
         match self {
@@ -79,6 +99,31 @@ pub fn run_semver_check(
         fs_err::remove_dir_all(registry_target_dir)?;
     }
 
+    semver_check_outcome(output)
+}
+
+/// Check the package at `manifest_path` against the latest version published in crates.io.
+/// Since the version of the package is already bumped, the outcome is
+/// [`SemverCheck::Incompatible`] only if the new version doesn't respect semver.
+pub fn run_semver_check_against_registry(manifest_path: &Utf8Path) -> anyhow::Result<SemverCheck> {
+    let output = Command::new("cargo-semver-checks")
+        .args(["semver-checks", "check-release"])
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .output()
+        .with_context(|| format!("error while running cargo-semver-checks on {manifest_path:?}"))?;
+    if !output.status.success() {
+        // E.g. the package was never published, so there's no baseline.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::ensure!(
+            stderr.contains("semver requires new major version"),
+            "cargo-semver-checks failed: {stderr}"
+        );
+    }
+    semver_check_outcome(output)
+}
+
+fn semver_check_outcome(output: std::process::Output) -> anyhow::Result<SemverCheck> {
     if output.status.success() {
         Ok(SemverCheck::Compatible)
     } else {
@@ -1,3 +1,5 @@
+use anyhow::Context as _;
+
 use crate::Remote;
 
 pub const PACKAGE_VAR: &str = "package";
@@ -5,6 +7,7 @@ pub const VERSION_VAR: &str = "version";
 pub const CHANGELOG_VAR: &str = "changelog";
 pub const REMOTE_VAR: &str = "remote";
 pub const RELEASES_VAR: &str = "releases";
+pub const GROUP_VAR: &str = "group";
 
 pub fn tera_var(var_name: &str) -> String {
     format!("{{{{ {var_name} }}}}")
@@ -27,14 +30,43 @@ pub fn release_body_from_template(
     render_template(body_template, &context, "release_body")
 }
 
+pub fn tag_message_from_template(
+    package_name: &str,
+    version: &str,
+    changelog: &str,
+    message_template: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut context = tera_context(package_name, version);
+    context.insert(CHANGELOG_VAR, changelog);
+
+    // Use same tag message of cargo-release
+    let default_message_template = format!(
+        "chore: Release package {} version {}",
+        tera_var(PACKAGE_VAR),
+        tera_var(VERSION_VAR)
+    );
+    let message_template = message_template.unwrap_or(&default_message_template);
+
+    try_render_template(message_template, &context, "tag_message")
+}
+
 pub fn render_template(template: &str, context: &tera::Context, template_name: &str) -> String {
+    try_render_template(template, context, template_name).unwrap_or_else(|e| panic!("{e:?}"))
+}
+
+/// Like [`render_template`], but returns an error if the template is invalid.
+pub fn try_render_template(
+    template: &str,
+    context: &tera::Context,
+    template_name: &str,
+) -> anyhow::Result<String> {
     let mut tera = tera::Tera::default();
 
     tera.add_raw_template(template_name, template)
-        .expect("failed to add release_body raw template");
+        .with_context(|| format!("failed to parse the {template_name} template"))?;
 
     tera.render(template_name, context)
-        .unwrap_or_else(|e| panic!("failed to render {template_name}: {e}"))
+        .with_context(|| format!("failed to render the {template_name} template"))
 }
 
 pub fn tera_context(package_name: &str, version: &str) -> tera::Context {
@@ -58,4 +90,30 @@ mod tests {
         let body = release_body_from_template("my_package", "0.1.0", "my changes", &remote, None);
         assert_eq!(body, "my changes");
     }
+
+    #[test]
+    fn default_tag_message_is_rendered() {
+        let message = tag_message_from_template("my_package", "0.1.0", "my changes", None).unwrap();
+        assert_eq!(message, "chore: Release package my_package version 0.1.0");
+    }
+
+    #[test]
+    fn tag_message_template_is_rendered() {
+        let message = tag_message_from_template(
+            "my_package",
+            "0.1.0",
+            "my changes",
+            Some("{{ package }} v{{ version }}\n\n{{ changelog }}"),
+        )
+        .unwrap();
+        assert_eq!(message, "my_package v0.1.0\n\nmy changes");
+    }
+
+    #[test]
+    fn invalid_tag_message_template_errors() {
+        let error =
+            tag_message_from_template("my_package", "0.1.0", "my changes", Some("{{ package"))
+                .unwrap_err();
+        assert!(error.to_string().contains("tag_message"), "{error:?}");
+    }
 }
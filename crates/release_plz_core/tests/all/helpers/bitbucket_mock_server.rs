@@ -0,0 +1,39 @@
+use url::Url;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+pub struct BitbucketMockServer {
+    server: MockServer,
+    owner: &'static str,
+    repo: &'static str,
+}
+
+impl BitbucketMockServer {
+    pub async fn start(owner: &'static str, repo: &'static str) -> Self {
+        Self {
+            server: MockServer::start().await,
+            owner,
+            repo,
+        }
+    }
+
+    pub fn base_url(&self) -> Url {
+        Url::parse(&self.server.uri()).unwrap()
+    }
+
+    /// Nobody tried to open a PR.
+    pub async fn expect_no_created_prs(&self) {
+        Mock::given(method("POST"))
+            .and(path(self.pulls_path()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&self.server)
+            .await;
+    }
+
+    fn pulls_path(&self) -> String {
+        format!("/repositories/{}/{}/pullrequests", self.owner, self.repo)
+    }
+}
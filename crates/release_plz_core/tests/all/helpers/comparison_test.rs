@@ -1,11 +1,13 @@
-use crate::helpers::gitea_mock_server::GiteaMockServer;
+use crate::helpers::{
+    bitbucket_mock_server::BitbucketMockServer, gitea_mock_server::GiteaMockServer,
+};
 use anyhow::Context;
 use cargo_metadata::camino::Utf8PathBuf;
 use cargo_utils::{get_manifest_metadata, CARGO_TOML};
 use chrono::NaiveDate;
 use release_plz_core::{
-    are_packages_equal, copy_to_temp_dir, fs_utils::Utf8TempDir, ChangelogRequest, GitBackend,
-    GitHub, Gitea, ReleasePrRequest, RepoUrl, UpdateRequest, CHANGELOG_FILENAME,
+    are_packages_equal, copy_to_temp_dir, fs_utils::Utf8TempDir, Bitbucket, ChangelogRequest,
+    GitBackend, GitHub, Gitea, ReleasePrRequest, RepoUrl, UpdateRequest, CHANGELOG_FILENAME,
 };
 use secrecy::SecretString;
 use url::Url;
@@ -18,6 +20,7 @@ pub struct ComparisonTest {
     registry_project: Utf8TempDir,
     github_mock_server: GitHubMockServer,
     gitea_mock_server: GiteaMockServer,
+    bitbucket_mock_server: BitbucketMockServer,
 }
 
 const PROJECT_NAME: &str = "myproject";
@@ -37,6 +40,7 @@ impl ComparisonTest {
             registry_project,
             github_mock_server: GitHubMockServer::start(OWNER, REPO).await,
             gitea_mock_server: GiteaMockServer::start(OWNER, REPO).await,
+            bitbucket_mock_server: BitbucketMockServer::start(OWNER, REPO).await,
         };
         fs_err::copy(
             comparison.registry_project().join(CARGO_TOML),
@@ -100,6 +104,26 @@ impl ComparisonTest {
         Ok(())
     }
 
+    fn bitbucket_release_pr_request(&self, base_url: Url) -> ReleasePrRequest {
+        let bitbucket = GitBackend::Bitbucket(
+            Bitbucket::new(
+                OWNER.to_string(),
+                REPO.to_string(),
+                SecretString::from("token".to_string()),
+            )
+            .with_base_url(base_url),
+        );
+        let update_request = self.update_request().with_git_client(bitbucket);
+        ReleasePrRequest::new(update_request)
+    }
+
+    pub async fn bitbucket_open_release_pr(&self) -> anyhow::Result<()> {
+        let base_url = self.bitbucket_mock_server.base_url();
+        let release_pr_request = self.bitbucket_release_pr_request(base_url);
+        release_plz_core::release_pr(&release_pr_request).await?;
+        Ok(())
+    }
+
     pub fn local_project(&self) -> Utf8PathBuf {
         self.local_project.path().join(PROJECT_NAME)
     }
@@ -145,4 +169,10 @@ impl ComparisonTest {
     pub fn gitea_mock_server(&self) -> &GiteaMockServer {
         &self.gitea_mock_server
     }
+
+    /// Get a reference to the comparison test's bitbucket mock server.
+    #[must_use]
+    pub fn bitbucket_mock_server(&self) -> &BitbucketMockServer {
+        &self.bitbucket_mock_server
+    }
 }
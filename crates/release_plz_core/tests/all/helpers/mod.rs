@@ -1,3 +1,4 @@
+pub mod bitbucket_mock_server;
 pub mod comparison_test;
 pub mod gitea_mock_server;
 pub mod github_mock_server;
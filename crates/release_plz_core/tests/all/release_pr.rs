@@ -19,3 +19,13 @@ async fn gitea_up_to_date_project_should_not_raise_pr() {
         .await;
     comparison_test.gitea_open_release_pr().await.unwrap();
 }
+
+#[tokio::test]
+async fn bitbucket_up_to_date_project_should_not_raise_pr() {
+    let comparison_test = ComparisonTest::new().await;
+    comparison_test
+        .bitbucket_mock_server()
+        .expect_no_created_prs()
+        .await;
+    comparison_test.bitbucket_open_release_pr().await.unwrap();
+}